@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use lsm_tree::{AbstractTree, BlockCache, Config};
+use rand::Rng;
 use std::sync::Arc;
 use tempfile::tempdir;
 
@@ -376,6 +377,42 @@ fn blob_tree_get(c: &mut Criterion) {
     });
 }
 
+fn leveled_compaction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("leveled compaction");
+    group.sample_size(10);
+
+    for min_overlapping_ratio in [false, true] {
+        group.bench_function(
+            format!("write 100K items, min_overlapping_ratio={min_overlapping_ratio}"),
+            |b| {
+                b.iter(|| {
+                    let folder = tempfile::tempdir().unwrap();
+
+                    let tree = Config::new(folder).open().unwrap();
+
+                    let compaction = Arc::new(
+                        lsm_tree::compaction::Leveled::default()
+                            .with_table_target_size(64_000)
+                            .with_min_overlapping_ratio(min_overlapping_ratio),
+                    );
+
+                    let mut rng = rand::rng();
+
+                    for seqno in 0..100_000 {
+                        let key = rng.random_range(0..50_000u32).to_be_bytes();
+                        tree.insert(key, nanoid::nanoid!(), seqno);
+
+                        if seqno % 2_000 == 0 {
+                            tree.flush_active_memtable(0).unwrap();
+                            tree.compact(compaction.clone(), 0).unwrap();
+                        }
+                    }
+                })
+            },
+        );
+    }
+}
+
 // TODO: benchmark point read disjoint vs non-disjoint level vs disjoint *tree*
 // TODO: benchmark .prefix().next() and .next_back(), disjoint and non-disjoint
 
@@ -385,6 +422,7 @@ criterion_group!(
     disjoint_tree_minmax,
     disk_point_read,
     full_scan,
+    leveled_compaction,
     scan_vs_query,
     scan_vs_prefix,
     tree_get_pairs,
@@ -123,6 +123,8 @@ fn main() {
                 checksum: lsm_tree::Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: lsm_tree::ChecksumType::Xxh3,
                 block_type: lsm_tree::table::block::BlockType::Data,
             },
         });
@@ -53,6 +53,8 @@ fn main() {
                         checksum: lsm_tree::checksum::Checksum::from_raw(0),
                         data_length: 0,
                         uncompressed_length: 0,
+                        is_uncompressed: false,
+                        checksum_type: lsm_tree::ChecksumType::Xxh3,
                         block_type: lsm_tree::table::block::BlockType::Index,
                     },
                 });
@@ -4,8 +4,8 @@
 
 use crate::{
     iter_guard::IterGuardImpl, merge_operator::MergeOperator, table::Table, version::Version,
-    vlog::BlobFile, AnyTree, BlobTree, Config, Guard, InternalValue, KvPair, Memtable, SeqNo,
-    TableId, Tree, UserKey, UserValue,
+    vlog::BlobFile, AnyTree, BlobTree, Config, Guard, InternalValue, KvPair, Memtable,
+    RangeTombstone, SeqNo, TableId, Tree, UserKey, UserValue, VerificationReport, WriteBatch,
 };
 use std::{
     ops::RangeBounds,
@@ -73,7 +73,9 @@ pub trait AbstractTree {
         _lock: &MutexGuard<'_, ()>,
         seqno_threshold: SeqNo,
     ) -> crate::Result<Option<u64>> {
-        use crate::{compaction::stream::CompactionStream, merge::Merger};
+        use crate::{
+            compaction::stream::CompactionStream, merge::Merger, range_tombstone::RangeTombstoneSet,
+        };
 
         let version_history = self.get_version_history_lock();
         let latest = version_history.latest_version();
@@ -97,11 +99,22 @@ pub trait AbstractTree {
                 .map(|mt| mt.iter().map(Ok))
                 .collect::<Vec<_>>(),
         );
-        let stream = CompactionStream::new(merger, seqno_threshold);
+        let range_tombstones = RangeTombstoneSet::new(
+            latest
+                .sealed_memtables
+                .iter()
+                .flat_map(|mt| mt.range_tombstones()),
+        );
+
+        let stream = CompactionStream::new(merger, seqno_threshold)
+            .merge_operator(self.get_merge_operator())
+            .range_tombstones(range_tombstones.clone());
 
         drop(version_history);
 
-        if let Some((tables, blob_files)) = self.flush_to_tables(stream)? {
+        if let Some((tables, blob_files)) =
+            self.flush_to_tables(stream, range_tombstones.fragments().to_vec())?
+        {
             self.register_tables(
                 &tables,
                 blob_files.as_deref(),
@@ -179,6 +192,25 @@ pub trait AbstractTree {
     /// Will return `Err` if an IO error occurs.
     fn major_compact(&self, target_size: u64, seqno_threshold: SeqNo) -> crate::Result<()>;
 
+    /// Compacts all tables that overlap a given range, blocking the caller until it's done.
+    ///
+    /// The overlapping tables are merged level by level, down to the last level,
+    /// so tombstones in the range can be evicted.
+    ///
+    /// Accepts any `RangeBounds`, including unbounded or exclusive endpoints.
+    /// If the normalized lower bound is greater than the upper bound, the
+    /// method returns without performing any work.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    fn compact_range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+        target_size: u64,
+        seqno_threshold: SeqNo,
+    ) -> crate::Result<()>;
+
     /// Returns the disk space used by stale blobs.
     fn stale_blob_bytes(&self) -> u64 {
         0
@@ -195,6 +227,11 @@ pub trait AbstractTree {
     /// Gets the memory usage of all pinned index blocks in the tree.
     fn pinned_block_index_size(&self) -> usize;
 
+    /// Gets the memory usage of all compression dictionaries in the tree.
+    ///
+    /// Compression dictionaries are always pinned.
+    fn pinned_compression_dictionary_size(&self) -> usize;
+
     /// Gets the length of the version free list.
     fn version_free_list_len(&self) -> usize;
 
@@ -207,6 +244,8 @@ pub trait AbstractTree {
 
     /// Synchronously flushes a memtable to a table.
     ///
+    /// The range tombstones are written into the resulting tables.
+    ///
     /// This method will not make the table immediately available,
     /// use [`AbstractTree::register_tables`] for that.
     ///
@@ -217,6 +256,7 @@ pub trait AbstractTree {
     fn flush_to_tables(
         &self,
         stream: impl Iterator<Item = crate::Result<InternalValue>>,
+        range_tombstones: Vec<RangeTombstone>,
     ) -> crate::Result<Option<FlushToTablesResult>>;
 
     /// Atomically registers flushed tables into the tree, removing their associated sealed memtables.
@@ -272,6 +312,16 @@ pub trait AbstractTree {
     /// Seals the active memtable.
     fn rotate_memtable(&self) -> Option<Arc<Memtable>>;
 
+    /// Syncs the write-ahead journal to disk, see [`Config::with_journal`](crate::Config::with_journal).
+    ///
+    /// Does nothing if the journal is disabled.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if a previous write to the journal failed,
+    /// in which case writes since then may not survive a crash.
+    fn sync_journal(&self) -> crate::Result<()>;
+
     /// Returns the number of tables currently in the tree.
     fn table_count(&self) -> usize;
 
@@ -280,6 +330,15 @@ pub trait AbstractTree {
     /// Returns `None` if the level does not exist (if idx >= 7).
     fn level_table_count(&self, idx: usize) -> Option<usize>;
 
+    /// Returns the compression ratio (uncompressed size / compressed size)
+    /// of the data blocks in `levels[idx]`.
+    ///
+    /// Tables that were written before the compressed size was tracked are not included.
+    ///
+    /// Returns `None` if the level does not exist (if idx >= 7),
+    /// or if it contains no tables with a tracked compressed size.
+    fn level_compression_ratio(&self, idx: usize) -> Option<f32>;
+
     /// Returns the number of disjoint runs in L0.
     ///
     /// Can be used to determine whether to write stall.
@@ -294,6 +353,22 @@ pub trait AbstractTree {
     /// Returns the disk space usage.
     fn disk_space(&self) -> u64;
 
+    /// Verifies the integrity of all tables and blob files in the current version.
+    ///
+    /// Every file is read in full, checking its full file checksum, and the checksums
+    /// of every block and blob in it. Also checks that every blob file that is referenced
+    /// by a table exists.
+    ///
+    /// Verification does not stop at the first corruption, so I/O errors
+    /// are reported as corruptions of the affected file as well.
+    ///
+    /// ###### Caution
+    ///
+    /// This is a very expensive operation, as it reads the entire tree from disk.
+    fn verify(&self) -> VerificationReport {
+        crate::verify::verify_version(&self.current_version())
+    }
+
     /// Returns the highest sequence number of the active memtable.
     fn get_highest_memtable_seqno(&self) -> Option<SeqNo>;
 
@@ -484,6 +559,41 @@ pub trait AbstractTree {
     /// Will return `Err` if an IO error occurs.
     fn get<K: AsRef<[u8]>>(&self, key: K, seqno: SeqNo) -> crate::Result<Option<UserValue>>;
 
+    /// Retrieves multiple items from the tree.
+    ///
+    /// The keys are sorted and looked up together, so lookups that land in
+    /// the same table share its filter and data block loads.
+    ///
+    /// Returns the values in the order of the given keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let folder = tempfile::tempdir()?;
+    /// use lsm_tree::{AbstractTree, Config, Tree};
+    ///
+    /// let tree = Config::new(folder, Default::default(), Default::default()).open()?;
+    /// tree.insert("a", "my_value", 0);
+    /// tree.insert("c", "my_value2", 1);
+    ///
+    /// let items = tree.multi_get(["c", "b", "a"], 2)?;
+    /// assert_eq!(
+    ///     vec![Some("my_value2".as_bytes().into()), None, Some("my_value".as_bytes().into())],
+    ///     items,
+    /// );
+    /// #
+    /// # Ok::<(), lsm_tree::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    fn multi_get<K: AsRef<[u8]>>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        seqno: SeqNo,
+    ) -> crate::Result<Vec<Option<UserValue>>>;
+
     /// Returns `true` if the tree contains the specified key.
     ///
     /// # Examples
@@ -514,6 +624,9 @@ pub trait AbstractTree {
     ///
     /// Returns the added item's size and new size of the memtable.
     ///
+    /// If the tree has a journal that cannot be written to, the write is still applied;
+    /// use [`AbstractTree::try_insert`] to reject it instead.
+    ///
     /// # Examples
     ///
     /// ```
@@ -536,10 +649,28 @@ pub trait AbstractTree {
         seqno: SeqNo,
     ) -> (u64, u64);
 
+    /// Inserts a key-value pair into the tree, see [`AbstractTree::insert`].
+    ///
+    /// Returns the added item's size and new size of the memtable.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the write could not be appended to the journal,
+    /// in which case it is not applied.
+    fn try_insert<K: Into<UserKey>, V: Into<UserValue>>(
+        &self,
+        key: K,
+        value: V,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)>;
+
     /// Removes an item from the tree.
     ///
     /// Returns the added item's size and new size of the memtable.
     ///
+    /// If the tree has a journal that cannot be written to, the write is still applied;
+    /// use [`AbstractTree::try_remove`] to reject it instead.
+    ///
     /// # Examples
     ///
     /// ```
@@ -565,6 +696,101 @@ pub trait AbstractTree {
     /// Will return `Err` if an IO error occurs.
     fn remove<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> (u64, u64);
 
+    /// Removes an item from the tree, see [`AbstractTree::remove`].
+    ///
+    /// Returns the added item's size and new size of the memtable.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the write could not be appended to the journal,
+    /// in which case it is not applied.
+    fn try_remove<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> crate::Result<(u64, u64)>;
+
+    /// Applies a batch of writes atomically, using a single sequence number.
+    ///
+    /// All writes are inserted into the same memtable, so a memtable rotation cannot split the batch.
+    /// Readers do not observe the batch until `seqno` is made visible to them.
+    ///
+    /// Returns the added batch's size and new size of the memtable.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the batch could not be written to the journal,
+    /// or the merge operands of a key could not be combined,
+    /// in which case it is not applied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let folder = tempfile::tempdir()?;
+    /// use lsm_tree::{AbstractTree, Config, SequenceNumberCounter, WriteBatch};
+    ///
+    /// let seqno = SequenceNumberCounter::default();
+    /// let visible_seqno = SequenceNumberCounter::default();
+    /// let tree = Config::new(folder, seqno.clone(), visible_seqno.clone()).open()?;
+    ///
+    /// let mut batch = WriteBatch::new();
+    /// batch.insert("a", "abc");
+    /// batch.remove("b");
+    ///
+    /// let batch_seqno = seqno.next();
+    /// tree.apply_batch(batch, batch_seqno)?;
+    /// visible_seqno.fetch_max(batch_seqno + 1);
+    ///
+    /// assert!(tree.contains_key("a", visible_seqno.get())?);
+    /// #
+    /// # Ok::<(), lsm_tree::Error>(())
+    /// ```
+    fn apply_batch(&self, batch: WriteBatch, seqno: SeqNo) -> crate::Result<(u64, u64)>;
+
+    /// Removes all items in the key range `[start, end)` from the tree.
+    ///
+    /// Unlike [`AbstractTree::drop_range`], this writes a range tombstone, so it
+    /// respects snapshots and also deletes items that are still in memtables.
+    /// The deleted items are physically removed during compaction.
+    ///
+    /// Returns the added tombstone's size and new size of the memtable.
+    ///
+    /// If the tree has a journal that cannot be written to, the write is still applied;
+    /// use [`AbstractTree::try_remove_range`] to reject it instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let folder = tempfile::tempdir()?;
+    /// # use lsm_tree::{AbstractTree, Config, Tree};
+    /// #
+    /// # let tree = Config::new(folder, Default::default(), Default::default()).open()?;
+    /// tree.insert("a", "abc", 0);
+    /// tree.insert("b", "abc", 1);
+    /// tree.insert("c", "abc", 2);
+    ///
+    /// tree.remove_range("a".."c", 3);
+    ///
+    /// assert_eq!(None, tree.get("a", 4)?);
+    /// assert_eq!(None, tree.get("b", 4)?);
+    /// assert!(tree.get("c", 4)?.is_some());
+    /// #
+    /// # Ok::<(), lsm_tree::Error>(())
+    /// ```
+    fn remove_range<K: Into<UserKey>>(&self, range: std::ops::Range<K>, seqno: SeqNo)
+        -> (u64, u64);
+
+    /// Removes all items in the key range `[start, end)` from the tree,
+    /// see [`AbstractTree::remove_range`].
+    ///
+    /// Returns the added tombstone's size and new size of the memtable.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the write could not be appended to the journal,
+    /// in which case it is not applied.
+    fn try_remove_range<K: Into<UserKey>>(
+        &self,
+        range: std::ops::Range<K>,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)>;
+
     /// Removes an item from the tree.
     ///
     /// The tombstone marker of this delete operation will vanish when it
@@ -574,6 +800,9 @@ pub trait AbstractTree {
     ///
     /// Returns the added item's size and new size of the memtable.
     ///
+    /// If the tree has a journal that cannot be written to, the write is still applied;
+    /// use [`AbstractTree::try_remove_weak`] to reject it instead.
+    ///
     /// # Examples
     ///
     /// ```
@@ -600,6 +829,17 @@ pub trait AbstractTree {
     #[doc(hidden)]
     fn remove_weak<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> (u64, u64);
 
+    /// Removes an item from the tree, see [`AbstractTree::remove_weak`].
+    ///
+    /// Returns the added item's size and new size of the memtable.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the write could not be appended to the journal,
+    /// in which case it is not applied.
+    #[doc(hidden)]
+    fn try_remove_weak<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> crate::Result<(u64, u64)>;
+
     /// Adds a merge operand for a key.
     ///
     /// Merge operands are combined with existing values using the configured
@@ -607,6 +847,9 @@ pub trait AbstractTree {
     ///
     /// Returns the added item's size and new size of the memtable.
     ///
+    /// If the tree has a journal that cannot be written to, the write is still applied;
+    /// use [`AbstractTree::try_merge`] to reject it instead.
+    ///
     /// # Panics
     ///
     /// Panics if no merge operator is configured.
@@ -618,10 +861,30 @@ pub trait AbstractTree {
         seqno: SeqNo,
     ) -> (u64, u64);
 
+    /// Adds a merge operand for a key, see [`AbstractTree::merge`].
+    ///
+    /// Returns the added item's size and new size of the memtable.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the write could not be appended to the journal,
+    /// in which case it is not applied.
+    #[doc(hidden)]
+    fn try_merge<K: Into<UserKey>, V: Into<UserValue>>(
+        &self,
+        key: K,
+        operand: V,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)>;
+
     /// Sets or updates the merge operator for this tree.
     ///
     /// This allows changing the merge operator after the tree has been created/recovered,
     /// which is necessary for recovering keyspaces since merge operators cannot be persisted.
     #[doc(hidden)]
     fn set_merge_operator(&self, operator: Option<Arc<dyn MergeOperator>>);
+
+    /// Returns the merge operator of this tree, if any.
+    #[doc(hidden)]
+    fn get_merge_operator(&self) -> Option<Arc<dyn MergeOperator>>;
 }
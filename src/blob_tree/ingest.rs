@@ -5,7 +5,7 @@
 use crate::{
     blob_tree::handle::BlobIndirection,
     file::BLOBS_FOLDER,
-    table::Table,
+    table::{Pinning, Table},
     tree::ingest::Ingestion as TableIngestion,
     vlog::{BlobFileWriter, ValueHandle},
     SeqNo, UserKey, UserValue,
@@ -52,7 +52,8 @@ impl<'a> BlobIngestion<'a> {
             tree.index.config.path.join(BLOBS_FOLDER),
         )?
         .use_target_size(blob_file_size)
-        .use_compression(kv.compression);
+        .use_compression(kv.compression)
+        .use_checksum_type(tree.index.config.checksum_type);
 
         let separation_threshold = kv.separation_threshold;
 
@@ -236,8 +237,7 @@ impl<'a> BlobIngestion<'a> {
                     index.id,
                     index.config.cache.clone(),
                     index.config.descriptor_table.clone(),
-                    false,
-                    false,
+                    Pinning::default(),
                     #[cfg(feature = "metrics")]
                     index.metrics.clone(),
                 )
@@ -255,6 +255,7 @@ impl<'a> BlobIngestion<'a> {
         // already assigned to the recovered tables.
         version_lock.upgrade_version_with_seqno(
             &index.config.path,
+            index.config.checksum_type,
             |current| {
                 let mut copy = current.clone();
                 copy.version =
@@ -14,7 +14,7 @@ use crate::{
     iter_guard::{IterGuard, IterGuardImpl},
     merge_operator::MergeOperator,
     r#abstract::{AbstractTree, RangeItem},
-    table::Table,
+    table::{Pinning, Table},
     tree::inner::MemtableId,
     value::InternalValue,
     version::Version,
@@ -137,7 +137,7 @@ pub struct BlobTree {
     #[doc(hidden)]
     pub index: crate::Tree,
 
-    blobs_folder: Arc<PathBuf>,
+    pub(crate) blobs_folder: Arc<PathBuf>,
 }
 
 impl BlobTree {
@@ -212,23 +212,25 @@ impl AbstractTree for BlobTree {
         seqno: SeqNo,
         index: Option<(Arc<Memtable>, SeqNo)>,
     ) -> Box<dyn DoubleEndedIterator<Item = IterGuardImpl> + Send + 'static> {
-        use crate::range::prefix_to_range;
-
         let super_version = self.index.get_version_for_snapshot(seqno);
         let tree = self.clone();
 
-        let range = prefix_to_range(prefix.as_ref());
-
         Box::new(
-            crate::Tree::create_internal_range(super_version.clone(), &range, seqno, index).map(
-                move |kv| {
-                    IterGuardImpl::Blob(Guard {
-                        tree: tree.clone(),
-                        version: super_version.version.clone(),
-                        kv,
-                    })
-                },
-            ),
+            crate::Tree::create_internal_prefix(
+                super_version.clone(),
+                prefix.as_ref(),
+                seqno,
+                index,
+                self.index.get_merge_operator(),
+                self.index.config.prefix_extractor.as_deref(),
+            )
+            .map(move |kv| {
+                IterGuardImpl::Blob(Guard {
+                    tree: tree.clone(),
+                    version: super_version.version.clone(),
+                    kv,
+                })
+            }),
         )
     }
 
@@ -242,15 +244,20 @@ impl AbstractTree for BlobTree {
         let tree = self.clone();
 
         Box::new(
-            crate::Tree::create_internal_range(super_version.clone(), &range, seqno, index).map(
-                move |kv| {
-                    IterGuardImpl::Blob(Guard {
-                        tree: tree.clone(),
-                        version: super_version.version.clone(),
-                        kv,
-                    })
-                },
-            ),
+            crate::Tree::create_internal_range(
+                super_version.clone(),
+                &range,
+                seqno,
+                index,
+                self.index.get_merge_operator(),
+            )
+            .map(move |kv| {
+                IterGuardImpl::Blob(Guard {
+                    tree: tree.clone(),
+                    version: super_version.version.clone(),
+                    kv,
+                })
+            }),
         )
     }
 
@@ -278,6 +285,16 @@ impl AbstractTree for BlobTree {
         self.index.major_compact(target_size, seqno_threshold)
     }
 
+    fn compact_range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+        target_size: u64,
+        seqno_threshold: SeqNo,
+    ) -> crate::Result<()> {
+        self.index
+            .compact_range(range, target_size, seqno_threshold)
+    }
+
     fn clear_active_memtable(&self) {
         self.index.clear_active_memtable();
     }
@@ -325,6 +342,10 @@ impl AbstractTree for BlobTree {
         self.index.pinned_block_index_size()
     }
 
+    fn pinned_compression_dictionary_size(&self) -> usize {
+        self.index.pinned_compression_dictionary_size()
+    }
+
     fn sealed_memtable_count(&self) -> usize {
         self.index.sealed_memtable_count()
     }
@@ -336,6 +357,7 @@ impl AbstractTree for BlobTree {
     fn flush_to_tables(
         &self,
         stream: impl Iterator<Item = crate::Result<InternalValue>>,
+        range_tombstones: Vec<crate::RangeTombstone>,
     ) -> crate::Result<Option<(Vec<Table>, Option<Vec<BlobFile>>)>> {
         use crate::{
             coding::Encode, file::BLOBS_FOLDER, file::TABLES_FOLDER,
@@ -354,6 +376,8 @@ impl AbstractTree for BlobTree {
             self.index.config.index_block_restart_interval_policy.get(0);
 
         let data_block_compression = self.index.config.data_block_compression_policy.get(0);
+        let data_block_compression_ratio =
+            self.index.config.data_block_compression_ratio_policy.get(0);
         let index_block_compression = self.index.config.index_block_compression_policy.get(0);
 
         let data_block_hash_ratio = self.index.config.data_block_hash_ratio_policy.get(0);
@@ -374,18 +398,21 @@ impl AbstractTree for BlobTree {
         .use_data_block_restart_interval(data_block_restart_interval)
         .use_index_block_restart_interval(index_block_restart_interval)
         .use_data_block_compression(data_block_compression)
+        .use_data_block_compression_ratio(data_block_compression_ratio)
+        .use_checksum_type(self.index.config.checksum_type)
         .use_index_block_compression(index_block_compression)
         .use_data_block_size(data_block_size)
         .use_data_block_hash_ratio(data_block_hash_ratio)
         .use_bloom_policy({
-            use crate::config::FilterPolicyEntry::{Bloom, None};
+            use crate::config::FilterPolicyEntry::{Bloom, None, PrefixBloom};
             use crate::table::filter::BloomConstructionPolicy;
 
             match self.index.config.filter_policy.get(0) {
-                Bloom(policy) => policy,
+                Bloom(policy) | PrefixBloom(policy) => policy,
                 None => BloomConstructionPolicy::BitsPerKey(0.0),
             }
-        });
+        })
+        .use_prefix_extractor(self.index.config.prefix_extractor_for_level(0));
 
         if index_partitioning {
             table_writer = table_writer.use_partitioned_index();
@@ -394,6 +421,15 @@ impl AbstractTree for BlobTree {
             table_writer = table_writer.use_partitioned_filter();
         }
 
+        #[cfg(feature = "zstd")]
+        {
+            table_writer = table_writer.use_compression_dictionary_size(
+                self.index.config.compression_dictionary_size_policy.get(0),
+            );
+        }
+
+        table_writer = table_writer.use_range_tombstones(range_tombstones);
+
         #[expect(
             clippy::expect_used,
             reason = "cannot create blob tree without defining kv separation options"
@@ -410,7 +446,8 @@ impl AbstractTree for BlobTree {
             self.index.config.path.join(BLOBS_FOLDER),
         )?
         .use_target_size(kv_opts.file_target_size)
-        .use_compression(kv_opts.compression);
+        .use_compression(kv_opts.compression)
+        .use_checksum_type(self.index.config.checksum_type);
 
         let separation_threshold = kv_opts.separation_threshold;
 
@@ -424,6 +461,13 @@ impl AbstractTree for BlobTree {
                 continue;
             }
 
+            // NOTE: Merge operands are never separated, because they
+            // need to stay readable for the merge operator
+            if item.key.value_type.is_merge() {
+                table_writer.write(item)?;
+                continue;
+            }
+
             let value = item.value;
 
             #[expect(clippy::cast_possible_truncation, reason = "values are u32 length max")]
@@ -462,8 +506,7 @@ impl AbstractTree for BlobTree {
 
         log::debug!("Flushed memtable(s) in {:?}", start.elapsed());
 
-        let pin_filter = self.index.config.filter_block_pinning_policy.get(0);
-        let pin_index = self.index.config.index_block_pinning_policy.get(0);
+        let pinning = Pinning::from_config(&self.index.config, 0);
 
         // Load tables
         let tables = result
@@ -476,8 +519,7 @@ impl AbstractTree for BlobTree {
                     self.index.id,
                     self.index.config.cache.clone(),
                     self.index.config.descriptor_table.clone(),
-                    pin_filter,
-                    pin_index,
+                    pinning,
                     #[cfg(feature = "metrics")]
                     self.index.metrics.clone(),
                 )
@@ -536,6 +578,10 @@ impl AbstractTree for BlobTree {
         self.index.rotate_memtable()
     }
 
+    fn sync_journal(&self) -> crate::Result<()> {
+        self.index.sync_journal()
+    }
+
     fn table_count(&self) -> usize {
         self.index.table_count()
     }
@@ -544,6 +590,10 @@ impl AbstractTree for BlobTree {
         self.index.level_table_count(idx)
     }
 
+    fn level_compression_ratio(&self, idx: usize) -> Option<f32> {
+        self.index.level_compression_ratio(idx)
+    }
+
     fn approximate_len(&self) -> usize {
         self.index.approximate_len()
     }
@@ -588,6 +638,15 @@ impl AbstractTree for BlobTree {
         self.index.insert(key, value.into(), seqno)
     }
 
+    fn try_insert<K: Into<UserKey>, V: Into<UserValue>>(
+        &self,
+        key: K,
+        value: V,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)> {
+        self.index.try_insert(key, value.into(), seqno)
+    }
+
     fn get<K: AsRef<[u8]>>(&self, key: K, seqno: SeqNo) -> crate::Result<Option<crate::UserValue>> {
         let key = key.as_ref();
 
@@ -604,6 +663,22 @@ impl AbstractTree for BlobTree {
             return Ok(None);
         };
 
+        let item = match self.index.get_merge_operator() {
+            Some(merge_op) if item.key.value_type.is_merge() => {
+                let Some(item) = crate::Tree::resolve_merge_operands(
+                    &super_version,
+                    key,
+                    item,
+                    merge_op.as_ref(),
+                )?
+                else {
+                    return Ok(None);
+                };
+                item
+            }
+            _ => item,
+        };
+
         let (_, v) = resolve_value_handle(
             self.id(),
             self.blobs_folder.as_path(),
@@ -616,14 +691,118 @@ impl AbstractTree for BlobTree {
         Ok(Some(v))
     }
 
+    fn multi_get<K: AsRef<[u8]>>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        seqno: SeqNo,
+    ) -> crate::Result<Vec<Option<UserValue>>> {
+        let keys = keys.into_iter().collect::<Vec<_>>();
+        let keys = keys.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let super_version = self
+            .index
+            .version_history
+            .read()
+            .expect("lock is poisoned")
+            .get_version_for_snapshot(seqno);
+
+        let entries =
+            crate::Tree::multi_get_internal_entries_from_version(&super_version, &keys, seqno)?;
+
+        let merge_operator = self.index.get_merge_operator();
+
+        let entries = entries
+            .into_iter()
+            .zip(&keys)
+            .map(|(entry, key)| match (entry, &merge_operator) {
+                (Some(item), Some(merge_op)) if item.key.value_type.is_merge() => {
+                    crate::Tree::resolve_merge_operands(
+                        &super_version,
+                        key,
+                        item,
+                        merge_op.as_ref(),
+                    )
+                }
+                (entry, _) => Ok(entry),
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let mut results = keys.iter().map(|_| None).collect::<Vec<_>>();
+
+        let mut items = entries
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, item)| item.map(|item| (idx, item)))
+            .map(|(idx, item)| {
+                let vhandle = if item.key.value_type.is_indirection() {
+                    let mut cursor = std::io::Cursor::new(&item.value[..]);
+                    Some(BlobIndirection::decode_from(&mut cursor)?.vhandle)
+                } else {
+                    None
+                };
+
+                Ok((idx, vhandle, item))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        // NOTE: Fetch blobs in file order, so blob file reads are mostly sequential
+        items.sort_by_key(|(_, vhandle, _)| vhandle.as_ref().map(|x| (x.blob_file_id, x.offset)));
+
+        for (idx, _, item) in items {
+            let (_, v) = resolve_value_handle(
+                self.id(),
+                self.blobs_folder.as_path(),
+                &self.index.config.cache,
+                &self.index.config.descriptor_table,
+                &super_version.version,
+                item,
+            )?;
+
+            if let Some(slot) = results.get_mut(idx) {
+                *slot = Some(v);
+            }
+        }
+
+        Ok(results)
+    }
+
     fn remove<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> (u64, u64) {
         self.index.remove(key, seqno)
     }
 
+    fn try_remove<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> crate::Result<(u64, u64)> {
+        self.index.try_remove(key, seqno)
+    }
+
+    fn remove_range<K: Into<UserKey>>(
+        &self,
+        range: std::ops::Range<K>,
+        seqno: SeqNo,
+    ) -> (u64, u64) {
+        self.index.remove_range(range, seqno)
+    }
+
+    fn try_remove_range<K: Into<UserKey>>(
+        &self,
+        range: std::ops::Range<K>,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)> {
+        self.index.try_remove_range(range, seqno)
+    }
+
+    fn apply_batch(&self, batch: crate::WriteBatch, seqno: SeqNo) -> crate::Result<(u64, u64)> {
+        self.index.apply_batch(batch, seqno)
+    }
+
     fn remove_weak<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> (u64, u64) {
         self.index.remove_weak(key, seqno)
     }
 
+    fn try_remove_weak<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> crate::Result<(u64, u64)> {
+        self.index.try_remove_weak(key, seqno)
+    }
+
     fn merge<K: Into<UserKey>, V: Into<UserValue>>(
         &self,
         key: K,
@@ -633,7 +812,20 @@ impl AbstractTree for BlobTree {
         self.index.merge(key, operand, seqno)
     }
 
+    fn try_merge<K: Into<UserKey>, V: Into<UserValue>>(
+        &self,
+        key: K,
+        operand: V,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)> {
+        self.index.try_merge(key, operand, seqno)
+    }
+
     fn set_merge_operator(&self, operator: Option<Arc<dyn MergeOperator>>) {
         self.index.set_merge_operator(operator);
     }
+
+    fn get_merge_operator(&self) -> Option<Arc<dyn MergeOperator>> {
+        self.index.get_merge_operator()
+    }
 }
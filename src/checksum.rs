@@ -2,15 +2,87 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Checksum algorithm that is used to protect data on disk
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
 pub enum ChecksumType {
+    /// 128-bit XXH3
+    #[default]
     Xxh3,
+
+    /// 32-bit CRC32C (Castagnoli), which is hardware-accelerated on most CPUs
+    Crc32c,
 }
 
 impl From<ChecksumType> for u8 {
     fn from(val: ChecksumType) -> Self {
         match val {
             ChecksumType::Xxh3 => 0,
+            ChecksumType::Crc32c => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for ChecksumType {
+    type Error = crate::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Xxh3),
+            1 => Ok(Self::Crc32c),
+            _ => Err(crate::Error::InvalidTag(("ChecksumType", value))),
+        }
+    }
+}
+
+impl std::fmt::Display for ChecksumType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Xxh3 => "xxh3",
+                Self::Crc32c => "crc32c",
+            }
+        )
+    }
+}
+
+impl ChecksumType {
+    /// Computes the checksum of the given bytes.
+    #[must_use]
+    pub fn checksum(self, bytes: &[u8]) -> Checksum {
+        match self {
+            Self::Xxh3 => Checksum::from_raw(crate::hash::hash128(bytes)),
+            Self::Crc32c => Checksum::from_raw(u128::from(crc32c::crc32c(bytes))),
+        }
+    }
+
+    pub(crate) fn hasher(self) -> Hasher {
+        match self {
+            Self::Xxh3 => Hasher::Xxh3(Box::default()),
+            Self::Crc32c => Hasher::Crc32c(0),
+        }
+    }
+}
+
+/// Streaming hasher of a [`ChecksumType`]
+pub(crate) enum Hasher {
+    Xxh3(Box<xxhash_rust::xxh3::Xxh3Default>),
+    Crc32c(u32),
+}
+
+impl Hasher {
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Xxh3(hasher) => hasher.update(bytes),
+            Self::Crc32c(crc) => *crc = crc32c::crc32c_append(*crc, bytes),
+        }
+    }
+
+    pub fn checksum(&self) -> Checksum {
+        match self {
+            Self::Xxh3(hasher) => Checksum::from_raw(hasher.digest128()),
+            Self::Crc32c(crc) => Checksum::from_raw(u128::from(*crc)),
         }
     }
 }
@@ -58,7 +130,7 @@ impl Checksum {
 
 pub struct ChecksummedWriter<W: std::io::Write> {
     inner: W,
-    hasher: xxhash_rust::xxh3::Xxh3Default,
+    hasher: Hasher,
 }
 
 impl<W: std::io::Write + std::io::Seek> std::io::Seek for ChecksummedWriter<W> {
@@ -68,15 +140,22 @@ impl<W: std::io::Write + std::io::Seek> std::io::Seek for ChecksummedWriter<W> {
 }
 
 impl<W: std::io::Write> ChecksummedWriter<W> {
-    pub fn new(writer: W) -> Self {
+    pub fn new(writer: W, checksum_type: ChecksumType) -> Self {
         Self {
             inner: writer,
-            hasher: xxhash_rust::xxh3::Xxh3Default::new(),
+            hasher: checksum_type.hasher(),
         }
     }
 
+    /// Changes the checksum algorithm.
+    ///
+    /// Needs to be called before anything is written.
+    pub fn set_checksum_type(&mut self, checksum_type: ChecksumType) {
+        self.hasher = checksum_type.hasher();
+    }
+
     pub fn checksum(&self) -> Checksum {
-        Checksum::from_raw(self.hasher.digest128())
+        self.hasher.checksum()
     }
 
     pub fn inner_mut(&mut self) -> &mut W {
@@ -94,3 +173,50 @@ impl<W: std::io::Write> std::io::Write for ChecksummedWriter<W> {
         self.inner.write(buf)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use test_log::test;
+
+    #[test]
+    fn checksum_type_serde_roundtrip() -> crate::Result<()> {
+        for checksum_type in [ChecksumType::Xxh3, ChecksumType::Crc32c] {
+            assert_eq!(
+                checksum_type,
+                ChecksumType::try_from(u8::from(checksum_type))?,
+            );
+        }
+
+        assert!(matches!(
+            ChecksumType::try_from(2),
+            Err(crate::Error::InvalidTag(("ChecksumType", 2))),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn checksum_crc32c() {
+        assert_eq!(
+            Checksum::from_raw(0xE306_9283),
+            ChecksumType::Crc32c.checksum(b"123456789"),
+        );
+    }
+
+    #[test]
+    fn checksummed_writer_matches_checksum() -> crate::Result<()> {
+        for checksum_type in [ChecksumType::Xxh3, ChecksumType::Crc32c] {
+            let mut writer = ChecksummedWriter::new(vec![], ChecksumType::Xxh3);
+            writer.set_checksum_type(checksum_type);
+
+            writer.write_all(b"1234")?;
+            writer.write_all(b"56789")?;
+
+            assert_eq!(checksum_type.checksum(b"123456789"), writer.checksum());
+        }
+
+        Ok(())
+    }
+}
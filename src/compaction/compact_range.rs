@@ -0,0 +1,130 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use super::{drop_range::OwnedBounds, Choice, CompactionStrategy, Input as CompactionInput};
+use crate::{
+    compaction::state::CompactionState,
+    config::Config,
+    version::{Level, Version},
+    HashSet, KeyRange, Table,
+};
+
+#[doc(hidden)]
+pub const NAME: &str = "CompactRangeCompaction";
+
+/// Compacts all tables of a single level that overlap a key range into the next level
+///
+/// The last level is rewritten in place instead.
+pub struct Strategy {
+    bounds: OwnedBounds,
+    level_idx: u8,
+    target_size: u64,
+}
+
+impl Strategy {
+    /// Configures a new `CompactRange` compaction strategy.
+    #[must_use]
+    pub fn new(bounds: OwnedBounds, level_idx: u8, target_size: u64) -> Self {
+        Self {
+            bounds,
+            level_idx,
+            target_size,
+        }
+    }
+}
+
+/// Returns the tables of a level that overlap the given bounds.
+pub fn overlapping_tables<'a>(
+    level: &'a Level,
+    bounds: &'a OwnedBounds,
+) -> impl Iterator<Item = &'a Table> + 'a {
+    level.iter().flat_map(|run| {
+        run.range_overlap_indexes(bounds)
+            .and_then(|(lo, hi)| run.get(lo..=hi))
+            .unwrap_or_default()
+    })
+}
+
+impl CompactionStrategy for Strategy {
+    fn get_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn choose(&self, version: &Version, cfg: &Config, state: &CompactionState) -> Choice {
+        let level_idx = usize::from(self.level_idx);
+        let last_level_idx = usize::from(cfg.level_count - 1);
+
+        let Some(level) = version.level(level_idx) else {
+            return Choice::DoNothing;
+        };
+
+        let mut tables = overlapping_tables(level, &self.bounds).collect::<Vec<_>>();
+
+        if tables.is_empty() {
+            return Choice::DoNothing;
+        }
+
+        // NOTE: If the level has multiple runs, older tables that overlap the
+        // picked tables need to be pulled in as well, otherwise they would
+        // shadow the newer data once it is moved to the next level
+        loop {
+            let key_range = KeyRange::aggregate(tables.iter().map(|t| &t.metadata.key_range));
+            let key_range = key_range.min()..=key_range.max();
+
+            let expanded = level
+                .iter()
+                .flat_map(|run| {
+                    run.range_overlap_indexes::<crate::Slice, _>(&key_range)
+                        .and_then(|(lo, hi)| run.get(lo..=hi))
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+
+            if expanded.len() == tables.len() {
+                break;
+            }
+
+            tables = expanded;
+        }
+
+        let mut table_ids: HashSet<_> = tables.iter().map(|t| t.id()).collect();
+
+        let dest_level = if level_idx == last_level_idx {
+            level_idx
+        } else {
+            level_idx + 1
+        };
+
+        if dest_level != level_idx {
+            let key_range = KeyRange::aggregate(tables.iter().map(|t| &t.metadata.key_range));
+
+            if let Some(next_level) = version.level(dest_level) {
+                table_ids.extend(
+                    next_level
+                        .iter()
+                        .flat_map(|run| run.get_overlapping(&key_range))
+                        .map(Table::id),
+                );
+            }
+        }
+
+        // NOTE: This should generally not occur because of the
+        // tree-level major compaction lock
+        // But just as a fail-safe...
+        if state.hidden_set().is_blocked(table_ids.iter().copied()) {
+            return Choice::DoNothing;
+        }
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "level index is bounded by level count (7, technically 255)"
+        )]
+        Choice::Merge(CompactionInput {
+            table_ids,
+            dest_level: dest_level as u8,
+            canonical_level: dest_level as u8,
+            target_size: self.target_size,
+        })
+    }
+}
@@ -248,6 +248,8 @@ mod tests {
 
     #[test]
     fn fifo_ttl() -> crate::Result<()> {
+        let _clock = crate::time::lock_clock_for_test();
+
         let dir = tempfile::tempdir()?;
         let tree = Config::new(
             dir.path(),
@@ -283,6 +285,8 @@ mod tests {
 
     #[test]
     fn fifo_ttl_then_limit_additional_drops_blob_unit() -> crate::Result<()> {
+        let _clock = crate::time::lock_clock_for_test();
+
         let dir = tempfile::tempdir()?;
         let tree = Config::new(
             dir.path(),
@@ -8,7 +8,7 @@ use crate::coding::{Decode, Encode};
 use crate::compaction::worker::Options;
 use crate::compaction::Input as CompactionPayload;
 use crate::file::TABLES_FOLDER;
-use crate::table::multi_writer::MultiWriter;
+use crate::table::{multi_writer::MultiWriter, Pinning};
 use crate::version::{SuperVersions, Version};
 use crate::vlog::blob_file::scanner::ScanEntry;
 use crate::vlog::{BlobFileId, BlobFileMergeScanner, BlobFileWriter};
@@ -56,6 +56,7 @@ pub(super) fn prepare_table_writer(
     let index_block_restart_interval = opts.config.index_block_restart_interval_policy.get(dst_lvl);
 
     let data_block_compression = opts.config.data_block_compression_policy.get(dst_lvl);
+    let data_block_compression_ratio = opts.config.data_block_compression_ratio_policy.get(dst_lvl);
     let index_block_compression = opts.config.index_block_compression_policy.get(dst_lvl);
 
     let data_block_hash_ratio = opts.config.data_block_hash_ratio_policy.get(dst_lvl);
@@ -79,6 +80,18 @@ pub(super) fn prepare_table_writer(
         payload.dest_level,
     )?;
 
+    if let Some(limit) = opts.config.max_grandparent_overlap_bytes {
+        if let Some(grandparent_level) = version.level(usize::from(payload.dest_level) + 1) {
+            let grandparents = grandparent_level
+                .iter()
+                .flat_map(|run| run.iter())
+                .map(|table| (table.metadata.key_range.max().clone(), table.file_size()))
+                .collect();
+
+            table_writer = table_writer.use_grandparent_overlap_limit(grandparents, limit);
+        }
+    }
+
     if index_partitioning {
         table_writer = table_writer.use_partitioned_index();
     }
@@ -86,6 +99,13 @@ pub(super) fn prepare_table_writer(
         table_writer = table_writer.use_partitioned_filter();
     }
 
+    #[cfg(feature = "zstd")]
+    {
+        table_writer = table_writer.use_compression_dictionary_size(
+            opts.config.compression_dictionary_size_policy.get(dst_lvl),
+        );
+    }
+
     #[expect(clippy::cast_possible_truncation, reason = "max key size = u16")]
     let last_level = (version.level_count() - 1) as u8;
     let is_last_level = payload.dest_level == last_level;
@@ -94,11 +114,13 @@ pub(super) fn prepare_table_writer(
         .use_data_block_restart_interval(data_block_restart_interval)
         .use_index_block_restart_interval(index_block_restart_interval)
         .use_data_block_compression(data_block_compression)
+        .use_data_block_compression_ratio(data_block_compression_ratio)
+        .use_checksum_type(opts.config.checksum_type)
         .use_data_block_size(data_block_size)
         .use_data_block_hash_ratio(data_block_hash_ratio)
         .use_index_block_compression(index_block_compression)
         .use_bloom_policy({
-            use crate::config::FilterPolicyEntry::{Bloom, None};
+            use crate::config::FilterPolicyEntry::{Bloom, None, PrefixBloom};
             use crate::table::filter::BloomConstructionPolicy;
 
             if is_last_level && opts.config.expect_point_read_hits {
@@ -109,11 +131,12 @@ pub(super) fn prepare_table_writer(
                     .filter_policy
                     .get(usize::from(payload.dest_level))
                 {
-                    Bloom(policy) => policy,
+                    Bloom(policy) | PrefixBloom(policy) => policy,
                     None => BloomConstructionPolicy::BitsPerKey(0.0),
                 }
             }
-        }))
+        })
+        .use_prefix_extractor(opts.config.prefix_extractor_for_level(dst_lvl)))
 }
 
 // TODO: find a better name
@@ -276,6 +299,7 @@ impl CompactionFlavour for RelocatingCompaction {
 
         super_version.upgrade_version(
             &opts.config.path,
+            opts.config.checksum_type,
             |current| {
                 let mut copy = current.clone();
 
@@ -321,6 +345,9 @@ pub struct StandardCompaction {
     start: Instant,
     table_writer: MultiWriter,
     tables_to_rewrite: Vec<Table>,
+
+    /// Writers of other subcompactions, whose output is registered together with ours
+    subcompaction_writers: Vec<MultiWriter>,
 }
 
 impl StandardCompaction {
@@ -329,17 +356,31 @@ impl StandardCompaction {
             start: Instant::now(),
             table_writer,
             tables_to_rewrite,
+            subcompaction_writers: Vec::new(),
         }
     }
 
+    /// Takes over the output of another subcompaction of the same compaction,
+    /// so all output is registered atomically.
+    pub fn add_subcompaction(&mut self, other: Self) {
+        self.subcompaction_writers.push(other.table_writer);
+        self.subcompaction_writers
+            .extend(other.subcompaction_writers);
+        self.tables_to_rewrite.extend(other.tables_to_rewrite);
+    }
+
     fn consume_writer(self, opts: &Options, dst_lvl: usize) -> crate::Result<Vec<Table>> {
         let table_base_folder = self.table_writer.base_path.clone();
 
-        let pin_filter = opts.config.filter_block_pinning_policy.get(dst_lvl);
-        let pin_index = opts.config.index_block_pinning_policy.get(dst_lvl);
+        let pinning = Pinning::from_config(&opts.config, dst_lvl);
+
+        let mut results = self.table_writer.finish()?;
+
+        for writer in self.subcompaction_writers {
+            results.extend(writer.finish()?);
+        }
 
-        self.table_writer
-            .finish()?
+        results
             .into_iter()
             .map(|(table_id, checksum)| -> crate::Result<Table> {
                 Table::recover(
@@ -349,8 +390,7 @@ impl StandardCompaction {
                     opts.tree_id,
                     opts.config.cache.clone(),
                     opts.config.descriptor_table.clone(),
-                    pin_filter,
-                    pin_index,
+                    pinning,
                     #[cfg(feature = "metrics")]
                     opts.metrics.clone(),
                 )
@@ -405,6 +445,7 @@ impl CompactionFlavour for StandardCompaction {
 
         super_version.upgrade_version(
             &opts.config.path,
+            opts.config.checksum_type,
             |current| {
                 let mut copy = current.clone();
 
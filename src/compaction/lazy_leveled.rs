@@ -0,0 +1,332 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use super::{Choice, CompactionStrategy, Input as CompactionInput};
+use crate::{
+    compaction::state::CompactionState, config::Config, table::Table, version::Version, KvPair,
+};
+
+#[doc(hidden)]
+pub const NAME: &str = "LazyLeveledCompaction";
+
+/// Lazy leveling compaction strategy (hybrid of tiering and leveling)
+///
+/// The upper levels are tiered: each level collects multiple runs, and once it reaches
+/// `level_ratio` runs, they are merged into a single, larger run in the next level.
+///
+/// Only the last level is leveled: it is kept as a single sorted run, and runs
+/// from the level above are merged into its overlapping tables.
+///
+/// Because the last level holds most of the data, this gets most of the write amplification
+/// savings of tiering, while keeping point reads and space amplification on the largest level
+/// close to leveling.
+///
+/// More info here: <https://stratos.seas.harvard.edu/files/stratos/files/dostoevskykv.pdf>
+#[derive(Clone)]
+pub struct Strategy {
+    l0_threshold: u8,
+
+    /// The target table size as disk (possibly compressed).
+    target_size: u64,
+
+    /// Number of runs a tiered level can hold before it is merged into the next level
+    level_ratio: u8,
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Self {
+            l0_threshold: 4,
+            target_size:/* 64 MiB */ 64 * 1_024 * 1_024,
+            level_ratio: 4,
+        }
+    }
+}
+
+impl Strategy {
+    /// Sets the L0 threshold.
+    ///
+    /// When the number of runs in L0 reaches this threshold,
+    /// they are merged into L1.
+    ///
+    /// Default = 4
+    #[must_use]
+    pub fn with_l0_threshold(mut self, threshold: u8) -> Self {
+        self.l0_threshold = threshold;
+        self
+    }
+
+    /// Sets the table target size on disk (possibly compressed).
+    ///
+    /// Default = 64 MiB
+    #[must_use]
+    pub fn with_table_target_size(mut self, bytes: u64) -> Self {
+        self.target_size = bytes;
+        self
+    }
+
+    /// Sets the number of runs the tiered levels (L1 up to the second-to-last level)
+    /// can hold before they are merged into the next level.
+    ///
+    /// Default = 4
+    #[must_use]
+    pub fn with_level_ratio(mut self, ratio: u8) -> Self {
+        self.level_ratio = ratio;
+        self
+    }
+
+    /// Returns the number of runs that triggers a compaction of the given level.
+    fn run_threshold(&self, level_idx: usize) -> usize {
+        let threshold = if level_idx == 0 {
+            self.l0_threshold
+        } else {
+            self.level_ratio
+        };
+
+        usize::from(threshold.max(1))
+    }
+}
+
+impl CompactionStrategy for Strategy {
+    fn get_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn get_config(&self) -> Vec<KvPair> {
+        vec![
+            (
+                crate::UserKey::from("lazy_leveled_l0_threshold"),
+                crate::UserValue::from(self.l0_threshold.to_le_bytes()),
+            ),
+            (
+                crate::UserKey::from("lazy_leveled_target_size"),
+                crate::UserValue::from(self.target_size.to_le_bytes()),
+            ),
+            (
+                crate::UserKey::from("lazy_leveled_level_ratio"),
+                crate::UserValue::from(self.level_ratio.to_le_bytes()),
+            ),
+        ]
+    }
+
+    fn choose(&self, version: &Version, _: &Config, state: &CompactionState) -> Choice {
+        let last_level_idx = version.level_count() - 1;
+
+        // NOTE: Deeper levels are compacted first, so merging a level
+        // does not immediately push the next level over its threshold
+        for level_idx in (0..last_level_idx).rev() {
+            let Some(level) = version.level(level_idx) else {
+                continue;
+            };
+
+            if level.run_count() < self.run_threshold(level_idx)
+                || version.level_is_busy(level_idx, state.hidden_set())
+            {
+                continue;
+            }
+
+            let dest_level = level_idx + 1;
+            let mut table_ids = level.list_ids();
+
+            let is_trivial_move = if dest_level == last_level_idx {
+                // NOTE: The last level is leveled, so the runs are merged
+                // into its overlapping tables, keeping it a single sorted run
+                if version.level_is_busy(dest_level, state.hidden_set()) {
+                    continue;
+                }
+
+                let Some(last_level) = version.level(dest_level) else {
+                    continue;
+                };
+
+                let key_range = level.aggregate_key_range();
+
+                let overlapping_table_ids = last_level
+                    .iter()
+                    .flat_map(|run| run.get_overlapping(&key_range))
+                    .map(Table::id)
+                    .collect::<Vec<_>>();
+
+                let is_trivial_move = overlapping_table_ids.is_empty() && level.is_disjoint();
+
+                table_ids.extend(overlapping_table_ids);
+
+                is_trivial_move
+            } else {
+                // NOTE: Tiered levels just get another run
+                level.run_count() == 1
+            };
+
+            #[expect(
+                clippy::cast_possible_truncation,
+                reason = "level index is bounded by level count (7, technically 255)"
+            )]
+            let input = CompactionInput {
+                table_ids,
+                dest_level: dest_level as u8,
+                canonical_level: dest_level as u8,
+                target_size: self.target_size,
+            };
+
+            return if is_trivial_move {
+                Choice::Move(input)
+            } else {
+                Choice::Merge(input)
+            };
+        }
+
+        Choice::DoNothing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Strategy;
+    use crate::{
+        compaction::test_util::flush_overlapping_tables, AbstractTree, Config, SeqNo,
+        SequenceNumberCounter,
+    };
+    use std::sync::Arc;
+    use test_log::test;
+
+    #[test]
+    fn lazy_leveled_empty_levels() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        tree.compact(Arc::new(Strategy::default()), 0)?;
+
+        assert_eq!(0, tree.table_count());
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_leveled_l0_below_limit() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        flush_overlapping_tables(&tree, &SequenceNumberCounter::default(), 3)?;
+
+        tree.compact(Arc::new(Strategy::default()), 0)?;
+
+        assert_eq!(Some(3), tree.level_table_count(0));
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_leveled_upper_levels_are_tiered() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        let seqno = SequenceNumberCounter::default();
+        let strategy = Arc::new(Strategy::default().with_l0_threshold(2).with_level_ratio(3));
+
+        for _ in 0..2 {
+            flush_overlapping_tables(&tree, &seqno, 2)?;
+            tree.compact(strategy.clone(), 0)?;
+        }
+
+        // NOTE: L1 collects multiple runs
+        let version = tree.current_version();
+        assert_eq!(0, version.l0().table_count());
+        assert_eq!(Some(2), version.level(1).map(|level| level.run_count()));
+
+        flush_overlapping_tables(&tree, &seqno, 2)?;
+        tree.compact(strategy.clone(), 0)?;
+        tree.compact(strategy, 0)?;
+
+        // NOTE: L1 reached the ratio, so its runs are merged into a single run in L2
+        let version = tree.current_version();
+        assert_eq!(Some(0), version.level(1).map(|level| level.run_count()));
+        assert_eq!(Some(1), version.level(2).map(|level| level.run_count()));
+        assert_eq!(4, tree.len(SeqNo::MAX, None)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_leveled_last_level_is_single_run() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        // NOTE: With a ratio of 1, every run is pushed down immediately
+        let strategy = Arc::new(Strategy::default().with_l0_threshold(1).with_level_ratio(1));
+
+        for i in 0..5u8 {
+            let seqno = u64::from(i) * 2;
+
+            tree.insert("a", "v", seqno);
+            tree.insert([b'k', i].as_slice(), "v", seqno);
+            tree.insert("z", "v", seqno);
+            tree.flush_active_memtable(0)?;
+
+            tree.remove([b'k', i].as_slice(), seqno + 1);
+            tree.flush_active_memtable(0)?;
+
+            for _ in 0..20 {
+                tree.compact(strategy.clone(), SeqNo::MAX)?;
+            }
+
+            let version = tree.current_version();
+            let last_level = version.level(6).expect("last level should exist");
+            assert_eq!(1, last_level.run_count());
+            assert_eq!(last_level.table_count(), version.table_count());
+        }
+
+        // NOTE: Tombstones are evicted in the last level
+        assert_eq!(2, tree.len(SeqNo::MAX, None)?);
+        assert_eq!(2, tree.approximate_len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_leveled_trivial_move_into_last_level() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        let strategy = Arc::new(Strategy::default().with_l0_threshold(1).with_level_ratio(1));
+
+        for i in 0..3u8 {
+            tree.insert([b'k', i].as_slice(), "v", u64::from(i));
+            tree.flush_active_memtable(0)?;
+
+            for _ in 0..20 {
+                tree.compact(strategy.clone(), 0)?;
+            }
+        }
+
+        // NOTE: Disjoint tables are never rewritten
+        let version = tree.current_version();
+        assert_eq!(Some(3), version.level(6).map(|level| level.table_count()));
+        assert!(version.iter_tables().all(|table| table.id() < 3));
+
+        Ok(())
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
     config::Config,
     slice_windows::{GrowingWindowsExt, ShrinkingWindowsExt},
     table::{util::aggregate_run_key_range, Table},
+    time::unix_timestamp,
     version::{Run, Version},
     HashSet, TableId,
 };
@@ -22,6 +23,7 @@ fn pick_minimal_compaction(
     hidden_set: &HiddenSet,
     overshoot: u64,
     table_base_size: u64,
+    min_overlapping_ratio: bool,
 ) -> Option<(HashSet<TableId>, bool)> {
     // NOTE: Find largest trivial move (if it exists)
     if let Some(window) = curr_run.shrinking_windows().find(|window| {
@@ -44,6 +46,11 @@ fn pick_minimal_compaction(
         return Some((ids, true));
     }
 
+    if min_overlapping_ratio {
+        return next_run
+            .and_then(|next_run| pick_min_overlapping_ratio(curr_run, next_run, hidden_set));
+    }
+
     // NOTE: Look for merges
     if let Some(next_run) = &next_run {
         next_run
@@ -107,6 +114,45 @@ fn pick_minimal_compaction(
     }
 }
 
+/// Picks the table whose merge into the next level rewrites the fewest bytes
+/// relative to its own size.
+///
+/// Same as `kMinOverlappingRatio` in `RocksDB`.
+fn pick_min_overlapping_ratio(
+    curr_run: &Run<Table>,
+    next_run: &Run<Table>,
+    hidden_set: &HiddenSet,
+) -> Option<(HashSet<TableId>, bool)> {
+    curr_run
+        .iter()
+        .filter(|table| !hidden_set.is_hidden(table.id()))
+        .filter_map(|table| {
+            let overlapping = next_run.get_overlapping(&table.metadata.key_range);
+
+            if hidden_set.is_blocked(overlapping.iter().map(Table::id)) {
+                // IMPORTANT: Compaction is blocked because of other
+                // on-going compaction
+                return None;
+            }
+
+            let overlapping_size = overlapping.iter().map(Table::file_size).sum::<u64>();
+
+            #[expect(
+                clippy::cast_precision_loss,
+                reason = "precision loss is acceptable for scoring calculations"
+            )]
+            let ratio = overlapping_size as f64 / table.file_size().max(1) as f64;
+
+            Some((table, overlapping, ratio))
+        })
+        .min_by(|(_, _, a), (_, _, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(table, overlapping, _)| {
+            let mut ids: HashSet<_> = overlapping.iter().map(Table::id).collect();
+            ids.insert(table.id());
+            (ids, false)
+        })
+}
+
 #[doc(hidden)]
 pub const NAME: &str = "LeveledCompaction";
 
@@ -130,6 +176,18 @@ pub struct Strategy {
 
     /// Size ratio between levels of the LSM tree (a.k.a fanout, growth rate)
     level_ratio_policy: Vec<f32>,
+
+    /// If true, level target sizes are derived from the size of the last level
+    dynamic_level_bytes: bool,
+
+    /// Ratio of reclaimable items at which a table is compacted, regardless of level sizes
+    tombstone_ratio_threshold: Option<f32>,
+
+    /// Age in seconds after which a table is rewritten, regardless of level sizes
+    periodic_compaction_seconds: Option<u64>,
+
+    /// If true, single tables are picked by the ratio of overlapping bytes in the next level
+    min_overlapping_ratio: bool,
 }
 
 impl Default for Strategy {
@@ -138,6 +196,10 @@ impl Default for Strategy {
             l0_threshold: 4,
             target_size:/* 64 MiB */ 64 * 1_024 * 1_024,
             level_ratio_policy: vec![10.0],
+            dynamic_level_bytes: false,
+            tombstone_ratio_threshold: None,
+            periodic_compaction_seconds: None,
+            min_overlapping_ratio: false,
         }
     }
 }
@@ -179,6 +241,77 @@ impl Strategy {
         self
     }
 
+    /// Enables dynamic level target sizes.
+    ///
+    /// Instead of growing the level sizes from L1 downwards, the target sizes
+    /// are computed backwards from the actual size of the last level,
+    /// so that every level is `ratio` times larger than the one above it.
+    ///
+    /// The base level (the level L0 is compacted into) is chosen as the level
+    /// whose target size does not exceed `target_size * l0_threshold`, so it moves up
+    /// as the tree grows, and down as the tree shrinks.
+    /// Levels above the base level are drained into the base level.
+    ///
+    /// This keeps space amplification low, even when the tree is small.
+    ///
+    /// Same as `level_compaction_dynamic_level_bytes` in `RocksDB`.
+    ///
+    /// Default = false
+    #[must_use]
+    pub fn with_dynamic_level_bytes(mut self, enabled: bool) -> Self {
+        self.dynamic_level_bytes = enabled;
+        self
+    }
+
+    /// Sets the ratio of reclaimable items (tombstones, and values shadowed by
+    /// weak tombstones) at which a table is compacted, even if its level is
+    /// below its size target.
+    ///
+    /// Only tables whose items are all below the GC watermark are considered,
+    /// and they are pushed down until their tombstones are evicted in the last level.
+    ///
+    /// Default = disabled
+    #[must_use]
+    pub fn with_tombstone_ratio_threshold(mut self, ratio: f32) -> Self {
+        self.tombstone_ratio_threshold = Some(ratio);
+        self
+    }
+
+    /// Sets the age after which a table is rewritten, even if its level is
+    /// below its size target.
+    ///
+    /// Cold tables that are never overlapped by compactions from upper levels
+    /// would otherwise keep obsolete versions and tombstones forever.
+    /// Rewriting them also applies the current compression and filter policies.
+    ///
+    /// Tables are rewritten one at a time, oldest first, into the same level.
+    ///
+    /// Same as `periodic_compaction_seconds` in `RocksDB`.
+    ///
+    /// Default = None (disabled)
+    #[must_use]
+    pub fn with_periodic_compaction_seconds(mut self, seconds: Option<u64>) -> Self {
+        self.periodic_compaction_seconds = seconds.filter(|&s| s > 0);
+        self
+    }
+
+    /// Picks compactions from L1 and below by the minimum overlapping ratio.
+    ///
+    /// Instead of choosing the smallest compaction among windows of the next level,
+    /// every table of an oversized level is scored by the bytes it overlaps
+    /// in the next level divided by its own size.
+    /// The table with the lowest ratio is merged, which lowers
+    /// write amplification for random-update workloads.
+    ///
+    /// Same as `kMinOverlappingRatio` in `RocksDB`.
+    ///
+    /// Default = false
+    #[must_use]
+    pub fn with_min_overlapping_ratio(mut self, enabled: bool) -> Self {
+        self.min_overlapping_ratio = enabled;
+        self
+    }
+
     /// Calculates the size of L1.
     fn level_base_size(&self) -> u64 {
         self.target_size * u64::from(self.l0_threshold)
@@ -211,13 +344,7 @@ impl Strategy {
 
             // NOTE: Minus 2 because |{L0, L1}|
             for idx in 0..=(canonical_level_idx - 2) {
-                let ratio = self
-                    .level_ratio_policy
-                    .get(usize::from(idx))
-                    .copied()
-                    .unwrap_or_else(|| self.level_ratio_policy.last().copied().unwrap_or(10.0));
-
-                size *= ratio;
+                size *= self.level_ratio(usize::from(idx));
             }
 
             #[expect(
@@ -232,6 +359,216 @@ impl Strategy {
     }
 }
 
+impl Strategy {
+    /// Returns the growth ratio between canonical level `idx + 1` and `idx + 2`.
+    fn level_ratio(&self, idx: usize) -> f32 {
+        self.level_ratio_policy
+            .get(idx)
+            .copied()
+            .unwrap_or_else(|| self.level_ratio_policy.last().copied().unwrap_or(10.0))
+    }
+
+    /// Calculates the dynamic level target sizes.
+    ///
+    /// Returns the base level index, and the target size of every level.
+    ///
+    /// Levels above the base level have a target size of 0.
+    fn dynamic_level_targets(&self, version: &Version) -> (usize, Vec<u64>) {
+        let last_level_idx = version.level_count() - 1;
+        let level_base_size = self.level_base_size();
+
+        let mut targets = vec![0; version.level_count()];
+
+        let Some((last_non_empty_idx, last_size)) = (1..=last_level_idx)
+            .rev()
+            .filter_map(|idx| version.level(idx).map(|lvl| (idx, lvl)))
+            .find(|(_, lvl)| !lvl.is_empty())
+            .map(|(idx, lvl)| (idx, lvl.size()))
+        else {
+            // NOTE: Fill up the last level first
+            if let Some(target) = targets.get_mut(last_level_idx) {
+                *target = level_base_size;
+            }
+            return (last_level_idx, targets);
+        };
+
+        // NOTE: Find the minimum number of levels above the last level,
+        // so that the base level does not exceed the level base size
+        let mut base_level_idx = last_non_empty_idx;
+
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "precision loss is acceptable for level size calculations"
+        )]
+        let mut base_size = last_size as f32;
+
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "precision loss is acceptable for level size calculations"
+        )]
+        while base_level_idx > 1 && base_size > level_base_size as f32 {
+            base_level_idx -= 1;
+            base_size = last_size as f32;
+
+            for ratio_idx in 0..(last_non_empty_idx - base_level_idx) {
+                base_size /= self.level_ratio(ratio_idx);
+            }
+        }
+
+        // NOTE: Grow the targets from the base level downwards
+        let mut size = base_size;
+
+        for (idx, target) in targets
+            .iter_mut()
+            .enumerate()
+            .take(last_non_empty_idx + 1)
+            .skip(base_level_idx)
+        {
+            if idx > base_level_idx {
+                size *= self.level_ratio(idx - base_level_idx - 1);
+            }
+
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "size is always positive and will never even come close to u64::MAX"
+            )]
+            {
+                *target = (size as u64).max(level_base_size);
+            }
+        }
+
+        (base_level_idx, targets)
+    }
+}
+
+impl Strategy {
+    /// Picks the table with the most reclaimable items that are below the GC watermark.
+    ///
+    /// Tables in the last level are rewritten in place, which evicts their tombstones,
+    /// tables in other levels are merged into the next level.
+    fn pick_tombstone_compaction(
+        &self,
+        version: &Version,
+        state: &CompactionState,
+        level_shift: usize,
+    ) -> Option<Choice> {
+        let threshold = self.tombstone_ratio_threshold?;
+        let gc_watermark = state.gc_watermark();
+        let last_level_idx = version.level_count() - 1;
+
+        let (level_idx, table) = version
+            .iter_levels()
+            .enumerate()
+            .skip(1)
+            .flat_map(|(idx, level)| {
+                level
+                    .iter()
+                    .flat_map(|run| run.iter())
+                    .map(move |table| (idx, table))
+            })
+            .filter(|(_, table)| {
+                table.get_highest_seqno() < gc_watermark
+                    && !state.hidden_set().is_hidden(table.id())
+            })
+            .map(|(idx, table)| (idx, table, table.reclaimable_ratio()))
+            .filter(|(_, _, ratio)| *ratio >= threshold && *ratio > 0.0)
+            .max_by(|(_, _, a), (_, _, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(idx, table, _)| (idx, table))?;
+
+        let dest_level = (level_idx + 1).min(last_level_idx);
+
+        if version.level_is_busy(dest_level, state.hidden_set()) {
+            return None;
+        }
+
+        let mut table_ids = HashSet::default();
+        table_ids.insert(table.id());
+
+        if dest_level != level_idx {
+            let next_level = version.level(dest_level)?;
+
+            table_ids.extend(
+                next_level
+                    .iter()
+                    .flat_map(|run| run.get_overlapping(&table.metadata.key_range))
+                    .map(Table::id),
+            );
+        }
+
+        log::debug!(
+            "Compacting table {} in L{level_idx} because of its reclaimable ratio",
+            table.id(),
+        );
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "level index is bounded by level count (7, technically 255)"
+        )]
+        Some(Choice::Merge(CompactionInput {
+            table_ids,
+            dest_level: dest_level as u8,
+            canonical_level: dest_level.saturating_sub(level_shift).max(1) as u8,
+            target_size: self.target_size,
+        }))
+    }
+}
+
+impl Strategy {
+    /// Picks the oldest table that is older than the periodic compaction age,
+    /// and rewrites it into the same level.
+    fn pick_periodic_compaction(
+        &self,
+        version: &Version,
+        state: &CompactionState,
+        level_shift: usize,
+    ) -> Option<Choice> {
+        let seconds = self.periodic_compaction_seconds?;
+
+        let cutoff = unix_timestamp()
+            .as_nanos()
+            .checked_sub(u128::from(seconds) * 1_000_000_000u128)?;
+
+        let (level_idx, table) = version
+            .iter_levels()
+            .enumerate()
+            .skip(1)
+            // NOTE: A table can only be rewritten in place if it is the only run
+            // in its level, otherwise it could overtake newer runs
+            .filter(|(_, level)| level.is_disjoint())
+            .flat_map(|(idx, level)| {
+                level
+                    .iter()
+                    .flat_map(|run| run.iter())
+                    .map(move |table| (idx, table))
+            })
+            .filter(|(_, table)| {
+                u128::from(table.metadata.created_at) <= cutoff
+                    && !state.hidden_set().is_hidden(table.id())
+            })
+            .min_by_key(|(_, table)| table.metadata.created_at)?;
+
+        log::debug!(
+            "Rewriting table {} in L{level_idx} because it is older than {seconds}s",
+            table.id(),
+        );
+
+        let mut table_ids = HashSet::default();
+        table_ids.insert(table.id());
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "level index is bounded by level count (7, technically 255)"
+        )]
+        Some(Choice::Merge(CompactionInput {
+            table_ids,
+            dest_level: level_idx as u8,
+            canonical_level: level_idx.saturating_sub(level_shift).max(1) as u8,
+            target_size: self.target_size,
+        }))
+    }
+}
+
 impl CompactionStrategy for Strategy {
     fn get_name(&self) -> &'static str {
         NAME
@@ -270,6 +607,30 @@ impl CompactionStrategy for Strategy {
                     v
                 }),
             ),
+            (
+                crate::UserKey::from("leveled_dynamic_level_bytes"),
+                crate::UserValue::from([u8::from(self.dynamic_level_bytes)]),
+            ),
+            (
+                crate::UserKey::from("leveled_tombstone_ratio_threshold"),
+                crate::UserValue::from(
+                    self.tombstone_ratio_threshold
+                        .map(f32::to_le_bytes)
+                        .unwrap_or_default(),
+                ),
+            ),
+            (
+                crate::UserKey::from("leveled_periodic_compaction_seconds"),
+                crate::UserValue::from(
+                    self.periodic_compaction_seconds
+                        .map(u64::to_le_bytes)
+                        .unwrap_or_default(),
+                ),
+            ),
+            (
+                crate::UserKey::from("leveled_min_overlapping_ratio"),
+                crate::UserValue::from([u8::from(self.min_overlapping_ratio)]),
+            ),
         ]
     }
 
@@ -322,7 +683,24 @@ impl CompactionStrategy for Strategy {
         // Number of levels we have to shift to get from the actual level idx to the canonical
         let mut level_shift = canonical_l1_idx - 1;
 
-        if canonical_l1_idx > 1 && version.iter_levels().skip(1).any(|lvl| !lvl.is_empty()) {
+        // NOTE: In dynamic mode, the level targets are derived from the last level instead
+        let dynamic_level_targets = if self.dynamic_level_bytes {
+            let (base_level_idx, targets) = self.dynamic_level_targets(version);
+
+            // NOTE: If there is data above the base level (because the tree shrunk),
+            // L0 cannot skip over it, so it is drained first
+            canonical_l1_idx = base_level_idx.min(first_non_empty_level);
+            level_shift = canonical_l1_idx - 1;
+
+            Some(targets)
+        } else {
+            None
+        };
+
+        if dynamic_level_targets.is_none()
+            && canonical_l1_idx > 1
+            && version.iter_levels().skip(1).any(|lvl| !lvl.is_empty())
+        {
             let need_new_l1 = version
                 .iter_levels()
                 .enumerate()
@@ -402,10 +780,6 @@ impl CompactionStrategy for Strategy {
         let mut scores = [(/* score */ 0.0, /* overshoot */ 0u64); 7];
 
         {
-            // TODO(weak-tombstone-rewrite): incorporate `Table::weak_tombstone_count` and
-            // `Table::weak_tombstone_reclaimable` when computing level scores so rewrite
-            // decisions can prioritize tables that would free the most reclaimable values.
-
             // Score first level
             let first_level = version.l0();
 
@@ -437,7 +811,12 @@ impl CompactionStrategy for Strategy {
                     clippy::cast_possible_truncation,
                     reason = "level index is bounded by level count (7, technically 255)"
                 )]
-                let target_size = self.level_target_size((idx - level_shift) as u8);
+                let target_size = match &dynamic_level_targets {
+                    // NOTE: Levels above the base level have a target size of 0,
+                    // so they are drained into the base level
+                    Some(targets) => targets.get(idx).copied().unwrap_or_default().max(1),
+                    None => self.level_target_size((idx - level_shift) as u8),
+                };
 
                 // NOTE: We check for level length above
                 #[expect(clippy::indexing_slicing)]
@@ -478,7 +857,12 @@ impl CompactionStrategy for Strategy {
             .expect("should have highest score somewhere");
 
         if score < 1.0 {
-            return Choice::DoNothing;
+            // NOTE: If all levels are in shape, clean up tables with lots of reclaimable items,
+            // or tables that have not been rewritten for a long time
+            return self
+                .pick_tombstone_compaction(version, state, level_shift)
+                .or_else(|| self.pick_periodic_compaction(version, state, level_shift))
+                .unwrap_or(Choice::DoNothing);
         }
 
         // We choose L0->L1 compaction
@@ -544,7 +928,10 @@ impl CompactionStrategy for Strategy {
         };
 
         debug_assert!(level.is_disjoint(), "level should be disjoint");
-        debug_assert!(next_level.is_disjoint(), "next level should be disjoint");
+        debug_assert!(
+            next_level.is_empty() || next_level.is_disjoint(),
+            "next level should be disjoint",
+        );
 
         #[expect(
             clippy::expect_used,
@@ -556,6 +943,7 @@ impl CompactionStrategy for Strategy {
             state.hidden_set(),
             overshoot_bytes,
             self.target_size,
+            self.min_overlapping_ratio,
         ) else {
             return Choice::DoNothing;
         };
@@ -175,3 +175,488 @@ fn leveled_sequential_inserts() -> crate::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn leveled_dynamic_targets_empty() {
+    let version = crate::version::Version::new(0, crate::TreeType::Standard);
+
+    let strategy = Strategy::default().with_dynamic_level_bytes(true);
+    let (base_level_idx, targets) = strategy.dynamic_level_targets(&version);
+
+    assert_eq!(6, base_level_idx);
+    assert_eq!(Some(&strategy.level_base_size()), targets.get(6));
+}
+
+#[test]
+#[expect(clippy::unwrap_used)]
+fn leveled_dynamic_targets_base_level_moves() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    for k in 0u64..100 {
+        tree.insert(k.to_be_bytes(), "", k);
+        tree.flush_active_memtable(0)?;
+    }
+    tree.compact(Arc::new(crate::compaction::MoveDown(0, 6)), 0)?;
+
+    let last_level_size = tree.current_version().level(6).unwrap().size();
+
+    // NOTE: L0 threshold = 4, so the level base size is 25x smaller than the last level
+    let strategy = Strategy::default()
+        .with_dynamic_level_bytes(true)
+        .with_table_target_size(last_level_size / 100);
+
+    let (base_level_idx, targets) = strategy.dynamic_level_targets(&tree.current_version());
+    assert_eq!(4, base_level_idx);
+    assert_eq!(vec![0, 0, 0, 0], targets[0..4]);
+    assert_eq!(last_level_size, targets[6]);
+    assert!(targets[5].abs_diff(last_level_size / 10) <= 1);
+    assert!(targets[4] >= strategy.level_base_size());
+    assert!(targets[4] <= strategy.level_base_size() * 10);
+
+    // NOTE: If the tree shrinks, the base level moves down
+    tree.drop_range(0u64.to_be_bytes()..90u64.to_be_bytes())?;
+
+    let (base_level_idx, targets) = strategy.dynamic_level_targets(&tree.current_version());
+    assert_eq!(5, base_level_idx);
+    assert_eq!(tree.current_version().level(6).unwrap().size(), targets[6]);
+
+    // NOTE: A small tree only uses the last level
+    let strategy = Strategy::default()
+        .with_dynamic_level_bytes(true)
+        .with_table_target_size(last_level_size);
+
+    let (base_level_idx, _) = strategy.dynamic_level_targets(&tree.current_version());
+    assert_eq!(6, base_level_idx);
+
+    Ok(())
+}
+
+#[test]
+#[expect(clippy::unwrap_used)]
+fn leveled_dynamic_drains_levels_above_base() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    for k in 0u64..10 {
+        tree.insert(k.to_be_bytes(), "old", k);
+        tree.flush_active_memtable(0)?;
+    }
+    tree.compact(Arc::new(crate::compaction::MoveDown(0, 6)), 0)?;
+
+    for k in 0u64..10 {
+        tree.insert(k.to_be_bytes(), "new", 10 + k);
+    }
+    tree.flush_active_memtable(0)?;
+    tree.compact(Arc::new(crate::compaction::MoveDown(0, 3)), 0)?;
+
+    // NOTE: The tree is so small that the base level is the last level
+    let strategy = Arc::new(Strategy::default().with_dynamic_level_bytes(true));
+
+    for _ in 0..10 {
+        tree.compact(strategy.clone(), 0)?;
+    }
+
+    let version = tree.current_version();
+    for idx in 0..6 {
+        assert_eq!(
+            0,
+            version.level(idx).unwrap().len(),
+            "L{idx} should be empty"
+        );
+    }
+    assert_eq!(
+        Some("new".as_bytes().into()),
+        tree.get(0u64.to_be_bytes(), crate::SeqNo::MAX)?
+    );
+
+    Ok(())
+}
+
+#[test]
+#[expect(clippy::unwrap_used)]
+fn leveled_dynamic_sequential_inserts() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    let strategy = Arc::new(
+        Strategy::default()
+            .with_dynamic_level_bytes(true)
+            .with_l0_threshold(2)
+            .with_table_target_size(1),
+    );
+
+    for k in 0u64..100 {
+        for x in 0u64..2 {
+            tree.insert(k.to_be_bytes(), x.to_be_bytes(), k * 2 + x);
+            tree.flush_active_memtable(0)?;
+        }
+        tree.compact(strategy.clone(), 0)?;
+    }
+
+    // NOTE: Levels are filled from the bottom up
+    let version = tree.current_version();
+    let sizes = (1..7)
+        .map(|idx| version.level(idx).unwrap().size())
+        .collect::<Vec<_>>();
+
+    let first_non_empty = sizes.iter().position(|&size| size > 0).unwrap();
+    assert!(sizes[first_non_empty..].iter().all(|&size| size > 0));
+    assert_eq!(100, tree.len(crate::SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn leveled_dynamic_config() {
+    let config = Strategy::default()
+        .with_dynamic_level_bytes(true)
+        .get_config();
+
+    assert!(config
+        .iter()
+        .any(|(k, v)| &**k == b"leveled_dynamic_level_bytes" && &**v == [1]));
+}
+
+/// Writes a table with 100 values, 50 of which are deleted, and moves it into the given level.
+fn write_deletion_heavy_table(tree: &crate::AnyTree, level: u8) -> crate::Result<()> {
+    for k in 0u64..100 {
+        tree.insert(k.to_be_bytes(), "v", k);
+    }
+    for k in 0u64..50 {
+        tree.remove(k.to_be_bytes(), 100 + k);
+    }
+    tree.flush_active_memtable(0)?;
+    tree.compact(Arc::new(crate::compaction::MoveDown(0, level)), 0)?;
+
+    Ok(())
+}
+
+#[test]
+#[expect(clippy::unwrap_used)]
+fn leveled_table_tombstone_ratio() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    write_deletion_heavy_table(&tree, 6)?;
+
+    let version = tree.current_version();
+    let table = version.iter_tables().next().unwrap();
+    assert_eq!(50, table.tombstone_count());
+    assert!((table.tombstone_ratio() - (50.0 / 150.0)).abs() < f32::EPSILON);
+    assert!((table.reclaimable_ratio() - (50.0 / 150.0)).abs() < f32::EPSILON);
+
+    Ok(())
+}
+
+#[test]
+fn leveled_tombstone_compaction_last_level() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    write_deletion_heavy_table(&tree, 6)?;
+    assert_eq!(150, tree.approximate_len());
+
+    // NOTE: Disabled by default
+    tree.compact(Arc::new(Strategy::default()), crate::SeqNo::MAX)?;
+    assert_eq!(150, tree.approximate_len());
+
+    let strategy = Arc::new(Strategy::default().with_tombstone_ratio_threshold(0.2));
+
+    // NOTE: Tombstones are not below the GC watermark yet
+    tree.compact(strategy.clone(), 100)?;
+    assert_eq!(150, tree.approximate_len());
+
+    tree.compact(strategy.clone(), crate::SeqNo::MAX)?;
+    assert_eq!(50, tree.approximate_len());
+    assert_eq!(0, tree.tombstone_count());
+    assert_eq!(Some(1), tree.level_table_count(6));
+
+    // NOTE: Nothing left to reclaim
+    let version_id = tree.current_version().id();
+    tree.compact(strategy, crate::SeqNo::MAX)?;
+    assert_eq!(version_id, tree.current_version().id());
+
+    Ok(())
+}
+
+#[test]
+fn leveled_tombstone_compaction_pushes_down() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    write_deletion_heavy_table(&tree, 3)?;
+
+    let strategy = Arc::new(Strategy::default().with_tombstone_ratio_threshold(0.2));
+
+    for _ in 0..10 {
+        tree.compact(strategy.clone(), crate::SeqNo::MAX)?;
+    }
+
+    assert_eq!(50, tree.approximate_len());
+    assert_eq!(0, tree.tombstone_count());
+    assert_eq!(Some(1), tree.level_table_count(6));
+    assert_eq!(50, tree.len(crate::SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn leveled_tombstone_compaction_weak_tombstones() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    for k in 0u64..100 {
+        tree.insert(k.to_be_bytes(), "v", k);
+    }
+    for k in 0u64..50 {
+        tree.remove_weak(k.to_be_bytes(), 100 + k);
+    }
+    tree.flush_active_memtable(0)?;
+    tree.compact(Arc::new(crate::compaction::MoveDown(0, 6)), 0)?;
+
+    assert_eq!(50, tree.weak_tombstone_reclaimable_count());
+
+    tree.compact(
+        Arc::new(Strategy::default().with_tombstone_ratio_threshold(0.2)),
+        crate::SeqNo::MAX,
+    )?;
+
+    assert_eq!(50, tree.approximate_len());
+    assert_eq!(0, tree.weak_tombstone_count());
+
+    Ok(())
+}
+
+#[test]
+fn leveled_tombstone_compaction_below_threshold() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    write_deletion_heavy_table(&tree, 6)?;
+
+    let strategy = Arc::new(Strategy::default().with_tombstone_ratio_threshold(0.5));
+    tree.compact(strategy, crate::SeqNo::MAX)?;
+
+    assert_eq!(150, tree.approximate_len());
+    assert_eq!(50, tree.tombstone_count());
+
+    Ok(())
+}
+
+#[test]
+fn leveled_periodic_compaction() -> crate::Result<()> {
+    let _clock = crate::time::lock_clock_for_test();
+
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    crate::time::set_unix_timestamp_for_test(Some(std::time::Duration::from_secs(1_000)));
+
+    for seqno in 0..3 {
+        tree.insert("a", "v", seqno);
+        tree.flush_active_memtable(0)?;
+    }
+    tree.insert("b", "v", 3);
+    tree.flush_active_memtable(0)?;
+    tree.compact(Arc::new(Strategy::default().with_l0_threshold(1)), 0)?;
+    tree.compact(Arc::new(crate::compaction::MoveDown(1, 6)), 0)?;
+
+    assert_eq!(Some(1), tree.level_table_count(6));
+    assert_eq!(4, tree.approximate_len());
+
+    let strategy = Arc::new(Strategy::default().with_periodic_compaction_seconds(Some(100)));
+
+    // NOTE: Table is not old enough yet
+    crate::time::set_unix_timestamp_for_test(Some(std::time::Duration::from_secs(1_050)));
+    tree.compact(strategy.clone(), crate::SeqNo::MAX)?;
+    assert_eq!(4, tree.approximate_len());
+
+    // NOTE: Disabled
+    crate::time::set_unix_timestamp_for_test(Some(std::time::Duration::from_secs(1_200)));
+    tree.compact(Arc::new(Strategy::default()), crate::SeqNo::MAX)?;
+    assert_eq!(4, tree.approximate_len());
+
+    tree.compact(strategy.clone(), crate::SeqNo::MAX)?;
+    assert_eq!(2, tree.approximate_len());
+    assert_eq!(Some(1), tree.level_table_count(6));
+
+    // NOTE: The rewritten table is fresh
+    let version_id = tree.current_version().id();
+    tree.compact(strategy, crate::SeqNo::MAX)?;
+    assert_eq!(version_id, tree.current_version().id());
+
+    crate::time::set_unix_timestamp_for_test(None);
+
+    Ok(())
+}
+
+#[test]
+fn leveled_periodic_compaction_config() {
+    let config = Strategy::default()
+        .with_periodic_compaction_seconds(Some(3_600))
+        .get_config();
+
+    assert!(config.iter().any(|(k, v)| {
+        &**k == b"leveled_periodic_compaction_seconds" && **v == 3_600u64.to_le_bytes()
+    }));
+}
+
+/// Writes L6 = [X: a00..a99, Y: m00..m99] and L5 = [P: a00..a99, Q: m50]
+///
+/// Returns the table IDs of P and Q.
+fn write_overlapping_ratio_tables(tree: &crate::AnyTree) -> crate::Result<(u64, u64)> {
+    let seqno = SequenceNumberCounter::default();
+
+    for prefix in [b'a', b'm'] {
+        for k in 0..100u8 {
+            tree.insert([prefix, k], "v", seqno.next());
+        }
+        tree.flush_active_memtable(0)?;
+    }
+    tree.compact(Arc::new(crate::compaction::MoveDown(0, 6)), 0)?;
+
+    for k in 0..100u8 {
+        tree.insert([b'a', k], "v", seqno.next());
+    }
+    tree.flush_active_memtable(0)?;
+
+    tree.insert([b'm', 50], "v", seqno.next());
+    tree.flush_active_memtable(0)?;
+
+    tree.compact(Arc::new(crate::compaction::MoveDown(0, 5)), 0)?;
+
+    let version = tree.current_version();
+    let level = version.level(5).expect("level should exist");
+    let run = level.first_run().expect("run should exist");
+    assert_eq!(2, run.len());
+
+    let ids = run.iter().map(Table::id).collect::<Vec<_>>();
+    Ok((ids[0], ids[1]))
+}
+
+#[test]
+fn leveled_min_overlapping_ratio() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    let (large_table_id, small_table_id) = write_overlapping_ratio_tables(&tree)?;
+
+    tree.compact(
+        Arc::new(
+            Strategy::default()
+                .with_table_target_size(1)
+                .with_min_overlapping_ratio(true),
+        ),
+        0,
+    )?;
+
+    // NOTE: The large table rewrites fewer bytes per byte of its own size
+    let version = tree.current_version();
+    assert!(version.get_table(large_table_id).is_none());
+    assert!(version.get_table(small_table_id).is_some());
+    assert_eq!(200, tree.len(crate::SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn leveled_min_overlapping_ratio_picks_lowest_ratio() -> crate::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let tree = Config::new(
+        dir.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    let (large_table_id, small_table_id) = write_overlapping_ratio_tables(&tree)?;
+
+    let version = tree.current_version();
+    let curr_run = version
+        .level(5)
+        .and_then(crate::version::Level::first_run)
+        .expect("run should exist");
+    let next_run = version
+        .level(6)
+        .and_then(crate::version::Level::first_run)
+        .expect("run should exist");
+
+    let (ids, is_trivial_move) =
+        pick_min_overlapping_ratio(curr_run, next_run, &HiddenSet::default())
+            .expect("should pick a table");
+
+    // NOTE: P overlaps X (ratio ~1), Q overlaps Y (ratio ~100), so P and X are merged
+    let x_table_id = 0;
+    assert!(!is_trivial_move);
+    assert_eq!(
+        [large_table_id, x_table_id]
+            .into_iter()
+            .collect::<HashSet<_>>(),
+        ids,
+    );
+    assert!(!ids.contains(&small_table_id));
+
+    Ok(())
+}
+
+#[test]
+fn leveled_min_overlapping_ratio_config() {
+    let config = Strategy::default()
+        .with_min_overlapping_ratio(true)
+        .get_config();
+
+    assert!(config
+        .iter()
+        .any(|(k, v)| &**k == b"leveled_min_overlapping_ratio" && &**v == [1]));
+}
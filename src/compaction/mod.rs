@@ -5,8 +5,10 @@
 //! Contains compaction strategies
 
 pub(crate) mod fifo;
+pub(crate) mod lazy_leveled;
 pub(crate) mod leveled;
 // pub(crate) mod maintenance;
+pub(crate) mod compact_range;
 pub(crate) mod drop_range;
 mod flavour;
 pub(crate) mod major;
@@ -14,14 +16,23 @@ pub(crate) mod movedown;
 pub(crate) mod pulldown;
 pub(crate) mod state;
 pub(crate) mod stream;
-// pub(crate) mod tiered;
+pub(crate) mod tiered;
+pub(crate) mod time_window;
 pub(crate) mod worker;
 
+#[cfg(test)]
+mod test_util;
+
 pub use fifo::Strategy as Fifo;
+pub use lazy_leveled::Strategy as LazyLeveled;
 pub use leveled::Strategy as Leveled;
-// pub use tiered::Strategy as SizeTiered;
+pub use tiered::Strategy as SizeTiered;
+pub use time_window::Strategy as TimeWindow;
 
-pub use {fifo::NAME as FIFO_COMPACTION_NAME, leveled::NAME as LEVELED_COMPACTION_NAME};
+pub use {
+    fifo::NAME as FIFO_COMPACTION_NAME, lazy_leveled::NAME as LAZY_LEVELED_COMPACTION_NAME,
+    leveled::NAME as LEVELED_COMPACTION_NAME, tiered::NAME as TIERED_COMPACTION_NAME,
+};
 
 /// Alias for `Leveled`
 pub type Levelled = Leveled;
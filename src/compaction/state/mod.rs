@@ -4,6 +4,7 @@
 
 pub mod hidden_set;
 
+use crate::SeqNo;
 use hidden_set::HiddenSet;
 
 #[derive(Default)]
@@ -13,6 +14,12 @@ pub struct CompactionState {
     /// While consuming tables (because of compaction) they will not appear in the list of tables
     /// as to not cause conflicts between multiple compaction threads (compacting the same tables).
     hidden_set: HiddenSet,
+
+    /// MVCC GC watermark of the current compaction.
+    ///
+    /// Items below the watermark may be evicted, so strategies can use it
+    /// to find tables that can be cleaned up.
+    gc_watermark: SeqNo,
 }
 
 impl CompactionState {
@@ -20,6 +27,14 @@ impl CompactionState {
         &self.hidden_set
     }
 
+    pub fn gc_watermark(&self) -> SeqNo {
+        self.gc_watermark
+    }
+
+    pub fn set_gc_watermark(&mut self, seqno: SeqNo) {
+        self.gc_watermark = seqno;
+    }
+
     pub fn hidden_set_mut(&mut self) -> &mut HiddenSet {
         &mut self.hidden_set
     }
@@ -4,9 +4,10 @@
 
 use crate::{
     merge_operator::{MergeOperator, MergeResult},
+    range_tombstone::RangeTombstoneSet,
     InternalValue, SeqNo, UserKey, UserValue, ValueType,
 };
-use std::{iter::Peekable, sync::Arc};
+use std::{collections::VecDeque, iter::Peekable, sync::Arc};
 
 type Item = crate::Result<InternalValue>;
 
@@ -37,6 +38,12 @@ pub struct CompactionStream<'a, I: Iterator<Item = Item>> {
 
     /// Optional merge operator for combining merge operands
     merge_operator: Option<Arc<dyn MergeOperator>>,
+
+    /// Range tombstones that apply to the stream
+    range_tombstones: RangeTombstoneSet,
+
+    /// Versions that were read ahead, but still need to be emitted
+    pending: VecDeque<InternalValue>,
 }
 
 impl<'a, I: Iterator<Item = Item>> CompactionStream<'a, I> {
@@ -52,6 +59,8 @@ impl<'a, I: Iterator<Item = Item>> CompactionStream<'a, I> {
             evict_tombstones: false,
             zero_seqnos: false,
             merge_operator: None,
+            range_tombstones: RangeTombstoneSet::default(),
+            pending: VecDeque::new(),
         }
     }
 
@@ -75,11 +84,35 @@ impl<'a, I: Iterator<Item = Item>> CompactionStream<'a, I> {
     }
 
     /// Sets the merge operator for combining merge operands.
+    ///
+    /// Merge operands that are not visible to any snapshot anymore are folded
+    /// into a single value, if their base value (or a tombstone) is part of the stream.
+    /// When evicting tombstones (last level), no older versions can exist, so
+    /// the operands are folded even without a base value.
+    /// Otherwise, the operands are combined using [`MergeOperator::partial_merge`].
     pub fn merge_operator(mut self, op: Option<Arc<dyn MergeOperator>>) -> Self {
         self.merge_operator = op;
         self
     }
 
+    /// Sets the range tombstones that apply to the stream.
+    ///
+    /// Versions that are deleted by a range tombstone that is visible to
+    /// every snapshot are dropped.
+    /// The range tombstones themselves are not emitted by the stream.
+    pub fn range_tombstones(mut self, range_tombstones: RangeTombstoneSet) -> Self {
+        self.range_tombstones = range_tombstones;
+        self
+    }
+
+    /// Returns `true` if the version is deleted by a range tombstone
+    /// that is not visible to any snapshot.
+    fn is_range_deleted(&self, kv: &InternalValue) -> bool {
+        self.range_tombstones
+            .max_covering_seqno(&kv.key.user_key, self.gc_seqno_threshold)
+            .is_some_and(|rt_seqno| rt_seqno > kv.key.seqno)
+    }
+
     /// Drains the remaining versions of the given key.
     fn drain_key(&mut self, key: &UserKey) -> crate::Result<()> {
         loop {
@@ -105,115 +138,204 @@ impl<'a, I: Iterator<Item = Item>> CompactionStream<'a, I> {
         }
     }
 
-    /// Collects merge operands for the current key and returns
-    /// (operands, base_value_if_any, seqno_of_head).
-    ///
-    /// The `head` should already be a Merge operand.
-    fn collect_merge_operands(
-        &mut self,
-        head: InternalValue,
-    ) -> crate::Result<(Vec<UserValue>, Option<InternalValue>)> {
-        let user_key = &head.key.user_key;
-
-        // Operands are collected from newest to oldest (as they appear in the stream)
-        // We'll reverse them later when applying full_merge
-        let mut operands = vec![head.value.clone()];
-
-        loop {
-            let Some(peeked) = self.inner.peek() else {
-                // No more items
-                return Ok((operands, None));
-            };
-
-            let Ok(peeked) = peeked else {
-                // Error - return it
-                #[expect(
-                    clippy::expect_used,
-                    reason = "we just asserted, the peeked value is an error"
-                )]
-                return Err(self
-                    .inner
-                    .next()
-                    .expect("value should exist")
-                    .expect_err("should be error"));
-            };
+    /// Takes the next version of the given key, if there is one.
+    fn next_version_of(&mut self, key: &UserKey) -> crate::Result<Option<InternalValue>> {
+        self.inner
+            .next_if(|kv| match kv {
+                Ok(kv) => kv.key.user_key == key,
+                Err(_) => true,
+            })
+            .transpose()
+    }
 
-            // Different key - we're done collecting
-            if peeked.key.user_key != *user_key {
-                return Ok((operands, None));
+    /// Folds the merge operand `head` with the older versions of its key.
+    ///
+    /// `head` needs to be the oldest version of its key that has to be retained,
+    /// so all versions below it are not visible to any snapshot anymore.
+    ///
+    /// If no base value is reachable, neighbouring operands are combined using
+    /// [`MergeOperator::partial_merge`] instead.
+    ///
+    /// If the operands cannot be folded (no merge operator, merge failure or
+    /// the base value is a blob indirection), all operands are retained.
+    ///
+    /// The resulting versions are pushed into the pending queue.
+    fn fold_merge_operands(&mut self, head: InternalValue) -> crate::Result<()> {
+        let user_key = head.key.user_key.clone();
+
+        // NOTE: Versions below the head, from newest to oldest
+        let mut below = vec![];
+
+        // NOTE: `Some(None)` means the merge chain ended without a value
+        let mut base_value: Option<Option<InternalValue>> = None;
+
+        // NOTE: Versions that are deleted by a range tombstone below the head
+        // are not part of the merge chain
+        let barrier = self
+            .range_tombstones
+            .covering(&user_key)
+            .iter()
+            .map(|rt| rt.seqno)
+            .find(|&rt_seqno| rt_seqno <= head.key.seqno);
+
+        while let Some(next) = self.next_version_of(&user_key)? {
+            if barrier.is_some_and(|barrier| next.key.seqno < barrier) {
+                if let Some(watcher) = &mut self.expiration_callback {
+                    watcher.on_expired(&next);
+                }
+                base_value = Some(None);
+                break;
             }
 
-            // Same key - consume and check type
-            #[expect(clippy::expect_used, reason = "we just peeked, so next should work")]
-            let next = self.inner.next().expect("should exist")?;
-
             match next.key.value_type {
                 ValueType::Merge => {
-                    // Another merge operand - add to list
-                    operands.push(next.value.clone());
+                    below.push(next);
                 }
-                ValueType::Value | ValueType::Indirection => {
-                    // Base value found
-                    return Ok((operands, Some(next)));
+                ValueType::Value => {
+                    base_value = Some(Some(next));
+                    break;
                 }
                 ValueType::Tombstone | ValueType::WeakTombstone => {
-                    // Tombstone means no base value - treat as full_merge(None, operands)
-                    return Ok((operands, None));
+                    below.push(next);
+                    base_value = Some(None);
+                    break;
+                }
+                ValueType::Indirection => {
+                    // NOTE: We cannot read blobs here, so we cannot merge into the value
+                    self.pending.push_back(head);
+                    self.pending.extend(below);
+                    self.pending.push_back(next);
+                    return self.drain_key(&user_key);
                 }
             }
         }
-    }
 
-    /// Applies the merge operator to combine operands with an optional base value.
-    fn apply_merge(
-        &self,
-        user_key: &UserKey,
-        operands: Vec<UserValue>,
-        base_value: Option<&UserValue>,
-        seqno: SeqNo,
-    ) -> Option<InternalValue> {
-        let Some(merge_op) = &self.merge_operator else {
-            // No merge operator - this shouldn't happen, but keep the first operand
-            log::warn!(
-                "Merge operands found but no merge operator configured for key {:?}",
-                user_key
-            );
-            // Return the newest operand as-is
-            #[expect(clippy::expect_used, reason = "operands is non-empty")]
-            let value = operands.into_iter().next().expect("operands non-empty");
-            return Some(InternalValue::from_components(
-                user_key.clone(),
-                value,
-                seqno,
-                ValueType::Value,
-            ));
-        };
+        // NOTE: In the last level, there cannot be any older versions of this key
+        if base_value.is_none() && self.evict_tombstones {
+            base_value = Some(None);
+        }
 
-        // Operands are in newest-to-oldest order, reverse for oldest-to-newest
-        let operands_reversed: Vec<_> = operands.iter().rev().cloned().collect();
+        let merged = match (&self.merge_operator, &base_value) {
+            (Some(merge_op), Some(base)) => {
+                // NOTE: Operands need to be in oldest-to-newest order
+                let operands = below
+                    .iter()
+                    .rev()
+                    .filter(|kv| kv.key.value_type == ValueType::Merge)
+                    .map(|kv| kv.value.clone())
+                    .chain(std::iter::once(head.value.clone()))
+                    .collect::<Vec<UserValue>>();
+
+                Self::apply_merge(merge_op.as_ref(), &user_key, &operands, base.as_ref())
+            }
+            _ => None,
+        };
 
-        match merge_op.full_merge(user_key, base_value, &operands_reversed) {
-            MergeResult::Success(merged_value) => Some(InternalValue::from_components(
+        if let Some(merged) = merged {
+            let mut merged = InternalValue::from_components(
                 user_key.clone(),
-                merged_value,
-                seqno,
+                merged,
+                head.key.seqno,
                 ValueType::Value,
-            )),
+            );
+
+            if self.zero_seqnos && merged.key.seqno < self.gc_seqno_threshold {
+                merged.key.seqno = 0;
+            }
+
+            if let Some(watcher) = &mut self.expiration_callback {
+                for kv in below.iter().chain(base_value.iter().flatten()) {
+                    watcher.on_expired(kv);
+                }
+            }
+
+            self.pending.push_back(merged);
+        } else if let (Some(merge_op), None) = (&self.merge_operator, &base_value) {
+            // NOTE: Without a base value, `below` only contains merge operands,
+            // which can only be combined with each other
+            let operands = below.into_iter().rev().chain(std::iter::once(head));
+
+            let mut combined = Self::partial_merge_operands(merge_op.as_ref(), operands, |kv| {
+                if let Some(watcher) = &mut self.expiration_callback {
+                    watcher.on_expired(kv);
+                }
+            });
+
+            if let [single] = combined.as_mut_slice() {
+                if self.zero_seqnos && single.key.seqno < self.gc_seqno_threshold {
+                    single.key.seqno = 0;
+                }
+            }
+
+            self.pending.extend(combined.into_iter().rev());
+        } else {
+            self.pending.push_back(head);
+            self.pending.extend(below);
+            self.pending.extend(base_value.into_iter().flatten());
+        }
+
+        // NOTE: Everything below the base value is shadowed by it
+        self.drain_key(&user_key)
+    }
+
+    /// Combines neighbouring merge operands (oldest to newest) pairwise using
+    /// [`MergeOperator::partial_merge`].
+    ///
+    /// A combined operand takes the sequence number of its newest input.
+    /// If two operands cannot be combined, both are kept.
+    ///
+    /// Returns the resulting operands from oldest to newest; operands that were
+    /// absorbed into a newer one are passed to `on_absorbed`.
+    fn partial_merge_operands(
+        merge_op: &dyn MergeOperator,
+        operands: impl Iterator<Item = InternalValue>,
+        mut on_absorbed: impl FnMut(&InternalValue),
+    ) -> Vec<InternalValue> {
+        let mut combined: Vec<InternalValue> = vec![];
+
+        for operand in operands {
+            let Some(left) = combined.last_mut() else {
+                combined.push(operand);
+                continue;
+            };
+
+            if let Some(value) =
+                merge_op.partial_merge(&operand.key.user_key, &left.value, &operand.value)
+            {
+                on_absorbed(left);
+
+                *left = InternalValue::from_components(
+                    operand.key.user_key,
+                    value,
+                    operand.key.seqno,
+                    ValueType::Merge,
+                );
+            } else {
+                combined.push(operand);
+            }
+        }
+
+        combined
+    }
+
+    /// Applies the merge operator to combine operands (oldest to newest) with an optional base value.
+    ///
+    /// Returns `None` if the merge failed.
+    fn apply_merge(
+        merge_op: &dyn MergeOperator,
+        user_key: &UserKey,
+        operands: &[UserValue],
+        base_value: Option<&InternalValue>,
+    ) -> Option<UserValue> {
+        match merge_op.full_merge(user_key, base_value.map(|kv| &kv.value), operands) {
+            MergeResult::Success(merged_value) => Some(merged_value),
             MergeResult::Failure => {
-                // Merge failed - keep the newest operand to avoid data loss
+                // Merge failed - keep the operands to avoid data loss
                 log::warn!(
-                    "Merge operator '{}' failed for key {:?}, keeping newest operand",
+                    "Merge operator '{}' failed for key {user_key:?}, keeping operands",
                     merge_op.name(),
-                    user_key
                 );
-                #[expect(clippy::expect_used, reason = "operands is non-empty")]
-                let value = operands.into_iter().next().expect("operands non-empty");
-                Some(InternalValue::from_components(
-                    user_key.clone(),
-                    value,
-                    seqno,
-                    ValueType::Merge,
-                ))
+                None
             }
         }
     }
@@ -223,26 +345,20 @@ impl<I: Iterator<Item = Item>> Iterator for CompactionStream<'_, I> {
     type Item = Item;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.pending.pop_front() {
+            return Some(Ok(item));
+        }
+
         loop {
             let mut head = fail_iter!(self.inner.next()?);
 
-            // Handle merge operands
-            if head.key.value_type == ValueType::Merge {
-                let user_key = head.key.user_key.clone();
-                let head_seqno = head.key.seqno;
-
-                let (operands, base_value) = fail_iter!(self.collect_merge_operands(head));
-
-                let base_value_ref = base_value.as_ref().map(|v| &v.value);
-                let merged = self.apply_merge(&user_key, operands, base_value_ref, head_seqno);
-
-                if let Some(mut merged_item) = merged {
-                    if self.zero_seqnos && merged_item.key.seqno < self.gc_seqno_threshold {
-                        merged_item.key.seqno = 0;
-                    }
-                    return Some(Ok(merged_item));
+            // NOTE: The head (and thus all older versions) is deleted by a range tombstone
+            // that every snapshot can see
+            if self.is_range_deleted(&head) {
+                if let Some(watcher) = &mut self.expiration_callback {
+                    watcher.on_expired(&head);
                 }
-                // Merge produced nothing, continue to next item
+                fail_iter!(self.drain_key(&head.key.user_key));
                 continue;
             }
 
@@ -259,6 +375,16 @@ impl<I: Iterator<Item = Item>> Iterator for CompactionStream<'_, I> {
                         .expect_err("should be error")));
                 };
 
+                // NOTE: A merge operand depends on the versions below it,
+                // so it needs to be folded with them before they can be dropped
+                if head.key.value_type == ValueType::Merge
+                    && (peeked.key.user_key > head.key.user_key
+                        || peeked.key.seqno < self.gc_seqno_threshold)
+                {
+                    fail_iter!(self.fold_merge_operands(head));
+                    return self.pending.pop_front().map(Ok);
+                }
+
                 if peeked.key.user_key > head.key.user_key {
                     if head.is_tombstone() && self.evict_tombstones {
                         continue;
@@ -285,6 +411,9 @@ impl<I: Iterator<Item = Item>> Iterator for CompactionStream<'_, I> {
                         continue;
                     }
                 }
+            } else if head.key.value_type == ValueType::Merge {
+                fail_iter!(self.fold_merge_operands(head));
+                return self.pending.pop_front().map(Ok);
             } else if head.is_tombstone() && self.evict_tombstones {
                 continue;
             }
@@ -320,6 +449,7 @@ mod tests {
                     "V" => ValueType::Value,
                     "T" => ValueType::Tombstone,
                     "W" => ValueType::WeakTombstone,
+                    "M" => ValueType::Merge,
                     _ => panic!("Unknown value type"),
                 };
 
@@ -692,4 +822,304 @@ mod tests {
 
         Ok(())
     }
+
+    /// Concatenates operands onto the base value, fails on "!" operands
+    struct ConcatMerge;
+
+    impl MergeOperator for ConcatMerge {
+        fn name(&self) -> &'static str {
+            "ConcatMerge"
+        }
+
+        fn full_merge(
+            &self,
+            _key: &UserKey,
+            existing_value: Option<&UserValue>,
+            operands: &[UserValue],
+        ) -> MergeResult {
+            let mut v = existing_value.map(|v| v.to_vec()).unwrap_or_default();
+
+            for operand in operands {
+                if &**operand == b"!" {
+                    return MergeResult::Failure;
+                }
+                v.extend_from_slice(operand);
+            }
+
+            MergeResult::Success(v.into())
+        }
+    }
+
+    fn concat_merge() -> Option<Arc<dyn MergeOperator>> {
+        Some(Arc::new(ConcatMerge))
+    }
+
+    /// Like [`ConcatMerge`], but can also concatenate operands without a base value
+    struct PartialConcatMerge;
+
+    impl MergeOperator for PartialConcatMerge {
+        fn name(&self) -> &'static str {
+            "PartialConcatMerge"
+        }
+
+        fn full_merge(
+            &self,
+            key: &UserKey,
+            existing_value: Option<&UserValue>,
+            operands: &[UserValue],
+        ) -> MergeResult {
+            ConcatMerge.full_merge(key, existing_value, operands)
+        }
+
+        fn partial_merge(
+            &self,
+            _key: &UserKey,
+            left: &UserValue,
+            right: &UserValue,
+        ) -> Option<UserValue> {
+            if &**left == b"!" || &**right == b"!" {
+                return None;
+            }
+
+            Some([&**left, &**right].concat().into())
+        }
+    }
+
+    fn partial_concat_merge() -> Option<Arc<dyn MergeOperator>> {
+        Some(Arc::new(PartialConcatMerge))
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn compaction_stream_merge_fold() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "c", "M",
+          "a", "b", "M",
+          "a", "a", "V",
+          "a", "old", "V",
+          "b", "b", "M",
+          "b", "", "T",
+          "b", "old", "V",
+        ];
+
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 1_000).merge_operator(concat_merge());
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"abc", 999, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"b", *b"b", 999, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn compaction_stream_merge_fold_snapshot() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "d", "M",
+          "a", "c", "M",
+          "a", "b", "M",
+          "a", "a", "V",
+        ];
+
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 998).merge_operator(concat_merge());
+
+        // NOTE: Operands >= watermark are still visible to snapshots
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"d", 999, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"abc", 998, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn compaction_stream_merge_no_base() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "b", "M",
+          "a", "a", "M",
+        ];
+
+        // NOTE: There may be a base value in a lower level, so we cannot fold
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 1_000).merge_operator(concat_merge());
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"b", 999, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"a", 998, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        // NOTE: In the last level, there is nothing below
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 1_000)
+            .merge_operator(concat_merge())
+            .evict_tombstones(true);
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"ab", 999, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn compaction_stream_merge_failure() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "!", "M",
+          "a", "a", "V",
+          "a", "old", "V",
+        ];
+
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 1_000).merge_operator(concat_merge());
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"!", 999, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"a", 998, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn compaction_stream_merge_no_operator() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "b", "M",
+          "a", "a", "V",
+          "a", "old", "V",
+        ];
+
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 1_000);
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"b", 999, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"a", 998, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn compaction_stream_merge_partial() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "c", "M",
+          "a", "b", "M",
+          "a", "a", "M",
+          "b", "b", "M",
+        ];
+
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 1_000).merge_operator(partial_concat_merge());
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"abc", 999, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"b", *b"b", 999, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn compaction_stream_merge_partial_snapshot() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "d", "M",
+          "a", "c", "M",
+          "a", "b", "M",
+          "a", "a", "M",
+        ];
+
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 998).merge_operator(partial_concat_merge());
+
+        // NOTE: Operands >= watermark are still visible to snapshots
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"d", 999, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"abc", 998, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn compaction_stream_merge_partial_not_possible() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "c", "M",
+          "a", "!", "M",
+          "a", "b", "M",
+          "a", "a", "M",
+        ];
+
+        let iter = vec.iter().cloned().map(Ok);
+        let mut iter = CompactionStream::new(iter, 1_000).merge_operator(partial_concat_merge());
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"c", 999, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"!", 998, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"ab", 997, ValueType::Merge),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,22 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{AbstractTree, AnyTree, SequenceNumberCounter};
+
+/// Flushes `count` tables that all overlap each other
+pub fn flush_overlapping_tables(
+    tree: &AnyTree,
+    seqno: &SequenceNumberCounter,
+    count: u8,
+) -> crate::Result<()> {
+    for i in 0..count {
+        // NOTE: Tables need to overlap
+        let seqno = seqno.next();
+        tree.insert("a", "v", seqno);
+        tree.insert([b'k', i].as_slice(), "v", seqno);
+        tree.insert("z", "v", seqno);
+        tree.flush_active_memtable(0)?;
+    }
+    Ok(())
+}
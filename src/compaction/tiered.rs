@@ -2,16 +2,26 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use super::{Choice, CompactionStrategy};
-use crate::{compaction::state::CompactionState, version::Version, Config};
-
-// fn desired_level_size_in_bytes(level_idx: u8, ratio: u8, base_size: u32) -> usize {
-//     (ratio as usize).pow(u32::from(level_idx + 1)) * (base_size as usize)
-// }
+use super::{Choice, CompactionStrategy, Input as CompactionInput};
+use crate::{compaction::state::CompactionState, version::Version, Config, HashSet, KvPair, Table};
+
+#[doc(hidden)]
+pub const NAME: &str = "TieredCompaction";
+
+fn desired_level_size_in_bytes(level_idx: usize, ratio: u8, base_size: u32) -> u64 {
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "there are never more than 255 levels"
+    )]
+    u64::from(ratio)
+        .saturating_pow(level_idx as u32 + 1)
+        .saturating_mul(u64::from(base_size))
+}
 
 /// Size-tiered compaction strategy (STCS)
 ///
-/// If a level reaches a threshold, it is merged into a larger table to the next level.
+/// If a level reaches a threshold, its oldest runs of similar size are merged
+/// into a larger table to the next level.
 ///
 /// STCS suffers from high read and temporary doubled space amplification, but has good write amplification.
 #[derive(Clone)]
@@ -27,6 +37,18 @@ pub struct Strategy {
     /// A level target size is: base_size * level_ratio.pow(#level + 1)
     #[expect(clippy::doc_markdown)]
     pub level_ratio: u8,
+
+    /// Runs smaller than `bucket_low` times the average size of
+    /// the current bucket are not merged with it.
+    ///
+    /// Same as `bucket_low` in Cassandra's STCS.
+    pub bucket_low: f32,
+
+    /// Runs larger than `bucket_high` times the average size of
+    /// the current bucket are not merged with it.
+    ///
+    /// Same as `bucket_high` in Cassandra's STCS.
+    pub bucket_high: f32,
 }
 
 impl Strategy {
@@ -36,8 +58,22 @@ impl Strategy {
         Self {
             base_size,
             level_ratio,
+            ..Default::default()
         }
     }
+
+    /// Returns `true` if a run is within the size window of the current bucket.
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "precision loss is acceptable for bucketing"
+    )]
+    fn fits_bucket(&self, run_size: u64, bucket_size: u64, bucket_runs: u64) -> bool {
+        let avg = bucket_size as f64 / bucket_runs as f64;
+        let run_size = run_size as f64;
+
+        run_size >= avg * f64::from(self.bucket_low)
+            && run_size <= avg * f64::from(self.bucket_high)
+    }
 }
 
 impl Default for Strategy {
@@ -45,299 +81,355 @@ impl Default for Strategy {
         Self {
             base_size: 64 * 1_024 * 1_024,
             level_ratio: 4,
+            bucket_low: 0.5,
+            bucket_high: 1.5,
         }
     }
 }
 
 impl CompactionStrategy for Strategy {
     fn get_name(&self) -> &'static str {
-        "TieredStrategy"
+        NAME
     }
 
-    fn choose(&self, version: &Version, _config: &Config, state: &CompactionState) -> Choice {
-        unimplemented!()
+    fn get_config(&self) -> Vec<KvPair> {
+        vec![
+            (
+                crate::UserKey::from("tiered_base_size"),
+                crate::UserValue::from(self.base_size.to_le_bytes()),
+            ),
+            (
+                crate::UserKey::from("tiered_level_ratio"),
+                crate::UserValue::from(self.level_ratio.to_le_bytes()),
+            ),
+            (
+                crate::UserKey::from("tiered_bucket_low"),
+                crate::UserValue::from(self.bucket_low.to_le_bytes()),
+            ),
+            (
+                crate::UserKey::from("tiered_bucket_high"),
+                crate::UserValue::from(self.bucket_high.to_le_bytes()),
+            ),
+        ]
+    }
+
+    fn choose(&self, version: &Version, _: &Config, state: &CompactionState) -> Choice {
+        let last_level_idx = version.level_count() - 1;
+
+        // NOTE: Deeper levels are compacted first, so merging a level
+        // does not immediately push the next level over its threshold
+        for level_idx in (0..last_level_idx).rev() {
+            let Some(level) = version.level(level_idx) else {
+                continue;
+            };
+
+            if level.is_empty() || version.level_is_busy(level_idx, state.hidden_set()) {
+                continue;
+            }
+
+            let desired_size =
+                desired_level_size_in_bytes(level_idx, self.level_ratio, self.base_size);
+
+            if level.size() < desired_size {
+                continue;
+            }
+
+            // NOTE: Only the oldest runs can be pushed into the next level,
+            // because the next level may only contain data that is older
+            // than the runs that stay in this level
+            //
+            // The oldest run starts the bucket, and newer runs are added
+            // as long as they are of similar size, so a large run is not
+            // rewritten just because some small runs were flushed after it
+            let mut table_ids = HashSet::default();
+            let mut picked_size = 0;
+            let mut picked_runs = 0;
+
+            for run in level.iter().rev() {
+                let run_size = run.iter().map(Table::file_size).sum::<u64>();
+
+                if picked_runs > 0 && !self.fits_bucket(run_size, picked_size, picked_runs) {
+                    break;
+                }
+
+                table_ids.extend(run.iter().map(Table::id));
+                picked_size += run_size;
+                picked_runs += 1;
+
+                if picked_size >= desired_size {
+                    break;
+                }
+            }
+
+            let dest_level = level_idx + 1;
+
+            // NOTE: Tombstones are evicted when merging into the last level,
+            // so the last level needs to be merged completely,
+            // otherwise the older runs in it would resurrect deleted data
+            let merges_into_last_level = dest_level == last_level_idx
+                && version
+                    .level(dest_level)
+                    .is_some_and(|level| !level.is_empty());
+
+            if merges_into_last_level {
+                if version.level_is_busy(dest_level, state.hidden_set()) {
+                    continue;
+                }
+
+                if let Some(level) = version.level(dest_level) {
+                    table_ids.extend(level.list_ids());
+                }
+            }
+
+            #[expect(
+                clippy::cast_possible_truncation,
+                reason = "there are never more than 255 levels"
+            )]
+            let input = CompactionInput {
+                table_ids,
+                dest_level: dest_level as u8,
+                canonical_level: dest_level as u8,
+                target_size: u64::MAX,
+            };
+
+            // NOTE: A single run does not need to be rewritten
+            return if picked_runs == 1 && !merges_into_last_level {
+                Choice::Move(input)
+            } else {
+                Choice::Merge(input)
+            };
+        }
+
+        Choice::DoNothing
     }
 }
-/*
+
 #[cfg(test)]
 mod tests {
     use super::Strategy;
     use crate::{
-        cache::Cache,
-        compaction::{Choice, CompactionStrategy, Input as CompactionInput},
-        config::Config,
-        descriptor_table::FileDescriptorTable,
-        file::LEVELS_MANIFEST_FILE,
-        level_manifest::LevelManifest,
-        segment::{
-            block::offset::BlockOffset,
-            block_index::{two_level_index::TwoLevelBlockIndex, BlockIndexImpl},
-            file_offsets::FileOffsets,
-            meta::{Metadata, SegmentId},
-            SegmentInner,
-        },
-        super_segment::Segment,
-        HashSet, KeyRange, SeqNo,
+        compaction::test_util::flush_overlapping_tables, AbstractTree, Config,
+        SequenceNumberCounter,
     };
-    use std::sync::{atomic::AtomicBool, Arc};
+    use std::sync::Arc;
     use test_log::test;
 
-    #[allow(clippy::expect_used)]
-    fn fixture_segment(id: SegmentId, size_mib: u64, max_seqno: SeqNo) -> Segment {
-        todo!()
-
-        /* let cache = Arc::new(Cache::with_capacity_bytes(10 * 1_024 * 1_024));
-
-        let block_index = TwoLevelBlockIndex::new((0, id).into(), cache.clone());
-        let block_index = Arc::new(BlockIndexImpl::TwoLevel(block_index));
-
-        SegmentInner {
-            tree_id: 0,
-            descriptor_table: Arc::new(FileDescriptorTable::new(512, 1)),
-            block_index,
-
-            offsets: FileOffsets {
-                bloom_ptr: BlockOffset(0),
-                range_filter_ptr: BlockOffset(0),
-                index_block_ptr: BlockOffset(0),
-                metadata_ptr: BlockOffset(0),
-                range_tombstones_ptr: BlockOffset(0),
-                tli_ptr: BlockOffset(0),
-                pfx_ptr: BlockOffset(0),
-            },
-
-            metadata: Metadata {
-                data_block_count: 0,
-                index_block_count: 0,
-                data_block_size: 4_096,
-                index_block_size: 4_096,
-                created_at: 0,
-                id,
-                file_size: size_mib * 1_024 * 1_024,
-                compression: crate::segment::meta::CompressionType::None,
-                table_type: crate::segment::meta::TableType::Block,
-                item_count: 0,
-                key_count: 0,
-                key_range: KeyRange::new((vec![].into(), vec![].into())),
-                tombstone_count: 0,
-                range_tombstone_count: 0,
-                uncompressed_size: size_mib * 1_024 * 1_024,
-                seqnos: (0, max_seqno),
-            },
-            cache,
-
-            bloom_filter: Some(BloomFilter::with_fp_rate(1, 0.1)),
-
-            path: "a".into(),
-            is_deleted: AtomicBool::default(),
+    fn table_size(tree: &crate::AnyTree) -> u32 {
+        tree.current_version()
+            .iter_tables()
+            .map(crate::Table::file_size)
+            .max()
+            .and_then(|x| u32::try_from(x).ok())
+            .unwrap_or_default()
+    }
+
+    fn flush_run(tree: &crate::AnyTree, item_count: u32, seqno: crate::SeqNo) -> crate::Result<()> {
+        for key in 0..item_count {
+            tree.insert(key.to_be_bytes(), "v", seqno);
         }
-        .into() */
+        tree.flush_active_memtable(0)?;
+        Ok(())
+    }
+
+    fn l0_target(tree: &crate::AnyTree) -> Arc<Strategy> {
+        // NOTE: L0 target size = base_size * 2, so the level has just reached it
+        let level_size = tree.current_version().l0().size();
+        let base_size = u32::try_from(level_size / 2).expect("should fit");
+        Arc::new(Strategy::new(base_size, 2))
     }
 
     #[test]
     fn tiered_empty_levels() -> crate::Result<()> {
-        let tempdir = tempfile::tempdir()?;
-
-        let compactor = Strategy {
-            base_size: 8 * 1_024 * 1_024,
-            level_ratio: 8,
-        };
-
-        let levels = LevelManifest::create_new(4, tempdir.path().join(LEVELS_MANIFEST_FILE))?;
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
 
-        assert_eq!(
-            compactor.choose(&levels, &Config::default()),
-            Choice::DoNothing
-        );
+        tree.compact(Arc::new(Strategy::new(1, 4)), 0)?;
 
+        assert_eq!(0, tree.table_count());
         Ok(())
     }
 
     #[test]
-    fn tiered_default_l0() -> crate::Result<()> {
-        let tempdir = tempfile::tempdir()?;
-
-        let compactor = Strategy {
-            base_size: 8 * 1_024 * 1_024,
-            level_ratio: 4,
-        };
-        let config = Config::default();
+    fn tiered_l0_below_limit() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
 
-        let mut levels = LevelManifest::create_new(4, tempdir.path().join(LEVELS_MANIFEST_FILE))?;
+        flush_overlapping_tables(&tree, &SequenceNumberCounter::default(), 3)?;
 
-        levels.add(fixture_segment(1, 8, 5));
-        assert_eq!(compactor.choose(&levels, &config), Choice::DoNothing);
-
-        levels.add(fixture_segment(2, 8, 6));
-        assert_eq!(compactor.choose(&levels, &config), Choice::DoNothing);
-
-        levels.add(fixture_segment(3, 8, 7));
-        assert_eq!(compactor.choose(&levels, &config), Choice::DoNothing);
-
-        levels.add(fixture_segment(4, 8, 8));
-
-        assert_eq!(
-            compactor.choose(&levels, &config),
-            Choice::Merge(CompactionInput {
-                dest_level: 1,
-                segment_ids: set![1, 2, 3, 4],
-                target_size: u64::MAX,
-            })
-        );
+        let base_size = table_size(&tree);
+        tree.compact(Arc::new(Strategy::new(base_size, 4)), 0)?;
 
+        assert_eq!(Some(3), tree.level_table_count(0));
         Ok(())
     }
 
     #[test]
-    fn tiered_ordering() -> crate::Result<()> {
-        let tempdir = tempfile::tempdir()?;
-
-        let compactor = Strategy {
-            base_size: 8 * 1_024 * 1_024,
-            level_ratio: 2,
-        };
-        let config = Config::default();
-
-        let mut levels = LevelManifest::create_new(4, tempdir.path().join(LEVELS_MANIFEST_FILE))?;
-
-        levels.add(fixture_segment(1, 8, 0));
-        levels.add(fixture_segment(2, 8, 1));
-        levels.add(fixture_segment(3, 8, 2));
-        levels.add(fixture_segment(4, 8, 3));
-
-        assert_eq!(
-            compactor.choose(&levels, &config),
-            Choice::Merge(CompactionInput {
-                dest_level: 1,
-                segment_ids: set![1, 2],
-                target_size: u64::MAX,
-            })
-        );
-
+    fn tiered_l0_reached_limit() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        flush_overlapping_tables(&tree, &SequenceNumberCounter::default(), 4)?;
+
+        let base_size = table_size(&tree);
+        tree.compact(Arc::new(Strategy::new(base_size, 4)), 0)?;
+
+        assert_eq!(Some(0), tree.level_table_count(0));
+        assert_eq!(Some(1), tree.level_table_count(1));
+        assert_eq!(6, tree.len(crate::SeqNo::MAX, None)?);
         Ok(())
     }
 
     #[test]
-    fn tiered_more_than_min() -> crate::Result<()> {
-        let tempdir = tempfile::tempdir()?;
+    fn tiered_merges_oldest_runs() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
 
-        let compactor = Strategy {
-            base_size: 8 * 1_024 * 1_024,
-            level_ratio: 4,
-        };
-        let config = Config::default();
+        flush_overlapping_tables(&tree, &SequenceNumberCounter::default(), 4)?;
 
-        let mut levels = LevelManifest::create_new(4, tempdir.path().join(LEVELS_MANIFEST_FILE))?;
-        levels.add(fixture_segment(1, 8, 5));
-        levels.add(fixture_segment(2, 8, 6));
-        levels.add(fixture_segment(3, 8, 7));
-        levels.add(fixture_segment(4, 8, 8));
+        let base_size = table_size(&tree);
+        tree.compact(Arc::new(Strategy::new(base_size, 2)), 0)?;
 
-        levels.insert_into_level(1, fixture_segment(5, 8 * 4, 9));
-        levels.insert_into_level(1, fixture_segment(6, 8 * 4, 10));
-        levels.insert_into_level(1, fixture_segment(7, 8 * 4, 11));
-        levels.insert_into_level(1, fixture_segment(8, 8 * 4, 12));
+        assert_eq!(Some(2), tree.level_table_count(0));
+        assert_eq!(Some(1), tree.level_table_count(1));
 
-        assert_eq!(
-            compactor.choose(&levels, &config),
-            Choice::Merge(CompactionInput {
-                dest_level: 2,
-                segment_ids: set![5, 6, 7, 8],
-                target_size: u64::MAX,
-            })
-        );
+        // NOTE: The newest versions are still in L0
+        let l0_ids = tree.current_version().l0().list_ids();
+        assert!(l0_ids.contains(&2));
+        assert!(l0_ids.contains(&3));
 
         Ok(())
     }
 
     #[test]
-    fn tiered_many_segments() -> crate::Result<()> {
-        let tempdir = tempfile::tempdir()?;
-
-        let compactor = Strategy {
-            base_size: 8 * 1_024 * 1_024,
-            level_ratio: 2,
-        };
-        let config = Config::default();
-
-        let mut levels = LevelManifest::create_new(4, tempdir.path().join(LEVELS_MANIFEST_FILE))?;
-        levels.add(fixture_segment(1, 8, 5));
-        levels.add(fixture_segment(2, 8, 6));
-        levels.add(fixture_segment(3, 8, 7));
-        levels.add(fixture_segment(4, 8, 8));
-
+    fn tiered_buckets_skip_newer_large_run() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        flush_run(&tree, 10, 0)?;
+        flush_run(&tree, 10, 1)?;
+        flush_run(&tree, 10_000, 2)?;
+        assert_eq!(3, tree.l0_run_count());
+
+        tree.compact(l0_target(&tree), 0)?;
+
+        // NOTE: Only the two small runs are of similar size
+        assert_eq!(Some(1), tree.level_table_count(0));
+        assert_eq!(Some(1), tree.level_table_count(1));
         assert_eq!(
-            compactor.choose(&levels, &config),
-            Choice::Merge(CompactionInput {
-                dest_level: 1,
-                segment_ids: set![1, 2],
-                target_size: u64::MAX,
-            })
+            vec![2],
+            tree.current_version()
+                .l0()
+                .list_ids()
+                .into_iter()
+                .collect::<Vec<_>>()
         );
 
         Ok(())
     }
 
     #[test]
-    fn tiered_deeper_level() -> crate::Result<()> {
-        let tempdir = tempfile::tempdir()?;
-
-        let compactor = Strategy {
-            base_size: 8 * 1_024 * 1_024,
-            level_ratio: 2,
-        };
-        let config = Config::default();
+    fn tiered_buckets_do_not_rewrite_large_run() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        flush_run(&tree, 10_000, 0)?;
+        flush_run(&tree, 10, 1)?;
+        flush_run(&tree, 10, 2)?;
+        assert_eq!(3, tree.l0_run_count());
+
+        tree.compact(l0_target(&tree), 0)?;
+
+        // NOTE: The large run is moved on its own, the small runs stay in L0
+        assert_eq!(Some(2), tree.level_table_count(0));
+        assert_eq!(Some(1), tree.level_table_count(1));
+        assert!(tree
+            .current_version()
+            .level(1)
+            .expect("should exist")
+            .list_ids()
+            .contains(&0));
 
-        let mut levels = LevelManifest::create_new(4, tempdir.path().join(LEVELS_MANIFEST_FILE))?;
-        levels.add(fixture_segment(1, 8, 5));
-
-        levels.insert_into_level(1, fixture_segment(2, 8 * 2, 6));
-        levels.insert_into_level(1, fixture_segment(3, 8 * 2, 7));
-
-        assert_eq!(
-            compactor.choose(&levels, &config),
-            Choice::Merge(CompactionInput {
-                dest_level: 2,
-                segment_ids: set![2, 3],
-                target_size: u64::MAX,
-            })
-        );
+        Ok(())
+    }
 
-        let tempdir = tempfile::tempdir()?;
-        let mut levels = LevelManifest::create_new(4, tempdir.path().join(LEVELS_MANIFEST_FILE))?;
+    #[test]
+    fn tiered_moves_single_run() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        for i in 0..4u8 {
+            tree.insert([b'k', i].as_slice(), "v", u64::from(i));
+            tree.flush_active_memtable(0)?;
+        }
 
-        levels.insert_into_level(2, fixture_segment(2, 8 * 4, 5));
-        levels.insert_into_level(2, fixture_segment(3, 8 * 4, 6));
+        // NOTE: Disjoint tables form a single run
+        assert_eq!(1, tree.l0_run_count());
 
-        assert_eq!(
-            compactor.choose(&levels, &config),
-            Choice::Merge(CompactionInput {
-                dest_level: 3,
-                segment_ids: set![2, 3],
-                target_size: u64::MAX,
-            })
-        );
+        tree.compact(Arc::new(Strategy::new(1, 2)), 0)?;
 
+        assert_eq!(Some(0), tree.level_table_count(0));
+        assert_eq!(Some(4), tree.level_table_count(1));
         Ok(())
     }
 
     #[test]
-    fn tiered_last_level() -> crate::Result<()> {
-        let tempdir = tempfile::tempdir()?;
-
-        let compactor = Strategy {
-            base_size: 8 * 1_024 * 1_024,
-            level_ratio: 2,
-        };
-        let config = Config::default();
-
-        let mut levels = LevelManifest::create_new(4, tempdir.path().join(LEVELS_MANIFEST_FILE))?;
-        levels.insert_into_level(3, fixture_segment(2, 8, 5));
-        levels.insert_into_level(3, fixture_segment(3, 8, 5));
-
-        assert_eq!(compactor.choose(&levels, &config), Choice::DoNothing);
+    fn tiered_last_level_is_merged_completely() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        tree.insert("a", "v", 0);
+        tree.flush_active_memtable(0)?;
+        tree.remove("a", 1);
+        tree.flush_active_memtable(0)?;
+
+        // NOTE: Push everything into the last level, run by run
+        let strategy = Arc::new(Strategy::new(1, 2));
+        for _ in 0..20 {
+            tree.compact(strategy.clone(), 2)?;
+        }
 
+        assert_eq!(0, tree.table_count());
+        assert!(!tree.contains_key("a", crate::SeqNo::MAX)?);
         Ok(())
     }
 }
- */
@@ -0,0 +1,450 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use super::{Choice, CompactionStrategy, Input as CompactionInput};
+use crate::{
+    compaction::state::CompactionState, config::Config, table::Table, time::unix_timestamp,
+    version::Version, HashSet, KvPair,
+};
+
+#[doc(hidden)]
+pub const NAME: &str = "TimeWindowCompaction";
+
+/// Time-window compaction strategy (TWCS)
+///
+/// Groups tables into fixed wall-clock windows of `window_seconds`, by their creation time.
+///
+/// Tables of the current window are kept in L0, and similarly sized tables are merged
+/// once there are `merge_threshold` of them (size-tiering).
+///
+/// Once a window has passed, its tables are merged into a single, frozen run in L1,
+/// which is never compacted again, and can be dropped as a whole once it
+/// is older than the TTL.
+///
+/// ###### Caution
+///
+/// Only use it for specific workloads where:
+///
+/// 1) Data is inserted in (roughly) chronological order (e.g. time series, metrics)
+/// 2) Data is not updated or deleted, except through the TTL
+///
+/// Tombstones are never evicted, as frozen windows are never merged with each other.
+#[derive(Clone)]
+pub struct Strategy {
+    /// Size of a time window in seconds
+    pub window_seconds: u64,
+
+    /// TTL in seconds, will be disabled if 0 or None
+    ///
+    /// The TTL is measured from the creation time of a table.
+    /// Windows with multiple tables are merged when they are frozen, which
+    /// resets the creation time, while single tables are moved and keep it.
+    /// Either way, data is dropped after at least `ttl_seconds`,
+    /// and at most `window_seconds + ttl_seconds`.
+    pub ttl_seconds: Option<u64>,
+
+    /// Minimum number of similarly sized tables in the current window to merge
+    pub merge_threshold: u8,
+
+    /// The target table size on disk (possibly compressed)
+    pub target_size: u64,
+}
+
+impl Strategy {
+    /// Configures a new `TimeWindow` compaction strategy
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_seconds` is 0.
+    #[must_use]
+    pub fn new(window_seconds: u64, ttl_seconds: Option<u64>) -> Self {
+        assert!(
+            window_seconds > 0,
+            "window size should be at least 1 second"
+        );
+
+        Self {
+            window_seconds,
+            ttl_seconds,
+            merge_threshold: 4,
+            target_size: /* 64 MiB */ 64 * 1_024 * 1_024,
+        }
+    }
+
+    /// Sets the minimum number of similarly sized tables in the current window to merge.
+    ///
+    /// Default = 4
+    #[must_use]
+    pub fn with_merge_threshold(mut self, threshold: u8) -> Self {
+        self.merge_threshold = threshold;
+        self
+    }
+
+    /// Sets the table target size on disk (possibly compressed).
+    ///
+    /// Default = 64 MiB
+    #[must_use]
+    pub fn with_table_target_size(mut self, bytes: u64) -> Self {
+        self.target_size = bytes;
+        self
+    }
+
+    fn window_of(&self, timestamp_nanos: u128) -> u128 {
+        timestamp_nanos / (u128::from(self.window_seconds.max(1)) * 1_000_000_000)
+    }
+}
+
+impl CompactionStrategy for Strategy {
+    fn get_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn get_config(&self) -> Vec<KvPair> {
+        vec![
+            (
+                crate::UserKey::from("time_window_seconds"),
+                crate::UserValue::from(self.window_seconds.to_le_bytes()),
+            ),
+            (
+                crate::UserKey::from("time_window_ttl_seconds"),
+                crate::UserValue::from(self.ttl_seconds.map(u64::to_le_bytes).unwrap_or_default()),
+            ),
+            (
+                crate::UserKey::from("time_window_merge_threshold"),
+                crate::UserValue::from(self.merge_threshold.to_le_bytes()),
+            ),
+            (
+                crate::UserKey::from("time_window_target_size"),
+                crate::UserValue::from(self.target_size.to_le_bytes()),
+            ),
+        ]
+    }
+
+    fn choose(&self, version: &Version, _: &Config, state: &CompactionState) -> Choice {
+        let now = unix_timestamp().as_nanos();
+
+        // Drop expired tables
+        if let Some(ttl_seconds) = self.ttl_seconds.filter(|&s| s > 0) {
+            let cutoff = now.saturating_sub(u128::from(ttl_seconds) * 1_000_000_000u128);
+
+            let ids_to_drop = version
+                .iter_tables()
+                .filter(|table| u128::from(table.metadata.created_at) <= cutoff)
+                .map(Table::id)
+                .collect::<HashSet<_>>();
+
+            if !ids_to_drop.is_empty() && !state.hidden_set().is_blocked(ids_to_drop.clone()) {
+                return Choice::Drop(ids_to_drop);
+            }
+        }
+
+        let current_window = self.window_of(now);
+
+        // NOTE: Sort L0 tables from oldest to newest data,
+        // so we only ever compact the oldest or newest tables,
+        // otherwise the compacted table could overtake newer data
+        let mut tables = version
+            .l0()
+            .iter()
+            .flat_map(|run| run.iter())
+            .collect::<Vec<_>>();
+
+        tables.sort_by_key(|table| table.get_highest_seqno());
+
+        let Some(oldest_table) = tables.first() else {
+            return Choice::DoNothing;
+        };
+
+        // Freeze the oldest window, if it has passed
+        let oldest_window = self.window_of(oldest_table.metadata.created_at.into());
+
+        if oldest_window < current_window {
+            // NOTE: Also take any table that is interleaved with the window's tables
+            let window_len = tables
+                .iter()
+                .rposition(|table| {
+                    self.window_of(table.metadata.created_at.into()) == oldest_window
+                })
+                .map_or(1, |idx| idx + 1);
+
+            let table_ids = tables
+                .iter()
+                .take(window_len)
+                .map(|table| table.id())
+                .collect::<HashSet<_>>();
+
+            if state.hidden_set().is_blocked(table_ids.iter().copied()) {
+                return Choice::DoNothing;
+            }
+
+            let input = CompactionInput {
+                table_ids,
+                dest_level: 1,
+                canonical_level: 1,
+                target_size: self.target_size,
+            };
+
+            return if window_len == 1 {
+                Choice::Move(input)
+            } else {
+                Choice::Merge(input)
+            };
+        }
+
+        // Size-tier the newest tables of the current window
+        let mut bucket: Vec<&Table> = vec![];
+        let mut bucket_size = 0u64;
+
+        for table in tables.iter().rev() {
+            let size = table.file_size();
+
+            if let Some(avg) = bucket_size.checked_div(bucket.len() as u64) {
+                // NOTE: Tables are similarly sized if they are within 50% of the average
+                if size < avg / 2 || size > avg + avg / 2 {
+                    break;
+                }
+            }
+
+            bucket.push(table);
+            bucket_size += size;
+        }
+
+        if bucket.len() < usize::from(self.merge_threshold.max(2)) {
+            return Choice::DoNothing;
+        }
+
+        let table_ids = bucket
+            .iter()
+            .map(|table| table.id())
+            .collect::<HashSet<_>>();
+
+        if state.hidden_set().is_blocked(table_ids.iter().copied()) {
+            return Choice::DoNothing;
+        }
+
+        Choice::Merge(CompactionInput {
+            table_ids,
+            dest_level: 0,
+            canonical_level: 0,
+            target_size: self.target_size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Strategy;
+    use crate::{AbstractTree, Config, SeqNo, SequenceNumberCounter};
+    use std::{sync::Arc, time::Duration};
+    use test_log::test;
+
+    fn set_time(seconds: u64) {
+        crate::time::set_unix_timestamp_for_test(Some(Duration::from_secs(seconds)));
+    }
+
+    fn flush_overlapping_table(tree: &crate::AnyTree, seqno: SeqNo) -> crate::Result<()> {
+        // NOTE: Tables need to overlap
+        tree.insert("a", seqno.to_be_bytes(), seqno);
+        tree.insert("z", seqno.to_be_bytes(), seqno);
+        tree.flush_active_memtable(0)?;
+        Ok(())
+    }
+
+    #[test]
+    fn time_window_empty_levels() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        tree.compact(Arc::new(Strategy::new(3_600, Some(1))), 0)?;
+
+        assert_eq!(0, tree.table_count());
+        Ok(())
+    }
+
+    #[test]
+    fn time_window_tiers_current_window() -> crate::Result<()> {
+        let _clock = crate::time::lock_clock_for_test();
+
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        set_time(1_000);
+
+        let strategy = Arc::new(Strategy::new(3_600, None));
+
+        for seqno in 0..3 {
+            flush_overlapping_table(&tree, seqno)?;
+        }
+
+        tree.compact(strategy.clone(), 0)?;
+        assert_eq!(Some(3), tree.level_table_count(0));
+
+        flush_overlapping_table(&tree, 3)?;
+
+        tree.compact(strategy, 0)?;
+        assert_eq!(Some(1), tree.level_table_count(0));
+        assert_eq!(Some(0), tree.level_table_count(1));
+        assert_eq!(Some(3u64.to_be_bytes().into()), tree.get("a", SeqNo::MAX)?);
+
+        crate::time::set_unix_timestamp_for_test(None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_window_freezes_old_windows() -> crate::Result<()> {
+        let _clock = crate::time::lock_clock_for_test();
+
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        let strategy = Arc::new(Strategy::new(3_600, None));
+
+        set_time(1_000);
+        flush_overlapping_table(&tree, 0)?;
+        flush_overlapping_table(&tree, 1)?;
+
+        set_time(4_000);
+        flush_overlapping_table(&tree, 2)?;
+
+        tree.compact(strategy.clone(), 0)?;
+
+        // NOTE: The first window is frozen into a single run
+        let version = tree.current_version();
+        assert_eq!(1, version.l0().table_count());
+        assert_eq!(Some(1), version.level(1).map(|level| level.run_count()));
+        assert_eq!(Some(1), tree.level_table_count(1));
+
+        // NOTE: The newer window still shadows the frozen window
+        assert_eq!(Some(2u64.to_be_bytes().into()), tree.get("a", SeqNo::MAX)?);
+
+        set_time(8_000);
+        flush_overlapping_table(&tree, 3)?;
+
+        tree.compact(strategy.clone(), 0)?;
+
+        // NOTE: Frozen windows are never merged with each other
+        let version = tree.current_version();
+        assert_eq!(1, version.l0().table_count());
+        assert_eq!(Some(2), tree.level_table_count(1));
+        assert_eq!(Some(2), version.level(1).map(|level| level.run_count()));
+
+        let version_id = version.id();
+        tree.compact(strategy, 0)?;
+        assert_eq!(version_id, tree.current_version().id());
+
+        assert_eq!(Some(3u64.to_be_bytes().into()), tree.get("a", SeqNo::MAX)?);
+
+        crate::time::set_unix_timestamp_for_test(None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_window_ttl() -> crate::Result<()> {
+        let _clock = crate::time::lock_clock_for_test();
+
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        let strategy = Arc::new(Strategy::new(3_600, Some(3_600)));
+
+        set_time(1_000);
+        flush_overlapping_table(&tree, 0)?;
+        flush_overlapping_table(&tree, 1)?;
+
+        // NOTE: Window is frozen at t=4000s
+        set_time(4_000);
+        tree.compact(strategy.clone(), 0)?;
+        assert_eq!(Some(1), tree.level_table_count(1));
+
+        set_time(7_300);
+        flush_overlapping_table(&tree, 2)?;
+
+        set_time(7_500);
+        tree.compact(strategy.clone(), 0)?;
+        assert_eq!(2, tree.table_count());
+
+        // NOTE: The frozen window expired
+        set_time(7_700);
+        tree.compact(strategy, 0)?;
+        assert_eq!(Some(0), tree.level_table_count(1));
+        assert_eq!(Some(1), tree.level_table_count(0));
+
+        crate::time::set_unix_timestamp_for_test(None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_window_ttl_single_table() -> crate::Result<()> {
+        let _clock = crate::time::lock_clock_for_test();
+
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        let strategy = Arc::new(Strategy::new(3_600, Some(3_600)));
+
+        set_time(1_000);
+        flush_overlapping_table(&tree, 0)?;
+
+        // NOTE: The single table is moved, so it keeps its creation time
+        set_time(4_000);
+        tree.compact(strategy.clone(), 0)?;
+        assert_eq!(Some(1), tree.level_table_count(1));
+
+        set_time(4_500);
+        tree.compact(strategy.clone(), 0)?;
+        assert_eq!(Some(1), tree.level_table_count(1));
+
+        set_time(4_700);
+        tree.compact(strategy, 0)?;
+        assert_eq!(0, tree.table_count());
+
+        crate::time::set_unix_timestamp_for_test(None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_window_config() {
+        use crate::compaction::CompactionStrategy;
+
+        let config = Strategy::new(60, Some(3_600))
+            .with_merge_threshold(8)
+            .get_config();
+
+        assert!(config
+            .iter()
+            .any(|(k, v)| &**k == b"time_window_seconds" && **v == 60u64.to_le_bytes()));
+        assert!(config
+            .iter()
+            .any(|(k, v)| &**k == b"time_window_merge_threshold" && **v == [8]));
+    }
+}
@@ -6,19 +6,22 @@ use super::{CompactionStrategy, Input as CompactionPayload};
 use crate::{
     blob_tree::FragmentationMap,
     compaction::{
-        flavour::{RelocatingCompaction, StandardCompaction},
+        flavour::{CompactionFlavour, RelocatingCompaction, StandardCompaction},
         state::CompactionState,
         stream::CompactionStream,
         Choice,
     },
     file::BLOBS_FOLDER,
     merge::Merger,
+    merge_operator::MergeOperator,
+    range_tombstone::{self, RangeTombstoneSet},
     run_scanner::RunScanner,
     stop_signal::StopSignal,
     tree::inner::TreeId,
     version::{Run, SuperVersions, Version},
     vlog::{BlobFileMergeScanner, BlobFileScanner, BlobFileWriter},
-    BlobFile, Config, HashSet, InternalValue, SeqNo, SequenceNumberCounter, Table, TableId,
+    AbstractTree, BlobFile, Config, HashSet, InternalValue, SeqNo, SequenceNumberCounter, Table,
+    TableId, UserKey,
 };
 use std::{
     sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard},
@@ -57,6 +60,9 @@ pub struct Options {
     /// Evicts items that are older than this seqno (MVCC GC).
     pub mvcc_gc_watermark: u64,
 
+    /// Merge operator used to fold merge operands.
+    pub merge_operator: Option<Arc<dyn MergeOperator>>,
+
     pub compaction_state: Arc<Mutex<CompactionState>>,
 
     #[cfg(feature = "metrics")]
@@ -76,6 +82,7 @@ impl Options {
             stop_signal: tree.stop_signal.clone(),
             strategy,
             mvcc_gc_watermark: 0,
+            merge_operator: tree.get_merge_operator(),
 
             compaction_state: tree.compaction_state.clone(),
 
@@ -90,7 +97,9 @@ impl Options {
 /// This will block until the compactor is fully finished.
 pub fn do_compaction(opts: &Options) -> crate::Result<()> {
     #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
-    let compaction_state = opts.compaction_state.lock().expect("lock is poisoned");
+    let mut compaction_state = opts.compaction_state.lock().expect("lock is poisoned");
+
+    compaction_state.set_gc_watermark(opts.mvcc_gc_watermark);
 
     #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
     let version_history_lock = opts.version_history.read().expect("lock is poisoned");
@@ -145,11 +154,10 @@ fn pick_run_indexes(run: &Run<Table>, to_compact: &[TableId]) -> Option<(usize,
     Some((lo, hi))
 }
 
-fn create_compaction_stream<'a>(
+fn create_compaction_readers<'a>(
     version: &Version,
     to_compact: &[TableId],
-    eviction_seqno: SeqNo,
-) -> crate::Result<Option<CompactionStream<'a, Merger<CompactionReader<'a>>>>> {
+) -> crate::Result<Option<Vec<CompactionReader<'a>>>> {
     let mut readers: Vec<CompactionReader<'_>> = vec![];
     let mut found = 0;
 
@@ -174,12 +182,21 @@ fn create_compaction_stream<'a>(
     }
 
     Ok(if found == to_compact.len() {
-        Some(CompactionStream::new(Merger::new(readers), eviction_seqno))
+        Some(readers)
     } else {
         None
     })
 }
 
+fn create_compaction_stream<'a>(
+    version: &Version,
+    to_compact: &[TableId],
+    eviction_seqno: SeqNo,
+) -> crate::Result<Option<CompactionStream<'a, Merger<CompactionReader<'a>>>>> {
+    Ok(create_compaction_readers(version, to_compact)?
+        .map(|readers| CompactionStream::new(Merger::new(readers), eviction_seqno)))
+}
+
 fn move_tables(
     compaction_state: &MutexGuard<'_, CompactionState>,
     opts: &Options,
@@ -204,6 +221,7 @@ fn move_tables(
 
     version_history_lock.upgrade_version(
         &opts.config.path,
+        opts.config.checksum_type,
         |current| {
             let mut copy = current.clone();
 
@@ -363,6 +381,33 @@ fn merge_tables(
         return Ok(());
     };
 
+    let blob_files_to_rewrite = match &opts.config.kv_separation_opts {
+        Some(blob_opts) => pick_blob_files_to_rewrite(
+            &payload.table_ids,
+            &current_super_version.version,
+            blob_opts,
+        )?,
+        None => vec![],
+    };
+
+    let boundaries = subcompaction_boundaries(&tables, opts.config.max_subcompactions);
+
+    // NOTE: Range tombstones and blob file relocation need to see
+    // the entire key range, so those compactions are not split up
+    if !boundaries.is_empty()
+        && tables.iter().all(|t| t.range_tombstones().is_empty())
+        && blob_files_to_rewrite.is_empty()
+    {
+        return merge_subcompactions(
+            compaction_state,
+            version_history_lock,
+            opts,
+            payload,
+            &tables,
+            &boundaries,
+        );
+    }
+
     let mut blob_frag_map = FragmentationMap::default();
 
     let Some(mut merge_iter) = create_compaction_stream(
@@ -377,19 +422,40 @@ fn merge_tables(
         return Ok(());
     };
 
-    let dst_lvl = payload.canonical_level.into();
     let last_level = opts.config.level_count - 1;
 
     // NOTE: Only evict tombstones when reaching the last level,
     // That way we don't resurrect data beneath the tombstone
     let is_last_level = payload.dest_level == last_level;
 
+    let range_tombstones =
+        RangeTombstoneSet::new(tables.iter().flat_map(Table::range_tombstones).cloned());
+
+    // NOTE: A range tombstone can only be evicted if there is no data outside
+    // of the compaction that it could still delete
+    let output_range_tombstones =
+        range_tombstone::gc(range_tombstones.fragments(), opts.mvcc_gc_watermark, |rt| {
+            use std::ops::Bound::{Excluded, Included};
+
+            let bounds = (Included(&*rt.start), Excluded(&*rt.end));
+
+            is_last_level
+                && current_super_version
+                    .version
+                    .iter_tables()
+                    .filter(|t| !payload.table_ids.contains(&t.id()))
+                    .all(|t| !t.metadata.key_range.overlaps_with_bounds(&bounds))
+        });
+
     merge_iter = merge_iter
         .evict_tombstones(is_last_level)
-        .zero_seqnos(false);
+        .zero_seqnos(false)
+        .merge_operator(opts.merge_operator.clone())
+        .range_tombstones(range_tombstones);
 
     let table_writer =
-        super::flavour::prepare_table_writer(&current_super_version.version, opts, payload)?;
+        super::flavour::prepare_table_writer(&current_super_version.version, opts, payload)?
+            .use_range_tombstones(output_range_tombstones);
 
     let start = Instant::now();
 
@@ -397,12 +463,6 @@ fn merge_tables(
         Some(blob_opts) => {
             merge_iter = merge_iter.with_expiration_callback(&mut blob_frag_map);
 
-            let blob_files_to_rewrite = pick_blob_files_to_rewrite(
-                &payload.table_ids,
-                &current_super_version.version,
-                blob_opts,
-            )?;
-
             if blob_files_to_rewrite.is_empty() {
                 log::debug!("No blob relocation needed");
 
@@ -420,7 +480,10 @@ fn merge_tables(
                 let scanner = BlobFileMergeScanner::new(
                     blob_files_to_rewrite
                         .iter()
-                        .map(|bf| BlobFileScanner::new(&bf.0.path, bf.id()))
+                        .map(|bf| {
+                            BlobFileScanner::new(&bf.0.path, bf.id())
+                                .map(|scanner| scanner.use_checksum_type(bf.0.meta.checksum_type))
+                        })
                         .collect::<crate::Result<Vec<_>>>()?,
                 );
 
@@ -429,7 +492,8 @@ fn merge_tables(
                     opts.config.path.join(BLOBS_FOLDER),
                 )?
                 .use_target_size(blob_opts.file_target_size)
-                .use_passthrough_compression(blob_opts.compression);
+                .use_passthrough_compression(blob_opts.compression)
+                .use_checksum_type(opts.config.checksum_type);
 
                 let inner = StandardCompaction::new(table_writer, tables);
 
@@ -472,6 +536,216 @@ fn merge_tables(
         Ok(())
     })?;
 
+    commit_compaction(compactor, opts, payload, blob_frag_map)
+}
+
+/// Picks the keys that split a compaction into disjoint subcompactions.
+///
+/// The key ranges are cut at table boundaries (their smallest keys), so every
+/// subcompaction gets a similar number of tables.
+///
+/// Returns an empty list if the compaction should not be split.
+fn subcompaction_boundaries(tables: &[Table], max_subcompactions: usize) -> Vec<UserKey> {
+    if max_subcompactions <= 1 || tables.len() <= 1 {
+        return vec![];
+    }
+
+    let mut keys = tables
+        .iter()
+        .map(|table| table.metadata.key_range.min().clone())
+        .collect::<Vec<_>>();
+
+    keys.sort();
+    keys.dedup();
+
+    // NOTE: The smallest key does not split anything
+    let Some((_, keys)) = keys.split_first() else {
+        return vec![];
+    };
+
+    let subcompaction_count = max_subcompactions.min(keys.len() + 1);
+
+    (1..subcompaction_count)
+        .filter_map(|idx| keys.get(idx * keys.len() / subcompaction_count))
+        .cloned()
+        .collect()
+}
+
+/// Scans the part of a table that lies in the key range `[lo, hi)`.
+///
+/// Like a regular compaction, this reads the table sequentially,
+/// without going through the block cache.
+fn scan_range<'a>(
+    table: &Table,
+    lo: Option<&'a UserKey>,
+    hi: Option<&'a UserKey>,
+) -> crate::Result<CompactionReader<'a>> {
+    let scanner = table.scan()?;
+
+    Ok(Box::new(
+        scanner
+            .skip_while(move |item| match (item, lo) {
+                (Ok(kv), Some(lo)) => kv.key.user_key < *lo,
+                _ => false,
+            })
+            .take_while(move |item| match (item, hi) {
+                (Ok(kv), Some(hi)) => kv.key.user_key < *hi,
+                _ => true,
+            }),
+    ))
+}
+
+/// Splits a merge into disjoint key ranges, and compacts them in parallel.
+///
+/// The output of all subcompactions is registered atomically in a single version.
+fn merge_subcompactions(
+    mut compaction_state: MutexGuard<'_, CompactionState>,
+    version_history_lock: RwLockReadGuard<'_, SuperVersions>,
+    opts: &Options,
+    payload: &CompactionPayload,
+    tables: &[Table],
+    boundaries: &[UserKey],
+) -> crate::Result<()> {
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    let version = version_history_lock.latest_version().version;
+
+    let mut subcompactions = Vec::with_capacity(boundaries.len() + 1);
+
+    for idx in 0..=boundaries.len() {
+        let lo = idx
+            .checked_sub(1)
+            .and_then(|idx| boundaries.get(idx))
+            .cloned();
+        let hi = boundaries.get(idx).cloned();
+
+        let bounds = (
+            lo.as_deref().map_or(Unbounded, Included),
+            hi.as_deref().map_or(Unbounded, Excluded),
+        );
+
+        let overlapping_tables = tables
+            .iter()
+            .filter(|table| table.metadata.key_range.overlaps_with_bounds(&bounds))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let table_writer = super::flavour::prepare_table_writer(&version, opts, payload)?;
+
+        // NOTE: The first subcompaction takes over the input tables,
+        // the output of the other subcompactions is added to it later
+        let tables_to_rewrite = if idx == 0 { tables.to_vec() } else { vec![] };
+
+        subcompactions.push((
+            overlapping_tables,
+            (lo, hi),
+            StandardCompaction::new(table_writer, tables_to_rewrite),
+        ));
+    }
+
+    log::debug!(
+        "Splitting compaction of tables {:?} into {} subcompactions",
+        payload.table_ids,
+        subcompactions.len(),
+    );
+
+    drop(version_history_lock);
+
+    {
+        compaction_state
+            .hidden_set_mut()
+            .hide(payload.table_ids.iter().copied());
+    }
+
+    // IMPORTANT: Unlock exclusive compaction lock as we are now doing the actual (CPU-intensive) compaction
+    drop(compaction_state);
+
+    // NOTE: Options is not Sync, so only pass what the threads need
+    let eviction_seqno = opts.mvcc_gc_watermark;
+    let is_last_level = payload.dest_level == opts.config.level_count - 1;
+    let track_blobs = opts.config.kv_separation_opts.is_some();
+    let merge_operator = &opts.merge_operator;
+    let stop_signal = &opts.stop_signal;
+
+    let mut results = vec![];
+
+    hidden_guard(payload, opts, || {
+        results = std::thread::scope(|scope| {
+            let handles = subcompactions
+                .into_iter()
+                .map(|(tables, (lo, hi), mut compactor)| {
+                    scope.spawn(move || -> crate::Result<_> {
+                        // NOTE: Tables may overlap into other subcompactions,
+                        // so only read the key range that belongs to this subcompaction
+                        let readers = tables
+                            .iter()
+                            .map(|table| scan_range(table, lo.as_ref(), hi.as_ref()))
+                            .collect::<crate::Result<Vec<_>>>()?;
+
+                        let mut blob_frag_map = FragmentationMap::default();
+
+                        let mut merge_iter =
+                            CompactionStream::new(Merger::new(readers), eviction_seqno)
+                                .evict_tombstones(is_last_level)
+                                .zero_seqnos(false)
+                                .merge_operator(merge_operator.clone());
+
+                        if track_blobs {
+                            merge_iter = merge_iter.with_expiration_callback(&mut blob_frag_map);
+                        }
+
+                        for (idx, item) in merge_iter.enumerate() {
+                            compactor.write(item?)?;
+
+                            if idx % 1_000_000 == 0 && stop_signal.is_stopped() {
+                                log::debug!("Stopping amidst subcompaction because of stop signal");
+                                break;
+                            }
+                        }
+
+                        Ok((compactor, blob_frag_map))
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                })
+                .collect::<crate::Result<Vec<_>>>()
+        })?;
+
+        Ok(())
+    })?;
+
+    let mut results = results.into_iter();
+
+    #[expect(
+        clippy::expect_used,
+        reason = "there are always at least two subcompactions"
+    )]
+    let (mut compactor, mut blob_frag_map) = results.next().expect("should exist");
+
+    for (subcompaction, subcompaction_frag_map) in results {
+        compactor.add_subcompaction(subcompaction);
+        subcompaction_frag_map.merge_into(&mut blob_frag_map);
+    }
+
+    commit_compaction(Box::new(compactor), opts, payload, blob_frag_map)
+}
+
+/// Registers the output of a compaction in a new version, and shows the input tables again.
+fn commit_compaction(
+    compactor: Box<dyn CompactionFlavour>,
+    opts: &Options,
+    payload: &CompactionPayload,
+    blob_frag_map: FragmentationMap,
+) -> crate::Result<()> {
+    let dst_lvl = payload.canonical_level.into();
+
     #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
     let mut compaction_state = opts.compaction_state.lock().expect("lock is poisoned");
 
@@ -564,6 +838,7 @@ fn drop_tables(
     // Otherwise the table files are deleted, but are still referenced!
     version_history_lock.upgrade_version(
         &opts.config.path,
+        opts.config.checksum_type,
         |current| {
             let mut copy = current.clone();
 
@@ -604,7 +879,7 @@ fn drop_tables(
 
 #[cfg(test)]
 mod tests {
-    use super::{create_compaction_stream, pick_run_indexes};
+    use super::{create_compaction_stream, pick_run_indexes, scan_range, subcompaction_boundaries};
     use crate::{
         compaction::{state::CompactionState, Choice, CompactionStrategy, Input},
         config::BlockSizePolicy,
@@ -614,6 +889,49 @@ mod tests {
     use std::sync::Arc;
     use test_log::test;
 
+    #[test]
+    fn compaction_scan_range() -> crate::Result<()> {
+        let folder = tempfile::tempdir()?;
+
+        let tree = crate::Config::new(
+            folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        for key in ["a", "b", "c", "d", "e"] {
+            tree.insert(key, key, 0);
+        }
+        tree.flush_active_memtable(0)?;
+
+        let version = tree.current_version();
+        let table = version.iter_tables().next().expect("should exist");
+
+        let keys = |lo: Option<&str>, hi: Option<&str>| -> crate::Result<Vec<crate::UserKey>> {
+            let (lo, hi) = (lo.map(crate::UserKey::from), hi.map(crate::UserKey::from));
+
+            let keys = scan_range(table, lo.as_ref(), hi.as_ref())?
+                .map(|item| item.map(|kv| kv.key.user_key))
+                .collect();
+
+            keys
+        };
+
+        assert_eq!(5, keys(None, None)?.len());
+        assert_eq!(
+            vec![crate::UserKey::from("b"), crate::UserKey::from("c")],
+            keys(Some("b"), Some("d"))?,
+        );
+        assert_eq!(
+            vec![crate::UserKey::from("d"), crate::UserKey::from("e")],
+            keys(Some("d"), None)?,
+        );
+        assert!(keys(Some("c"), Some("c"))?.is_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn compaction_stream_run_not_found() -> crate::Result<()> {
         let folder = tempfile::tempdir()?;
@@ -781,6 +1099,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn compaction_subcompaction_boundaries() -> crate::Result<()> {
+        let folder = tempfile::tempdir()?;
+
+        let tree = crate::Config::new(
+            folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        for (idx, key) in ["a", "b", "c", "d", "e"].into_iter().enumerate() {
+            tree.insert(key, "a", idx as u64);
+            tree.insert("z", "a", idx as u64);
+            tree.flush_active_memtable(0)?;
+        }
+
+        let tables = tree
+            .current_version()
+            .iter_tables()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        assert!(subcompaction_boundaries(&tables, 1).is_empty());
+        assert!(subcompaction_boundaries(&tables[..1], 4).is_empty());
+
+        assert_eq!(
+            [b"d"],
+            &*subcompaction_boundaries(&tables, 2)
+                .iter()
+                .map(|x| &**x)
+                .collect::<Vec<_>>(),
+        );
+
+        // NOTE: There are only 5 distinct smallest keys, so at most 5 subcompactions
+        assert_eq!(
+            [b"b", b"c", b"d", b"e"],
+            &*subcompaction_boundaries(&tables, 100)
+                .iter()
+                .map(|x| &**x)
+                .collect::<Vec<_>>(),
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn compaction_drop_tables() -> crate::Result<()> {
         let folder = tempfile::tempdir()?;
@@ -2,8 +2,12 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use crate::coding::{Decode, Encode};
-use byteorder::{ReadBytesExt, WriteBytesExt};
+use crate::{
+    coding::{Decode, Encode},
+    Slice,
+};
+#[cfg_attr(not(feature = "zstd"), expect(unused_imports))]
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::io::{Read, Write};
 
 /// Compression algorithm to use
@@ -20,6 +24,15 @@ pub enum CompressionType {
     /// on speed over compression ratio.
     #[cfg(feature = "lz4")]
     Lz4,
+
+    /// Zstd compression with the given compression level
+    ///
+    /// Recommended for cold data (e.g. the last levels),
+    /// with a focus on compression ratio over speed.
+    ///
+    /// Levels range from 1 to 22, negative levels are faster, but compress worse.
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
 }
 
 impl Encode for CompressionType {
@@ -33,6 +46,12 @@ impl Encode for CompressionType {
             Self::Lz4 => {
                 writer.write_u8(1)?;
             }
+
+            #[cfg(feature = "zstd")]
+            Self::Zstd(level) => {
+                writer.write_u8(2)?;
+                writer.write_i32::<LittleEndian>(*level)?;
+            }
         }
 
         Ok(())
@@ -49,6 +68,12 @@ impl Decode for CompressionType {
             #[cfg(feature = "lz4")]
             1 => Ok(Self::Lz4),
 
+            #[cfg(feature = "zstd")]
+            2 => {
+                let level = reader.read_i32::<LittleEndian>()?;
+                Ok(Self::Zstd(level))
+            }
+
             tag => Err(crate::Error::InvalidTag(("CompressionType", tag))),
         }
     }
@@ -56,16 +81,106 @@ impl Decode for CompressionType {
 
 impl std::fmt::Display for CompressionType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::None => "none",
-
-                #[cfg(feature = "lz4")]
-                Self::Lz4 => "lz4",
-            }
-        )
+        match self {
+            Self::None => write!(f, "none"),
+
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => write!(f, "lz4"),
+
+            #[cfg(feature = "zstd")]
+            Self::Zstd(level) => write!(f, "zstd({level})"),
+        }
+    }
+}
+
+/// Compression dictionary that is shared by all data blocks of a table
+///
+/// Small blocks compress badly on their own, because there is little
+/// repetition inside a single block.
+/// A dictionary that is trained on samples of the table's data blocks
+/// provides the common byte patterns instead.
+#[derive(Clone)]
+pub struct CompressionDictionary {
+    raw: Slice,
+
+    #[cfg(feature = "zstd")]
+    decoder: std::sync::Arc<zstd::dict::DecoderDictionary<'static>>,
+}
+
+impl std::fmt::Debug for CompressionDictionary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CompressionDictionary({}B)", self.raw.len())
+    }
+}
+
+impl CompressionDictionary {
+    /// Creates a compression dictionary from its raw bytes.
+    #[must_use]
+    pub fn new(raw: Slice) -> Self {
+        Self {
+            #[cfg(feature = "zstd")]
+            decoder: std::sync::Arc::new(zstd::dict::DecoderDictionary::copy(&raw)),
+
+            raw,
+        }
+    }
+
+    /// Trains a dictionary of at most `max_size` bytes on the given samples.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are not enough samples to train on.
+    #[cfg(feature = "zstd")]
+    pub fn train<S: AsRef<[u8]>>(samples: &[S], max_size: usize) -> crate::Result<Self> {
+        let raw = zstd::dict::from_samples(samples, max_size)?;
+        Ok(Self::new(raw.into()))
+    }
+
+    /// Returns the raw bytes of the dictionary.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// Returns the size of the dictionary in bytes.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Returns the memory usage of the dictionary, including the
+    /// prepared decompression dictionary.
+    #[must_use]
+    pub fn memory_size(&self) -> usize {
+        #[cfg(feature = "zstd")]
+        let decoder_size = self.decoder.as_ddict().sizeof();
+
+        #[cfg(not(feature = "zstd"))]
+        let decoder_size = 0;
+
+        self.raw.len() + decoder_size
+    }
+
+    /// Decompresses a Zstd frame that was compressed using this dictionary.
+    #[cfg(feature = "zstd")]
+    pub(crate) fn decompress_to_buffer(
+        &self,
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> std::io::Result<usize> {
+        use std::cell::RefCell;
+        use zstd::zstd_safe::{get_error_name, DCtx};
+
+        thread_local! {
+            // NOTE: Creating a decompression context allocates, so reuse one per thread
+            // instead of creating a new one for every block
+            static DECOMPRESSOR: RefCell<DCtx<'static>> = RefCell::new(DCtx::create());
+        }
+
+        DECOMPRESSOR.with_borrow_mut(|dctx| {
+            dctx.decompress_using_ddict(dst, src, self.decoder.as_ddict())
+                .map_err(|code| std::io::Error::other(get_error_name(code)))
+        })
     }
 }
 
@@ -91,4 +206,39 @@ mod tests {
             assert_eq!(1, serialized.len());
         }
     }
+
+    #[cfg(feature = "zstd")]
+    mod zstd {
+        use super::*;
+        use test_log::test;
+
+        #[test]
+        fn compression_serialize_zstd() -> crate::Result<()> {
+            let serialized = CompressionType::Zstd(-3).encode_into_vec();
+            assert_eq!(5, serialized.len());
+
+            let deserialized = CompressionType::decode_from(&mut &serialized[..])?;
+            assert_eq!(CompressionType::Zstd(-3), deserialized);
+
+            Ok(())
+        }
+
+        #[test]
+        fn compression_dictionary_memory_size() -> crate::Result<()> {
+            let samples = (0..1_000)
+                .map(|x| format!("{{\"id\":{x},\"name\":\"user{x}\"}}"))
+                .collect::<Vec<_>>();
+
+            let dictionary = CompressionDictionary::train(&samples, 1_024)?;
+            assert!(dictionary.size() > 0);
+            assert!(dictionary.memory_size() > dictionary.size());
+
+            Ok(())
+        }
+
+        #[test]
+        fn compression_display_zstd() {
+            assert_eq!("zstd(3)", CompressionType::Zstd(3).to_string());
+        }
+    }
 }
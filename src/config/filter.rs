@@ -14,6 +14,12 @@ pub enum FilterPolicyEntry {
 
     /// Standard bloom filter with K bits per key
     Bloom(BloomConstructionPolicy),
+
+    /// Standard bloom filter with K bits per key, which also contains the prefixes
+    /// of keys, as returned by the configured prefix extractor
+    ///
+    /// Behaves like [`FilterPolicyEntry::Bloom`] if no prefix extractor is configured.
+    PrefixBloom(BloomConstructionPolicy),
 }
 
 /// Filter policy
@@ -19,9 +19,13 @@ pub use restart_interval::RestartIntervalPolicy;
 /// Partitioning policy for indexes and filters
 pub type PartitioningPolicy = PinningPolicy;
 
+/// Minimum compression ratio policy for data blocks
+pub type CompressionRatioPolicy = HashRatioPolicy;
+
 use crate::{
-    merge_operator::MergeOperator, path::absolute_path, version::DEFAULT_LEVEL_COUNT, AnyTree,
-    BlobTree, Cache, CompressionType, DescriptorTable, SequenceNumberCounter, Tree,
+    journal::JournalSyncPolicy, merge_operator::MergeOperator, path::absolute_path,
+    prefix::PrefixExtractor, version::DEFAULT_LEVEL_COUNT, AnyTree, BlobTree, Cache, ChecksumType,
+    CompressionType, DescriptorTable, SequenceNumberCounter, Tree,
 };
 use std::{
     path::{Path, PathBuf},
@@ -180,9 +184,20 @@ pub struct Config {
     /// What type of compression is used for data blocks
     pub data_block_compression_policy: CompressionPolicy,
 
+    /// Minimum compression ratio (uncompressed size / compressed size) a data block
+    /// needs to reach to be stored compressed, otherwise it is stored uncompressed
+    pub data_block_compression_ratio_policy: CompressionRatioPolicy,
+
     /// What type of compression is used for index blocks
     pub index_block_compression_policy: CompressionPolicy,
 
+    /// Maximum size of the compression dictionary that is trained per table
+    /// for the data blocks (0 = disabled)
+    pub compression_dictionary_size_policy: BlockSizePolicy,
+
+    /// Checksum algorithm of newly written blocks, blobs and files
+    pub checksum_type: ChecksumType,
+
     /// Restart interval inside data blocks
     pub data_block_restart_interval_policy: RestartIntervalPolicy,
 
@@ -226,9 +241,15 @@ pub struct Config {
     /// Filter construction policy
     pub filter_policy: FilterPolicy,
 
+    /// Prefix extractor used for prefix bloom filters
+    pub prefix_extractor: Option<Arc<dyn PrefixExtractor>>,
+
     #[doc(hidden)]
     pub kv_separation_opts: Option<KvSeparationOptions>,
 
+    /// Sync policy of the write-ahead journal, if enabled
+    pub journal_sync_policy: Option<JournalSyncPolicy>,
+
     /// The global sequence number generator
     ///
     /// Should be shared between multple trees of a database
@@ -239,8 +260,20 @@ pub struct Config {
     /// Optional merge operator for atomic read-modify-write operations
     #[doc(hidden)]
     pub merge_operator: Option<Arc<dyn MergeOperator>>,
+
+    /// Maximum number of threads a single compaction may be split into
+    pub max_subcompactions: usize,
+
+    /// Maximum number of bytes a compaction output table may overlap in the grandparent level
+    pub max_grandparent_overlap_bytes: Option<u64>,
 }
 
+// NOTE: The merge operator and prefix extractor are user-provided trait objects, which are not
+// necessarily unwind safe. The tree never relies on their state after a panic, so this keeps trees
+// usable across `catch_unwind` without requiring every implementation to be `RefUnwindSafe`.
+impl std::panic::UnwindSafe for Config {}
+impl std::panic::RefUnwindSafe for Config {}
+
 // TODO: remove default?
 impl Default for Config {
     fn default() -> Self {
@@ -265,7 +298,7 @@ impl Default for Config {
             filter_block_pinning_policy: PinningPolicy::new([true, false]),
 
             top_level_index_block_pinning_policy: PinningPolicy::all(true), // TODO: implement
-            top_level_filter_block_pinning_policy: PinningPolicy::all(true), // TODO: implement
+            top_level_filter_block_pinning_policy: PinningPolicy::all(true),
 
             index_block_partitioning_policy: PinningPolicy::new([false, false, false, true]),
             filter_block_partitioning_policy: PinningPolicy::new([false, false, false, true]),
@@ -282,8 +315,13 @@ impl Default for Config {
 
                 c
             }),
+            data_block_compression_ratio_policy: CompressionRatioPolicy::all(1.0),
             index_block_compression_policy: CompressionPolicy::all(CompressionType::None),
 
+            compression_dictionary_size_policy: BlockSizePolicy::all(0),
+
+            checksum_type: ChecksumType::Xxh3,
+
             data_block_hash_ratio_policy: HashRatioPolicy::all(0.0),
 
             filter_policy: FilterPolicy::all(FilterPolicyEntry::Bloom(
@@ -292,9 +330,17 @@ impl Default for Config {
 
             expect_point_read_hits: false,
 
+            prefix_extractor: None,
+
             kv_separation_opts: None,
 
+            journal_sync_policy: None,
+
             merge_operator: None,
+
+            max_subcompactions: 1,
+
+            max_grandparent_overlap_bytes: None,
         }
     }
 }
@@ -364,6 +410,15 @@ impl Config {
         self
     }
 
+    /// Sets the pinning policy for the top level index of partitioned filters.
+    ///
+    /// Unpinned top level indexes are loaded through the block cache.
+    #[must_use]
+    pub fn top_level_filter_block_pinning_policy(mut self, policy: PinningPolicy) -> Self {
+        self.top_level_filter_block_pinning_policy = policy;
+        self
+    }
+
     /// Sets the pinning policy for index blocks.
     #[must_use]
     pub fn index_block_pinning_policy(mut self, policy: PinningPolicy) -> Self {
@@ -410,6 +465,22 @@ impl Config {
         self
     }
 
+    /// Sets the minimum compression ratio (uncompressed size / compressed size)
+    /// of data blocks.
+    ///
+    /// Data blocks that do not reach the ratio are stored uncompressed, which
+    /// avoids decompression costs for data that does not compress well,
+    /// e.g. already compressed values.
+    ///
+    /// Data blocks that do not get smaller when compressed are always stored uncompressed.
+    ///
+    /// Defaults to 1.0 for all levels.
+    #[must_use]
+    pub fn data_block_compression_ratio_policy(mut self, policy: CompressionRatioPolicy) -> Self {
+        self.data_block_compression_ratio_policy = policy;
+        self
+    }
+
     /// Sets the compression method for index blocks.
     #[must_use]
     pub fn index_block_compression_policy(mut self, policy: CompressionPolicy) -> Self {
@@ -417,6 +488,34 @@ impl Config {
         self
     }
 
+    /// Sets the maximum size of the compression dictionaries of data blocks.
+    ///
+    /// For every table, a dictionary is trained on samples of its data blocks,
+    /// which is then used to compress all data blocks of the table.
+    /// This improves the compression ratio of small data blocks, at the
+    /// cost of slower table writes and keeping the dictionary in memory.
+    ///
+    /// Only used for data blocks that are compressed using Zstd, 0 disables dictionaries.
+    ///
+    /// Defaults to 0 for all levels.
+    #[must_use]
+    pub fn compression_dictionary_size_policy(mut self, policy: BlockSizePolicy) -> Self {
+        self.compression_dictionary_size_policy = policy;
+        self
+    }
+
+    /// Sets the checksum algorithm of newly written tables, blob files and versions.
+    ///
+    /// The checksum type is recorded in each of these files, so existing files stay readable
+    /// when the checksum type is changed.
+    ///
+    /// Defaults to [`ChecksumType::Xxh3`].
+    #[must_use]
+    pub fn checksum_type(mut self, checksum_type: ChecksumType) -> Self {
+        self.checksum_type = checksum_type;
+        self
+    }
+
     // TODO: level count is fixed to 7 right now
     // /// Sets the number of levels of the LSM tree (depth of tree).
     // ///
@@ -452,6 +551,28 @@ impl Config {
         self
     }
 
+    /// Sets the prefix extractor.
+    ///
+    /// If the filter policy of a level uses [`FilterPolicyEntry::PrefixBloom`],
+    /// the prefixes of keys are inserted into its filters, so prefix scans
+    /// can skip tables that cannot contain the prefix.
+    #[must_use]
+    pub fn prefix_extractor(mut self, extractor: Option<Arc<dyn PrefixExtractor>>) -> Self {
+        self.prefix_extractor = extractor;
+        self
+    }
+
+    /// Returns the prefix extractor that should be used to build the filters of a level.
+    pub(crate) fn prefix_extractor_for_level(
+        &self,
+        level: usize,
+    ) -> Option<Arc<dyn PrefixExtractor>> {
+        match self.filter_policy.get(level) {
+            FilterPolicyEntry::PrefixBloom(_) => self.prefix_extractor.clone(),
+            _ => None,
+        }
+    }
+
     /// Toggles key-value separation.
     #[must_use]
     pub fn with_kv_separation(mut self, opts: Option<KvSeparationOptions>) -> Self {
@@ -459,6 +580,21 @@ impl Config {
         self
     }
 
+    /// Toggles the write-ahead journal.
+    ///
+    /// If enabled, every write to the memtable is also appended to a journal on disk,
+    /// which is replayed when the tree is reopened, so writes are not lost
+    /// if the tree is closed (or crashes) before the memtable is flushed.
+    ///
+    /// The sync policy controls when the journal is synced to disk.
+    ///
+    /// Defaults to `None` (no journal).
+    #[must_use]
+    pub fn with_journal(mut self, sync_policy: Option<JournalSyncPolicy>) -> Self {
+        self.journal_sync_policy = sync_policy;
+        self
+    }
+
     /// Sets the merge operator for atomic read-modify-write operations.
     ///
     /// A merge operator allows efficient partial updates without requiring
@@ -469,6 +605,37 @@ impl Config {
         self
     }
 
+    /// Sets the maximum number of subcompactions.
+    ///
+    /// A large compaction is split into disjoint key ranges (using the input tables' boundaries),
+    /// which are then compacted in parallel, each on its own thread.
+    ///
+    /// Compactions that contain range tombstones or relocate blob files
+    /// are never split.
+    ///
+    /// Defaults to 1 (no subcompactions).
+    #[must_use]
+    pub fn max_subcompactions(mut self, n: usize) -> Self {
+        self.max_subcompactions = n.max(1);
+        self
+    }
+
+    /// Sets the maximum number of bytes a compaction output table may overlap
+    /// in the grandparent level (the level below the compaction's destination level).
+    ///
+    /// Output tables are then also cut at grandparent table boundaries, not only
+    /// when they reach their target size, so the compaction that later merges them
+    /// into the grandparent level stays small and predictable.
+    ///
+    /// A good value is about 10 times the table target size.
+    ///
+    /// Defaults to `None` (unlimited).
+    #[must_use]
+    pub fn max_grandparent_overlap_bytes(mut self, bytes: Option<u64>) -> Self {
+        self.max_grandparent_overlap_bytes = bytes;
+        self
+    }
+
     /// Opens a tree using the config.
     ///
     /// # Errors
@@ -2,7 +2,7 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use crate::{Checksum, CompressionType};
+use crate::{Checksum, ChecksumType, CompressionType};
 
 /// Represents errors that can occur in the LSM-tree
 #[derive(Debug)]
@@ -29,6 +29,16 @@ pub enum Error {
         expected: Checksum,
     },
 
+    /// Checksum type of a table or blob file does not match
+    /// the checksum type recorded in the version
+    ChecksumTypeMismatch {
+        /// Checksum type stored in the file
+        got: ChecksumType,
+
+        /// Checksum type recorded in the version
+        expected: ChecksumType,
+    },
+
     /// Invalid enum tag
     InvalidTag((&'static str, u8)),
 
@@ -40,6 +50,9 @@ pub enum Error {
 
     /// UTF-8 error
     Utf8(std::str::Utf8Error),
+
+    /// The merge operator could not combine the merge operands of a key
+    MergeFailed(&'static str),
 }
 
 impl std::fmt::Display for Error {
@@ -0,0 +1,517 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+mod record;
+
+pub use record::Record;
+
+use crate::{
+    coding::{Decode, Encode},
+    file::fsync_directory,
+    memtable::Memtable,
+    tree::inner::MemtableId,
+};
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Size of a frame header (checksum + payload length)
+const FRAME_HEADER_SIZE: usize = std::mem::size_of::<u64>() + std::mem::size_of::<u32>();
+
+/// Controls when the write-ahead journal is synced to disk
+///
+/// Regardless of the policy, every write is handed to the OS before
+/// it is inserted into the memtable, so it survives a crash of the process.
+/// The policy only controls durability against power loss.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum JournalSyncPolicy {
+    /// Syncs the journal after every write (and every write batch)
+    PerWrite,
+
+    /// Syncs the journal after every write batch
+    ///
+    /// Single writes are not synced.
+    PerBatch,
+
+    /// Never syncs the journal, leaving it to the OS
+    Never,
+}
+
+/// Write-ahead journal
+///
+/// Every memtable has a journal file, named by the memtable's ID,
+/// that contains all writes to the memtable.
+///
+/// The journal file of the active memtable is written to, journal files of
+/// sealed memtables are deleted once the memtables have been flushed to tables.
+pub struct Journal {
+    folder: PathBuf,
+    sync_policy: JournalSyncPolicy,
+    writer: Mutex<Writer>,
+}
+
+struct Writer {
+    memtable_id: MemtableId,
+    file: BufWriter<File>,
+
+    /// Set if a write failed, in which case the journal may be incomplete
+    error: Option<std::io::ErrorKind>,
+}
+
+impl Writer {
+    fn create(folder: &Path, memtable_id: MemtableId) -> std::io::Result<Self> {
+        let file = File::create(folder.join(memtable_id.to_string()))?;
+        fsync_directory(folder)?;
+
+        Ok(Self {
+            memtable_id,
+            file: BufWriter::new(file),
+            error: None,
+        })
+    }
+
+    fn write_frame(&mut self, payload: &[u8]) -> std::io::Result<()> {
+        self.file
+            .write_u64::<LE>(xxhash_rust::xxh3::xxh3_64(payload))?;
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "a record is never larger than 4 GiB"
+        )]
+        self.file.write_u32::<LE>(payload.len() as u32)?;
+
+        self.file.write_all(payload)?;
+        self.file.flush()
+    }
+
+    fn sync(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()
+    }
+
+    /// Returns the error of a previous write that failed.
+    fn check(&self) -> std::io::Result<()> {
+        match self.error {
+            Some(kind) => Err(std::io::Error::new(kind, "journal write failed")),
+            None => Ok(()),
+        }
+    }
+
+    fn poison(&mut self, e: &std::io::Error) {
+        log::error!(
+            "Write to journal #{} failed, journal may be incomplete: {e:?}",
+            self.memtable_id,
+        );
+        self.error.get_or_insert_with(|| e.kind());
+    }
+}
+
+/// Reads the next frame's payload.
+///
+/// Returns `None` at the end of the journal, or if the frame is torn or corrupted.
+fn read_frame<R: Read>(reader: &mut R, path: &Path) -> crate::Result<Option<Vec<u8>>> {
+    let mut header = [0; FRAME_HEADER_SIZE];
+
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let mut header = &header[..];
+    let checksum = header.read_u64::<LE>()?;
+    let len = header.read_u32::<LE>()?;
+
+    let mut payload = Vec::new();
+    reader.take(len.into()).read_to_end(&mut payload)?;
+
+    if payload.len() != len as usize {
+        log::warn!(
+            "Journal {} has a torn record, ignoring tail",
+            path.display()
+        );
+        return Ok(None);
+    }
+
+    if xxhash_rust::xxh3::xxh3_64(&payload) != checksum {
+        log::warn!(
+            "Journal {} has a corrupted record, ignoring tail",
+            path.display(),
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(payload))
+}
+
+/// Lists the memtable IDs of all journal files in the folder, in ascending order.
+fn list_journals(folder: &Path) -> crate::Result<Vec<MemtableId>> {
+    let mut ids = vec![];
+
+    for dirent in std::fs::read_dir(folder)? {
+        let dirent = dirent?;
+        let file_name = dirent.file_name();
+
+        match file_name
+            .to_str()
+            .and_then(|x| x.parse::<MemtableId>().ok())
+        {
+            Some(id) => ids.push(id),
+            None => log::warn!(
+                "Ignoring unknown file in journal folder: {}",
+                file_name.display(),
+            ),
+        }
+    }
+
+    ids.sort_unstable();
+
+    Ok(ids)
+}
+
+impl Journal {
+    /// Creates a new journal for the (empty) active memtable.
+    pub fn create_new(
+        folder: PathBuf,
+        sync_policy: JournalSyncPolicy,
+        memtable_id: MemtableId,
+    ) -> crate::Result<Self> {
+        std::fs::create_dir_all(&folder)?;
+
+        let writer = Writer::create(&folder, memtable_id)?;
+
+        // IMPORTANT: fsync folders on Unix
+        if let Some(parent) = folder.parent() {
+            fsync_directory(parent)?;
+        }
+
+        Ok(Self {
+            folder,
+            sync_policy,
+            writer: Mutex::new(writer),
+        })
+    }
+
+    /// Recovers all journals in the folder, replaying them into a fresh memtable.
+    ///
+    /// The valid records of all journals are moved into a new journal file for the
+    /// recovered memtable, so a torn tail cannot hide records written after recovery.
+    pub fn recover(
+        folder: PathBuf,
+        sync_policy: JournalSyncPolicy,
+    ) -> crate::Result<(Self, Memtable)> {
+        std::fs::create_dir_all(&folder)?;
+
+        let old_ids = list_journals(&folder)?;
+
+        let memtable_id = old_ids.last().map(|id| id + 1).unwrap_or_default();
+        let memtable = Memtable::new(memtable_id);
+
+        let mut writer = Writer::create(&folder, memtable_id)?;
+        let mut record_count = 0;
+
+        for &id in &old_ids {
+            let path = folder.join(id.to_string());
+            let mut reader = BufReader::new(File::open(&path)?);
+
+            log::debug!("Replaying journal {}", path.display());
+
+            while let Some(payload) = read_frame(&mut reader, &path)? {
+                let record = Record::decode_from(&mut &payload[..])?;
+
+                for item in record.items {
+                    memtable.insert(item);
+                }
+                for tombstone in record.range_tombstones {
+                    memtable.insert_range_tombstone(tombstone);
+                }
+
+                writer.write_frame(&payload)?;
+                record_count += 1;
+            }
+        }
+
+        writer.sync()?;
+
+        for &id in &old_ids {
+            std::fs::remove_file(folder.join(id.to_string()))?;
+        }
+
+        // IMPORTANT: fsync folders on Unix
+        fsync_directory(&folder)?;
+        if let Some(parent) = folder.parent() {
+            fsync_directory(parent)?;
+        }
+
+        log::info!(
+            "Recovered {record_count} journal records from {} journals",
+            old_ids.len(),
+        );
+
+        Ok((
+            Self {
+                folder,
+                sync_policy,
+                writer: Mutex::new(writer),
+            },
+            memtable,
+        ))
+    }
+
+    /// Appends a record of writes to the journal.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if a previous write to the journal failed.
+    #[expect(
+        clippy::significant_drop_tightening,
+        reason = "lock needs to be held while writing"
+    )]
+    pub fn append(&self, record: &Record, is_batch: bool) -> crate::Result<()> {
+        let payload = record.encode_into_vec();
+
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let mut writer = self.writer.lock().expect("lock is poisoned");
+
+        // NOTE: Recovery stops at the first broken record, so there is no point in writing more
+        writer.check()?;
+
+        let sync = match self.sync_policy {
+            JournalSyncPolicy::PerWrite => true,
+            JournalSyncPolicy::PerBatch => is_batch,
+            JournalSyncPolicy::Never => false,
+        };
+
+        let result =
+            writer
+                .write_frame(&payload)
+                .and_then(|()| if sync { writer.sync() } else { Ok(()) });
+
+        if let Err(e) = result {
+            writer.poison(&e);
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    /// Seals the current journal file and starts a new one for the given memtable.
+    ///
+    /// Errors are logged and reported by [`Journal::sync`].
+    pub fn rotate(&self, memtable_id: MemtableId) {
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let mut writer = self.writer.lock().expect("lock is poisoned");
+
+        if self.sync_policy != JournalSyncPolicy::Never {
+            if let Err(e) = writer.sync() {
+                writer.poison(&e);
+            }
+        }
+
+        match Writer::create(&self.folder, memtable_id) {
+            Ok(mut new_writer) => {
+                new_writer.error = writer.error;
+                *writer = new_writer;
+            }
+            Err(e) => writer.poison(&e),
+        }
+    }
+
+    /// Starts a new journal file for the given memtable, deleting all other journal files.
+    ///
+    /// Used when the memtables are cleared.
+    ///
+    /// Errors are logged and reported by [`Journal::sync`].
+    pub fn reset(&self, memtable_id: MemtableId) {
+        self.rotate(memtable_id);
+
+        match list_journals(&self.folder) {
+            Ok(ids) => {
+                let ids = ids
+                    .into_iter()
+                    .filter(|&id| id != memtable_id)
+                    .collect::<Vec<_>>();
+
+                self.delete(&ids);
+            }
+            Err(e) => log::warn!("Failed to list journals: {e:?}"),
+        }
+    }
+
+    /// Deletes the journal files of the given (flushed) memtables.
+    ///
+    /// Failing to delete a journal file is not fatal, its writes are
+    /// already persisted, so replaying it again is harmless.
+    pub fn delete(&self, memtable_ids: &[MemtableId]) {
+        for &id in memtable_ids {
+            log::trace!("Deleting journal #{id}");
+
+            match std::fs::remove_file(self.folder.join(id.to_string())) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => log::warn!("Failed to delete journal #{id}: {e:?}"),
+            }
+        }
+    }
+
+    /// Syncs the journal to disk.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or if a previous write to the journal failed.
+    #[expect(
+        clippy::significant_drop_tightening,
+        reason = "lock needs to be held while syncing"
+    )]
+    pub fn sync(&self) -> crate::Result<()> {
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let mut writer = self.writer.lock().expect("lock is poisoned");
+
+        writer.check()?;
+        writer.sync()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InternalValue, ValueType};
+    use std::io::Seek;
+    use test_log::test;
+
+    #[test]
+    fn journal_append_after_failed_write() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let journal =
+            Journal::create_new(dir.path().join("journals"), JournalSyncPolicy::Never, 0)?;
+
+        let record = Record {
+            items: vec![InternalValue::from_components(
+                "a",
+                "abc",
+                0,
+                ValueType::Value,
+            )],
+            range_tombstones: vec![],
+        };
+
+        journal.append(&record, false)?;
+
+        journal
+            .writer
+            .lock()
+            .expect("lock is poisoned")
+            .poison(&std::io::Error::other("simulated write failure"));
+
+        assert!(journal.append(&record, false).is_err());
+        assert!(journal.sync().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn journal_tree_try_write_after_failed_write() -> crate::Result<()> {
+        use crate::{AbstractTree, AnyTree, Config, SequenceNumberCounter};
+
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .with_journal(Some(JournalSyncPolicy::PerWrite))
+        .open()?;
+
+        let AnyTree::Standard(inner) = &tree else {
+            unreachable!("should be standard tree");
+        };
+
+        inner
+            .journal
+            .as_ref()
+            .expect("should have journal")
+            .writer
+            .lock()
+            .expect("lock is poisoned")
+            .poison(&std::io::Error::other("simulated write failure"));
+
+        // NOTE: Fallible writes are rejected...
+        assert!(tree.try_insert("a", "abc", 0).is_err());
+        assert!(tree.try_remove("b", 0).is_err());
+        assert!(tree.try_remove_weak("b", 0).is_err());
+        assert!(tree.try_merge("b", "abc", 0).is_err());
+        assert!(tree.try_remove_range("a".."z", 0).is_err());
+        assert!(tree.is_empty(1, None)?);
+        assert_eq!(0, tree.active_memtable().len());
+
+        // ...while infallible writes are still applied
+        tree.insert("a", "abc", 1);
+        assert!(tree.contains_key("a", 2)?);
+        assert!(tree.sync_journal().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn journal_recover_torn_tail() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let folder = dir.path().join("journals");
+
+        {
+            let journal = Journal::create_new(folder.clone(), JournalSyncPolicy::PerWrite, 0)?;
+
+            for seqno in 0..10 {
+                let item = InternalValue::from_components("a", "abc", seqno, ValueType::Value);
+                journal.append(
+                    &Record {
+                        items: vec![item],
+                        range_tombstones: vec![],
+                    },
+                    false,
+                )?;
+            }
+        }
+
+        // NOTE: Cut off half of the last record
+        {
+            let mut file = std::fs::OpenOptions::new()
+                .write(true)
+                .open(folder.join("0"))?;
+            let len = file.seek(std::io::SeekFrom::End(0))?;
+            file.set_len(len - 5)?;
+        }
+
+        let (journal, memtable) = Journal::recover(folder.clone(), JournalSyncPolicy::PerWrite)?;
+        assert_eq!(1, memtable.id());
+        assert_eq!(9, memtable.len());
+        assert_eq!(vec![1], list_journals(&folder)?);
+
+        // NOTE: Writes after recovery are not hidden by the torn record
+        journal.append(
+            &Record {
+                items: vec![InternalValue::from_components(
+                    "b",
+                    "abc",
+                    10,
+                    ValueType::Value,
+                )],
+                range_tombstones: vec![],
+            },
+            false,
+        )?;
+        drop(journal);
+
+        let (_, memtable) = Journal::recover(folder.clone(), JournalSyncPolicy::PerWrite)?;
+        assert_eq!(2, memtable.id());
+        assert_eq!(10, memtable.len());
+
+        Ok(())
+    }
+}
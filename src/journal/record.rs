@@ -0,0 +1,148 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    coding::{Decode, Encode},
+    InternalValue, RangeTombstone, UserKey, ValueType,
+};
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use std::io::{Read, Write};
+
+/// A journal record
+///
+/// Contains all writes that are applied to the memtable as one unit,
+/// so either a single write or a whole write batch.
+#[derive(Debug, Default)]
+pub struct Record {
+    pub items: Vec<InternalValue>,
+    pub range_tombstones: Vec<RangeTombstone>,
+}
+
+impl Record {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.range_tombstones.is_empty()
+    }
+}
+
+fn write_key<W: Write>(writer: &mut W, key: &[u8]) -> crate::Result<()> {
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "keys are limited to 16-bit length"
+    )]
+    writer.write_u16::<LE>(key.len() as u16)?;
+    writer.write_all(key)?;
+    Ok(())
+}
+
+fn read_key<R: Read>(reader: &mut R) -> crate::Result<UserKey> {
+    let len = reader.read_u16::<LE>()?;
+    Ok(UserKey::from_reader(reader, len.into())?)
+}
+
+impl Encode for Record {
+    fn encode_into<W: Write>(&self, writer: &mut W) -> Result<(), crate::Error> {
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "a record never contains 4 billion writes"
+        )]
+        writer.write_u32::<LE>(self.items.len() as u32)?;
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "a record never contains 4 billion writes"
+        )]
+        writer.write_u32::<LE>(self.range_tombstones.len() as u32)?;
+
+        for item in &self.items {
+            writer.write_u64::<LE>(item.key.seqno)?;
+            writer.write_u8(u8::from(item.key.value_type))?;
+            write_key(writer, &item.key.user_key)?;
+
+            #[expect(
+                clippy::cast_possible_truncation,
+                reason = "values are limited to 32-bit length"
+            )]
+            writer.write_u32::<LE>(item.value.len() as u32)?;
+            writer.write_all(&item.value)?;
+        }
+
+        for tombstone in &self.range_tombstones {
+            writer.write_u64::<LE>(tombstone.seqno)?;
+            write_key(writer, &tombstone.start)?;
+            write_key(writer, &tombstone.end)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Decode for Record {
+    fn decode_from<R: Read>(reader: &mut R) -> Result<Self, crate::Error> {
+        let item_count = reader.read_u32::<LE>()? as usize;
+        let range_tombstone_count = reader.read_u32::<LE>()? as usize;
+
+        let mut items = Vec::with_capacity(item_count);
+
+        for _ in 0..item_count {
+            let seqno = reader.read_u64::<LE>()?;
+
+            let value_type = reader.read_u8()?;
+            let value_type = ValueType::try_from(value_type)
+                .map_err(|()| crate::Error::InvalidTag(("ValueType", value_type)))?;
+
+            let key = read_key(reader)?;
+
+            let value_len = reader.read_u32::<LE>()?;
+            let value = crate::UserValue::from_reader(reader, value_len as usize)?;
+
+            items.push(InternalValue::from_components(
+                key, value, seqno, value_type,
+            ));
+        }
+
+        let mut range_tombstones = Vec::with_capacity(range_tombstone_count);
+
+        for _ in 0..range_tombstone_count {
+            let seqno = reader.read_u64::<LE>()?;
+            let start = read_key(reader)?;
+            let end = read_key(reader)?;
+            range_tombstones.push(RangeTombstone::new(start, end, seqno));
+        }
+
+        Ok(Self {
+            items,
+            range_tombstones,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn journal_record_roundtrip() -> crate::Result<()> {
+        let record = Record {
+            items: vec![
+                InternalValue::from_components("a", "abc", 5, ValueType::Value),
+                InternalValue::from_components("b", "", 6, ValueType::WeakTombstone),
+                InternalValue::from_components("c", "+1", 7, ValueType::Merge),
+            ],
+            range_tombstones: vec![RangeTombstone::new("d", "f", 8)],
+        };
+
+        let bytes = record.encode_into_vec();
+        let decoded = Record::decode_from(&mut &bytes[..])?;
+
+        assert_eq!(record.items.len(), decoded.items.len());
+        for (a, b) in record.items.iter().zip(&decoded.items) {
+            assert_eq!(a.key, b.key);
+            assert_eq!(a.value, b.value);
+        }
+        assert_eq!(record.range_tombstones, decoded.range_tombstones);
+
+        Ok(())
+    }
+}
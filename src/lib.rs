@@ -8,8 +8,9 @@
 //!
 //! > This crate only provides a primitive LSM-tree, not a full storage engine.
 //! > You probably want to use <https://crates.io/crates/fjall> instead.
-//! > For example, it does not ship with a write-ahead log, so writes are not
-//! > persisted until manually flushing the memtable.
+//! > For example, it does not ship with a full write-ahead log, so writes are not
+//! > persisted until manually flushing the memtable, unless the optional
+//! > journal is enabled using `Config::with_journal`.
 //!
 //! ##### About
 //!
@@ -101,6 +102,7 @@ pub mod file;
 mod hash;
 mod ingestion;
 mod iter_guard;
+mod journal;
 mod key;
 mod key_range;
 mod manifest;
@@ -121,13 +123,17 @@ pub(crate) mod metrics;
 pub mod mvcc_stream;
 
 mod path;
+mod prefix;
 
 #[doc(hidden)]
 pub mod range;
 
+mod range_tombstone;
+
 #[doc(hidden)]
 pub mod table;
 
+mod scheduler;
 mod seqno;
 mod slice;
 mod slice_windows;
@@ -144,8 +150,10 @@ pub mod util;
 
 mod value;
 mod value_type;
+mod verify;
 mod version;
 mod vlog;
+mod write_batch;
 
 /// User defined key (byte array)
 pub type UserKey = Slice;
@@ -163,6 +171,7 @@ pub use {
     iter_guard::IterGuardImpl,
     key_range::KeyRange,
     merge::BoxedIterator,
+    range_tombstone::RangeTombstone,
     slice::Builder,
     table::{GlobalTableId, Table, TableId},
     tree::inner::TreeId,
@@ -174,22 +183,28 @@ pub use {
     any_tree::AnyTree,
     blob_tree::BlobTree,
     cache::Cache,
-    compression::CompressionType,
+    checksum::ChecksumType,
+    compression::{CompressionDictionary, CompressionType},
     config::{Config, KvSeparationOptions, TreeType},
     descriptor_table::DescriptorTable,
     error::{Error, Result},
     format_version::FormatVersion,
     ingestion::AnyIngestion,
     iter_guard::IterGuard as Guard,
+    journal::JournalSyncPolicy,
     memtable::{Memtable, MemtableId},
     merge_operator::{MergeOperator, MergeResult},
+    prefix::{FixedPrefixExtractor, PrefixExtractor},
     r#abstract::AbstractTree,
+    scheduler::{Scheduler, SchedulerOptions},
     seqno::SequenceNumberCounter,
     slice::Slice,
     tree::Tree,
     value::SeqNo,
     value_type::ValueType,
+    verify::{CorruptedFile, Corruption, MissingBlobFile, VerificationReport},
     vlog::BlobFile,
+    write_batch::WriteBatch,
 };
 
 #[cfg(feature = "metrics")]
@@ -4,12 +4,14 @@
 
 use crate::key::InternalKey;
 use crate::{
+    range_tombstone::{RangeTombstone, RangeTombstoneSet},
     value::{InternalValue, SeqNo, UserValue},
     ValueType,
 };
 use crossbeam_skiplist::SkipMap;
 use std::ops::RangeBounds;
 use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::RwLock;
 
 pub use crate::tree::inner::MemtableId;
 
@@ -24,6 +26,15 @@ pub struct Memtable {
     #[doc(hidden)]
     pub items: SkipMap<InternalKey, UserValue>,
 
+    /// Range tombstones, in insertion order
+    range_tombstones: RwLock<Vec<RangeTombstone>>,
+
+    /// Fragmented range tombstones, built on first use and reset on insert
+    range_tombstone_set: RwLock<Option<RangeTombstoneSet>>,
+
+    /// Set once a range tombstone is inserted, so reads can skip locking the range tombstones
+    has_range_tombstones: AtomicBool,
+
     /// Approximate active memtable size.
     ///
     /// If this grows too large, a flush is triggered.
@@ -61,6 +72,9 @@ impl Memtable {
         Self {
             id,
             items: SkipMap::default(),
+            range_tombstones: RwLock::default(),
+            range_tombstone_set: RwLock::default(),
+            has_range_tombstones: AtomicBool::default(),
             approximate_size: AtomicU64::default(),
             highest_seqno: AtomicU64::default(),
             requested_rotation: AtomicBool::default(),
@@ -130,10 +144,6 @@ impl Memtable {
     /// This is used for merge operator support to collect all merge operands.
     #[doc(hidden)]
     pub fn range_for_key(&self, key: &[u8], seqno: SeqNo) -> Vec<InternalValue> {
-        if seqno == 0 {
-            return Vec::new();
-        }
-
         // NOTE: Due to InternalKey's Ord impl using Reverse(seqno), we need to use
         // seqno (not seqno-1) to include entries with the requested seqno.
         // The range starts from the key with the requested seqno.
@@ -163,7 +173,101 @@ impl Memtable {
     /// Returns `true` if the memtable is empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.items.is_empty()
+        self.items.is_empty() && !self.has_range_tombstones()
+    }
+
+    #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+    fn range_tombstones_read(&self) -> std::sync::RwLockReadGuard<'_, Vec<RangeTombstone>> {
+        self.range_tombstones.read().expect("lock is poisoned")
+    }
+
+    /// Returns `true` if the memtable contains range tombstones.
+    pub(crate) fn has_range_tombstones(&self) -> bool {
+        self.has_range_tombstones
+            .load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    /// Returns all range tombstones.
+    pub(crate) fn range_tombstones(&self) -> Vec<RangeTombstone> {
+        self.range_tombstones_read().clone()
+    }
+
+    /// Returns the fragmented range tombstones.
+    ///
+    /// The fragments are cached until the next range tombstone is inserted.
+    #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+    #[expect(
+        clippy::significant_drop_tightening,
+        reason = "the range tombstones need to stay locked until the fragments are cached"
+    )]
+    pub(crate) fn range_tombstone_set(&self) -> RangeTombstoneSet {
+        if !self.has_range_tombstones() {
+            return RangeTombstoneSet::default();
+        }
+
+        if let Some(set) = &*self.range_tombstone_set.read().expect("lock is poisoned") {
+            return set.clone();
+        }
+
+        // IMPORTANT: Hold the read lock while caching the fragments,
+        // so no tombstone can be inserted in the meantime
+        let range_tombstones = self.range_tombstones_read();
+        let set = RangeTombstoneSet::new(range_tombstones.iter().cloned());
+
+        *self.range_tombstone_set.write().expect("lock is poisoned") = Some(set.clone());
+
+        set
+    }
+
+    /// Returns the highest sequence number of a range tombstone
+    /// containing the key that is visible at the given sequence number.
+    pub(crate) fn max_covering_seqno(&self, key: &[u8], seqno: SeqNo) -> Option<SeqNo> {
+        if !self.has_range_tombstones() {
+            return None;
+        }
+
+        self.range_tombstones_read()
+            .iter()
+            .filter(|rt| crate::range::seqno_filter(rt.seqno, seqno) && rt.contains_key(key))
+            .map(|rt| rt.seqno)
+            .max()
+    }
+
+    /// Inserts a range tombstone into the memtable
+    #[doc(hidden)]
+    pub fn insert_range_tombstone(&self, tombstone: RangeTombstone) -> (u64, u64) {
+        #[expect(
+            clippy::expect_used,
+            reason = "keys are limited to 16-bit length, so tombstones cannot exceed u64"
+        )]
+        let item_size =
+            (tombstone.start.len() + tombstone.end.len() + std::mem::size_of::<RangeTombstone>())
+                .try_into()
+                .expect("should fit into u64");
+
+        let size_before = self
+            .approximate_size
+            .fetch_add(item_size, std::sync::atomic::Ordering::AcqRel);
+
+        self.highest_seqno
+            .fetch_max(tombstone.seqno, std::sync::atomic::Ordering::AcqRel);
+
+        // IMPORTANT: Reset the cached fragments before unlocking the range tombstones
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let mut range_tombstones = self.range_tombstones.write().expect("lock is poisoned");
+        range_tombstones.push(tombstone);
+
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        {
+            *self.range_tombstone_set.write().expect("lock is poisoned") = None;
+        }
+
+        self.has_range_tombstones
+            .store(true, std::sync::atomic::Ordering::Release);
+
+        drop(range_tombstones);
+
+        (item_size, size_before + item_size)
     }
 
     /// Inserts an item into the memtable
@@ -3,13 +3,20 @@
 // (found in the LICENSE-* files in the repository)
 
 use crate::double_ended_peekable::{DoubleEndedPeekable, DoubleEndedPeekableExt};
-use crate::{InternalValue, UserKey};
+use crate::{
+    merge_operator::{MergeOperator, MergeResult},
+    InternalValue, UserKey, UserValue, ValueType,
+};
+use std::sync::Arc;
 
 /// Consumes a stream of KVs and emits a new stream according to MVCC and tombstone rules
 ///
 /// This iterator is used for read operations.
 pub struct MvccStream<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>> {
     inner: DoubleEndedPeekable<crate::Result<InternalValue>, I>,
+
+    /// Optional merge operator for resolving merge operands
+    merge_operator: Option<Arc<dyn MergeOperator>>,
 }
 
 impl<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>> MvccStream<I> {
@@ -18,9 +25,20 @@ impl<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>> MvccStream<I>
     pub fn new(iter: I) -> Self {
         Self {
             inner: iter.double_ended_peekable(),
+            merge_operator: None,
         }
     }
 
+    /// Sets the merge operator for resolving merge operands.
+    ///
+    /// If set, a key whose latest version is a merge operand is emitted as
+    /// the merged value, the same way a point read would return it.
+    #[must_use]
+    pub fn merge_operator(mut self, op: Option<Arc<dyn MergeOperator>>) -> Self {
+        self.merge_operator = op;
+        self
+    }
+
     // Drains all entries for the given user key from the front of the iterator.
     fn drain_key_min(&mut self, key: &UserKey) -> crate::Result<()> {
         loop {
@@ -37,6 +55,90 @@ impl<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>> MvccStream<I>
             next?;
         }
     }
+
+    /// Collects the older merge operands (newest to oldest) and the base value
+    /// of the given key from the front of the iterator.
+    ///
+    /// All remaining versions of the key are drained.
+    fn collect_merge_operands_min(
+        &mut self,
+        key: &UserKey,
+        operands: &mut Vec<UserValue>,
+    ) -> crate::Result<Option<InternalValue>> {
+        loop {
+            let Some(next) = self.inner.next_if(|kv| {
+                if let Ok(kv) = kv {
+                    kv.key.user_key == key
+                } else {
+                    true
+                }
+            }) else {
+                return Ok(None);
+            };
+
+            let next = next?;
+
+            if next.key.value_type == ValueType::Merge {
+                operands.push(next.value);
+            } else {
+                self.drain_key_min(key)?;
+                return Ok(Some(next));
+            }
+        }
+    }
+
+    /// Applies the merge operator to the merge operands (newest to oldest) of a key.
+    ///
+    /// Tombstones end the merge chain, so they are treated like a missing base value.
+    fn resolve_merge(
+        merge_op: &dyn MergeOperator,
+        head: InternalValue,
+        mut operands: Vec<UserValue>,
+        base_value: Option<&InternalValue>,
+    ) -> InternalValue {
+        let base_value = base_value
+            .filter(|v| !v.key.is_tombstone())
+            .map(|v| &v.value);
+
+        operands.reverse();
+
+        match merge_op.full_merge(&head.key.user_key, base_value, &operands) {
+            MergeResult::Success(merged_value) => InternalValue::from_components(
+                head.key.user_key,
+                merged_value,
+                head.key.seqno,
+                ValueType::Value,
+            ),
+            MergeResult::Failure => {
+                // Merge failed - return the newest operand as-is to avoid data loss
+                log::warn!(
+                    "Merge operator '{}' failed for key {:?}, returning newest operand",
+                    merge_op.name(),
+                    head.key.user_key,
+                );
+                head
+            }
+        }
+    }
+
+    /// Builds the item for the latest version of a key, after it has been reached from the back.
+    ///
+    /// `operands` are the older merge operands (oldest to newest) above `base_value`.
+    fn finish_key_back(
+        &self,
+        tail: InternalValue,
+        mut operands: Vec<UserValue>,
+        base_value: Option<&InternalValue>,
+    ) -> InternalValue {
+        match &self.merge_operator {
+            Some(merge_op) if tail.key.value_type == ValueType::Merge => {
+                operands.push(tail.value.clone());
+                operands.reverse();
+                Self::resolve_merge(merge_op.as_ref(), tail, operands, base_value)
+            }
+            _ => tail,
+        }
+    }
 }
 
 impl<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>> Iterator for MvccStream<I> {
@@ -45,6 +147,22 @@ impl<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>> Iterator for M
     fn next(&mut self) -> Option<Self::Item> {
         let head = fail_iter!(self.inner.next()?);
 
+        if head.key.value_type == ValueType::Merge {
+            if let Some(merge_op) = self.merge_operator.clone() {
+                let mut operands = vec![head.value.clone()];
+
+                let base_value =
+                    fail_iter!(self.collect_merge_operands_min(&head.key.user_key, &mut operands));
+
+                return Some(Ok(Self::resolve_merge(
+                    merge_op.as_ref(),
+                    head,
+                    operands,
+                    base_value.as_ref(),
+                )));
+            }
+        }
+
         // As long as items are the same key, ignore them
         fail_iter!(self.drain_key_min(&head.key.user_key));
 
@@ -56,6 +174,12 @@ impl<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>> DoubleEndedIte
     for MvccStream<I>
 {
     fn next_back(&mut self) -> Option<Self::Item> {
+        // NOTE: Versions of a key are visited from oldest to newest here,
+        // so we only need to remember the merge operands that are newer
+        // than the latest non-merge version (the base value)
+        let mut operands = vec![];
+        let mut base_value = None;
+
         loop {
             let tail = fail_iter!(self.inner.next_back()?);
 
@@ -73,12 +197,29 @@ impl<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>> DoubleEndedIte
                         .expect_err("should be error")));
                 }
                 None => {
-                    return Some(Ok(tail));
+                    return Some(Ok(self.finish_key_back(
+                        tail,
+                        operands,
+                        base_value.as_ref(),
+                    )));
                 }
             };
 
             if prev.key.user_key < tail.key.user_key {
-                return Some(Ok(tail));
+                return Some(Ok(self.finish_key_back(
+                    tail,
+                    operands,
+                    base_value.as_ref(),
+                )));
+            }
+
+            if self.merge_operator.is_some() {
+                if tail.key.value_type == ValueType::Merge {
+                    operands.push(tail.value);
+                } else {
+                    operands.clear();
+                    base_value = Some(tail);
+                }
             }
         }
     }
@@ -103,6 +244,7 @@ mod tests {
                   "V" => ValueType::Value,
                   "T" => ValueType::Tombstone,
                   "W" => ValueType::WeakTombstone,
+                  "M" => ValueType::Merge,
                   _ => panic!("Unknown value type"),
               };
 
@@ -685,4 +827,162 @@ mod tests {
 
         Ok(())
     }
+
+    /// Concatenates operands onto the base value
+    struct ConcatMerge;
+
+    impl MergeOperator for ConcatMerge {
+        fn name(&self) -> &'static str {
+            "ConcatMerge"
+        }
+
+        fn full_merge(
+            &self,
+            _key: &UserKey,
+            existing_value: Option<&UserValue>,
+            operands: &[UserValue],
+        ) -> MergeResult {
+            let mut v = existing_value.map(|v| v.to_vec()).unwrap_or_default();
+
+            for operand in operands {
+                v.extend_from_slice(operand);
+            }
+
+            MergeResult::Success(v.into())
+        }
+    }
+
+    fn merge_stream<I: DoubleEndedIterator<Item = crate::Result<InternalValue>>>(
+        iter: I,
+    ) -> MvccStream<I> {
+        MvccStream::new(iter).merge_operator(Some(Arc::new(ConcatMerge)))
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn mvcc_stream_merge_simple() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "c", "M",
+          "a", "b", "M",
+          "a", "a", "V",
+          "a", "old", "V",
+          "b", "b", "V",
+        ];
+
+        let mut iter = merge_stream(vec.iter().cloned().map(Ok));
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"abc", 999, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"b", *b"b", 999, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        let mut iter = merge_stream(vec.iter().cloned().map(Ok));
+
+        assert_eq!(
+            InternalValue::from_components(*b"b", *b"b", 999, ValueType::Value),
+            iter.next_back().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"abc", 999, ValueType::Value),
+            iter.next_back().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn mvcc_stream_merge_no_base() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "b", "M",
+          "a", "a", "M",
+          "b", "b", "M",
+          "b", "", "T",
+          "b", "old", "V",
+        ];
+
+        let mut iter = merge_stream(vec.iter().cloned().map(Ok));
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"ab", 999, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"b", *b"b", 999, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        let mut iter = merge_stream(vec.iter().cloned().map(Ok));
+
+        assert_eq!(
+            InternalValue::from_components(*b"b", *b"b", 999, ValueType::Value),
+            iter.next_back().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"ab", 999, ValueType::Value),
+            iter.next_back().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn mvcc_stream_merge_shadowed() -> crate::Result<()> {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "new", "V",
+          "a", "b", "M",
+          "a", "a", "V",
+          "b", "", "T",
+          "b", "b", "M",
+        ];
+
+        let mut iter = merge_stream(vec.iter().cloned().map(Ok));
+
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"new", 999, ValueType::Value),
+            iter.next().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"b", *b"", 999, ValueType::Tombstone),
+            iter.next().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        let mut iter = merge_stream(vec.iter().cloned().map(Ok));
+
+        assert_eq!(
+            InternalValue::from_components(*b"b", *b"", 999, ValueType::Tombstone),
+            iter.next_back().unwrap()?,
+        );
+        assert_eq!(
+            InternalValue::from_components(*b"a", *b"new", 999, ValueType::Value),
+            iter.next_back().unwrap()?,
+        );
+        iter_closed!(iter);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mvcc_stream_merge_without_operator() {
+        #[rustfmt::skip]
+        let vec = stream![
+          "a", "b", "M",
+          "a", "a", "V",
+        ];
+
+        test_reverse!(vec);
+    }
 }
@@ -0,0 +1,75 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+//! Prefix extractors for prefix bloom filters.
+//!
+//! If a tree has a prefix extractor and its filter policy uses
+//! [`FilterPolicyEntry::PrefixBloom`](crate::config::FilterPolicyEntry::PrefixBloom),
+//! the prefix of every key is inserted into the table filters as well.
+//! Prefix scans can then skip tables that cannot contain the prefix.
+
+use std::borrow::Cow;
+
+/// Trait for extracting the prefix of a key.
+///
+/// # Contract
+///
+/// If `extract(p)` returns `Some(x)`, every key that starts with `p` needs to
+/// extract to `x` as well, otherwise prefix scans may miss items.
+///
+/// The extractor's name is stored in the tables, so changing the extractor
+/// does not break filtering: tables that were written using another extractor
+/// are simply not filtered by prefix.
+///
+/// # Example
+///
+/// ```
+/// use lsm_tree::PrefixExtractor;
+/// use std::borrow::Cow;
+///
+/// /// Extracts everything up to (and including) the first `:`
+/// struct ColonPrefix;
+///
+/// impl PrefixExtractor for ColonPrefix {
+///     fn name(&self) -> Cow<'static, str> {
+///         Cow::Borrowed("ColonPrefix")
+///     }
+///
+///     fn extract<'a>(&self, key: &'a [u8]) -> Option<&'a [u8]> {
+///         let idx = key.iter().position(|&b| b == b':')?;
+///         key.get(..=idx)
+///     }
+/// }
+///
+/// assert_eq!(Some(&b"user:"[..]), ColonPrefix.extract(b"user:123"));
+/// assert_eq!(None, ColonPrefix.extract(b"user"));
+/// ```
+pub trait PrefixExtractor: Send + Sync {
+    /// Returns the name of the prefix extractor.
+    ///
+    /// The name is persisted in tables, so it should be unique and never change.
+    /// Two extractors with the same name need to return the same prefixes.
+    fn name(&self) -> Cow<'static, str>;
+
+    /// Returns the prefix of a key.
+    ///
+    /// Returns `None` if the key has no prefix (it is outside of the extractor's domain).
+    fn extract<'a>(&self, key: &'a [u8]) -> Option<&'a [u8]>;
+}
+
+/// Extracts the first `n` bytes of a key.
+///
+/// Keys that are shorter than `n` bytes have no prefix.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FixedPrefixExtractor(pub usize);
+
+impl PrefixExtractor for FixedPrefixExtractor {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("fixed:{}", self.0))
+    }
+
+    fn extract<'a>(&self, key: &'a [u8]) -> Option<&'a [u8]> {
+        key.get(..self.0)
+    }
+}
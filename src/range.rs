@@ -6,14 +6,19 @@ use crate::{
     key::InternalKey,
     memtable::Memtable,
     merge::Merger,
+    merge_operator::MergeOperator,
     mvcc_stream::MvccStream,
+    prefix::PrefixExtractor,
+    range_tombstone::RangeTombstoneSet,
     run_reader::RunReader,
+    table::{filter::standard_bloom::Builder, Table},
     value::{SeqNo, UserKey},
-    version::SuperVersion,
+    version::{Level, Run, SuperVersion},
     BoxedIterator, InternalValue,
 };
 use self_cell::self_cell;
 use std::{
+    borrow::Cow,
     ops::{Bound, RangeBounds},
     sync::Arc,
 };
@@ -62,12 +67,50 @@ pub fn prefix_to_range(prefix: &[u8]) -> (Bound<UserKey>, Bound<UserKey>) {
     (Included(prefix.into()), prefix_upper_range(prefix))
 }
 
+/// Hashed key prefix of a prefix scan, used to skip tables
+/// whose filter does not contain the prefix
+#[derive(Clone, Debug)]
+pub struct PrefixFilter {
+    extractor_name: Cow<'static, str>,
+    hash: u64,
+}
+
+impl PrefixFilter {
+    /// Creates the prefix filter for a prefix scan.
+    ///
+    /// Returns `None` if the prefix is outside of the extractor's domain.
+    #[must_use]
+    pub fn new(extractor: &dyn PrefixExtractor, prefix: &[u8]) -> Option<Self> {
+        let extracted = extractor.extract(prefix)?;
+
+        Some(Self {
+            extractor_name: extractor.name(),
+            hash: Builder::get_hash(extracted),
+        })
+    }
+
+    /// Returns `false` if the table definitely contains no key with the prefix.
+    fn may_match(&self, table: &Table) -> bool {
+        // NOTE: If the filter cannot be loaded, we fall back to reading the table,
+        // which will surface the I/O error to the caller
+        table
+            .maybe_contains_prefix(&self.extractor_name, self.hash)
+            .unwrap_or(true)
+    }
+}
+
 /// The iter state references the memtables used while the range is open
 ///
 /// Because of Rust rules, the state is referenced using `self_cell`, see below.
 pub struct IterState {
     pub(crate) version: SuperVersion,
     pub(crate) ephemeral: Option<(Arc<Memtable>, SeqNo)>,
+
+    /// Merge operator used to resolve merge operands, if any
+    pub(crate) merge_operator: Option<Arc<dyn MergeOperator>>,
+
+    /// Prefix filter used to skip tables and sealed memtables, if any
+    pub(crate) prefix_filter: Option<PrefixFilter>,
 }
 
 type BoxedMerge<'a> = Box<dyn DoubleEndedIterator<Item = crate::Result<InternalValue>> + Send + 'a>;
@@ -147,56 +190,16 @@ impl TreeIter {
 
             let mut iters: Vec<BoxedIterator<'_>> = Vec::with_capacity(5);
 
-            for run in lock
-                .version
-                .version
-                .iter_levels()
-                .flat_map(|lvl| lvl.iter())
-            {
-                match run.len() {
-                    0 => {
-                        // Do nothing
-                    }
-                    1 => {
-                        #[expect(clippy::expect_used, reason = "we checked for length")]
-                        let table = run.first().expect("should exist");
-
-                        if table.check_key_range_overlap(&(
-                            range.start_bound().map(|x| &*x.user_key),
-                            range.end_bound().map(|x| &*x.user_key),
-                        )) {
-                            let reader = table
-                                .range((
-                                    range.start_bound().map(|x| &x.user_key).cloned(),
-                                    range.end_bound().map(|x| &x.user_key).cloned(),
-                                ))
-                                .filter(move |item| match item {
-                                    Ok(item) => seqno_filter(item.key.seqno, seqno),
-                                    Err(_) => true,
-                                });
-
-                            iters.push(Box::new(reader));
-                        }
-                    }
-                    _ => {
-                        if let Some(reader) = RunReader::new(
-                            run.clone(),
-                            (
-                                range.start_bound().map(|x| &x.user_key).cloned(),
-                                range.end_bound().map(|x| &x.user_key).cloned(),
-                            ),
-                        ) {
-                            iters.push(Box::new(reader.filter(move |item| match item {
-                                Ok(item) => seqno_filter(item.key.seqno, seqno),
-                                Err(_) => true,
-                            })));
-                        }
-                    }
-                }
-            }
+            Self::collect_table_readers(lock, &range, seqno, &mut iters);
 
             // Sealed memtables
             for memtable in lock.version.sealed_memtables.iter() {
+                // NOTE: Sealed memtables do not change anymore, so if the prefix filter is active
+                // we can cheaply check if there is anything to read at all
+                if lock.prefix_filter.is_some() && memtable.range(range.clone()).next().is_none() {
+                    continue;
+                }
+
                 let iter = memtable.range(range.clone());
 
                 iters.push(Box::new(
@@ -217,15 +220,16 @@ impl TreeIter {
 
             if let Some((mt, seqno)) = &lock.ephemeral {
                 let iter = Box::new(
-                    mt.range(range)
+                    mt.range(range.clone())
                         .filter(move |item| seqno_filter(item.key.seqno, *seqno))
                         .map(Ok),
                 );
                 iters.push(iter);
             }
 
-            let merged = Merger::new(iters);
-            let iter = MvccStream::new(merged);
+            let merged = Self::skip_range_deleted(Merger::new(iters), lock, &range, seqno);
+
+            let iter = MvccStream::new(merged).merge_operator(lock.merge_operator.clone());
 
             Box::new(iter.filter(|x| match x {
                 Ok(value) => !value.key.is_tombstone(),
@@ -233,6 +237,164 @@ impl TreeIter {
             }))
         })
     }
+
+    /// Adds readers for all tables that may contain items inside the range.
+    fn collect_table_readers<'a>(
+        lock: &'a IterState,
+        range: &(Bound<InternalKey>, Bound<InternalKey>),
+        seqno: SeqNo,
+        iters: &mut Vec<BoxedIterator<'a>>,
+    ) {
+        let bounds = (
+            range.start_bound().map(|x| &*x.user_key),
+            range.end_bound().map(|x| &*x.user_key),
+        );
+
+        let user_range = (
+            range.start_bound().map(|x| &x.user_key).cloned(),
+            range.end_bound().map(|x| &x.user_key).cloned(),
+        );
+
+        for run in lock
+            .version
+            .version
+            .iter_levels()
+            .flat_map(|lvl| lvl.iter())
+        {
+            match run.len() {
+                0 => {
+                    // Do nothing
+                }
+                1 => {
+                    #[expect(clippy::expect_used, reason = "we checked for length")]
+                    let table = run.first().expect("should exist");
+
+                    if table.check_key_range_overlap(&bounds)
+                        && lock
+                            .prefix_filter
+                            .as_ref()
+                            .is_none_or(|f| f.may_match(table))
+                    {
+                        let reader =
+                            table
+                                .range(user_range.clone())
+                                .filter(move |item| match item {
+                                    Ok(item) => seqno_filter(item.key.seqno, seqno),
+                                    Err(_) => true,
+                                });
+
+                        iters.push(Box::new(reader));
+                    }
+                }
+                _ => {
+                    let run = match &lock.prefix_filter {
+                        Some(filter) => {
+                            let Some(run) = Self::cull_run(run, &user_range, filter) else {
+                                continue;
+                            };
+                            run
+                        }
+                        None => run.clone(),
+                    };
+
+                    if let Some(reader) = RunReader::new(run, user_range.clone()) {
+                        iters.push(Box::new(reader.filter(move |item| match item {
+                            Ok(item) => seqno_filter(item.key.seqno, seqno),
+                            Err(_) => true,
+                        })));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the tables of a run that overlap with the range and may contain the prefix.
+    fn cull_run(
+        run: &Run<Table>,
+        range: &(Bound<UserKey>, Bound<UserKey>),
+        filter: &PrefixFilter,
+    ) -> Option<Arc<Run<Table>>> {
+        let (lo, hi) = run.range_overlap_indexes(range)?;
+
+        let tables = run
+            .get(lo..=hi)?
+            .iter()
+            .filter(|table| filter.may_match(table))
+            .cloned()
+            .collect();
+
+        Run::new(tables).map(Arc::new)
+    }
+
+    /// Removes versions that are deleted by a range tombstone.
+    ///
+    /// This needs to happen before resolving the latest version of a key,
+    /// so older versions cannot be resurrected.
+    fn skip_range_deleted<'a>(
+        iter: Merger<BoxedIterator<'a>>,
+        lock: &IterState,
+        range: &(Bound<InternalKey>, Bound<InternalKey>),
+        seqno: SeqNo,
+    ) -> BoxedIterator<'a> {
+        let range_tombstones = Self::collect_range_tombstones(lock, range, seqno);
+
+        if range_tombstones.is_empty() {
+            return Box::new(iter);
+        }
+
+        Box::new(iter.filter(move |item| match item {
+            Ok(item) => {
+                !range_tombstones.iter().any(|(set, seqno)| {
+                    set.is_deleted_at(&item.key.user_key, item.key.seqno, *seqno)
+                })
+            }
+            Err(_) => true,
+        }))
+    }
+
+    /// Collects the range tombstones of all tables and memtables that may delete
+    /// items inside the range, and the sequence number they are read at.
+    ///
+    /// The tables and memtables keep their tombstones fragmented, so they
+    /// do not need to be fragmented again for every iterator.
+    fn collect_range_tombstones(
+        lock: &IterState,
+        range: &(Bound<InternalKey>, Bound<InternalKey>),
+        seqno: SeqNo,
+    ) -> Vec<(RangeTombstoneSet, SeqNo)> {
+        let bounds = (
+            range.start_bound().map(|x| &*x.user_key),
+            range.end_bound().map(|x| &*x.user_key),
+        );
+
+        let tables = lock
+            .version
+            .version
+            .iter_levels()
+            .flat_map(Level::range_tombstone_tables)
+            .filter(|table| table.check_key_range_overlap(&bounds))
+            .map(|table| table.range_tombstones.clone());
+
+        let memtables = lock
+            .version
+            .sealed_memtables
+            .iter()
+            .chain(std::iter::once(&lock.version.active_memtable))
+            .filter(|mt| mt.has_range_tombstones())
+            .map(|mt| mt.range_tombstone_set());
+
+        let ephemeral = lock
+            .ephemeral
+            .iter()
+            .filter(|(mt, _)| mt.has_range_tombstones())
+            .map(|(mt, seqno)| (mt.range_tombstone_set(), *seqno));
+
+        tables
+            .chain(memtables)
+            .map(|set| (set, seqno))
+            .chain(ephemeral)
+            .collect()
+    }
 }
 
 #[cfg(test)]
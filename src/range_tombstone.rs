@@ -0,0 +1,403 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{InternalValue, SeqNo, UserKey, ValueType};
+use std::sync::Arc;
+
+/// A range tombstone deletes all versions of the keys in `[start, end)`
+/// that are older than the tombstone itself
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RangeTombstone {
+    /// Start key (inclusive)
+    pub start: UserKey,
+
+    /// End key (exclusive)
+    pub end: UserKey,
+
+    /// Sequence number of the delete operation
+    pub seqno: SeqNo,
+}
+
+impl RangeTombstone {
+    /// Creates a new range tombstone.
+    #[must_use]
+    pub fn new<K: Into<UserKey>>(start: K, end: K, seqno: SeqNo) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+            seqno,
+        }
+    }
+
+    /// Returns `true` if the key is inside the tombstone's key range.
+    #[must_use]
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        &*self.start <= key && key < &*self.end
+    }
+
+    /// Returns `true` if the tombstone deletes the given version of a key.
+    #[must_use]
+    pub fn covers(&self, key: &[u8], seqno: SeqNo) -> bool {
+        seqno < self.seqno && self.contains_key(key)
+    }
+
+    /// Returns the highest key that is covered by the tombstone.
+    ///
+    /// Because the end key is exclusive, the returned key may not actually be covered,
+    /// unless the end key is the immediate successor of another key.
+    pub(crate) fn max_key(&self) -> UserKey {
+        match self.end.split_last() {
+            Some((0, head)) => head.into(),
+            _ => self.end.clone(),
+        }
+    }
+
+    /// Returns the part of the tombstone inside `[lo, hi)`, if any.
+    pub(crate) fn clip(&self, lo: Option<&[u8]>, hi: Option<&[u8]>) -> Option<Self> {
+        let start = match lo {
+            Some(lo) if lo > &*self.start => lo.into(),
+            _ => self.start.clone(),
+        };
+
+        let end = match hi {
+            Some(hi) if hi < &*self.end => hi.into(),
+            _ => self.end.clone(),
+        };
+
+        (start < end).then_some(Self {
+            start,
+            end,
+            seqno: self.seqno,
+        })
+    }
+
+    /// Encodes the tombstone as an item, so it can be stored in a data block.
+    ///
+    /// NOTE: The item needs to be a value, because tombstones do not store their value,
+    /// but the value holds the end key.
+    pub(crate) fn to_internal_value(&self) -> InternalValue {
+        InternalValue::from_components(
+            self.start.clone(),
+            self.end.clone(),
+            self.seqno,
+            ValueType::Value,
+        )
+    }
+
+    /// Decodes a tombstone that was stored as an item.
+    pub(crate) fn from_internal_value(item: InternalValue) -> Self {
+        Self {
+            start: item.key.user_key,
+            end: item.value,
+            seqno: item.key.seqno,
+        }
+    }
+}
+
+/// Returns the immediate successor of a key.
+pub fn successor(key: &[u8]) -> UserKey {
+    let mut key = key.to_vec();
+    key.push(0);
+    key.into()
+}
+
+/// Splits range tombstones into non-overlapping fragments.
+///
+/// Each fragment holds the sequence numbers of all tombstones that cover it,
+/// so the result is sorted by start key and then by sequence number (descending).
+/// Fragments that have the same start key always have the same end key.
+pub fn fragment<I: IntoIterator<Item = RangeTombstone>>(tombstones: I) -> Vec<RangeTombstone> {
+    let mut tombstones = tombstones
+        .into_iter()
+        .filter(|rt| rt.start < rt.end)
+        .collect::<Vec<_>>();
+    tombstones.sort_by(|a, b| a.start.cmp(&b.start));
+
+    let mut boundaries = tombstones
+        .iter()
+        .flat_map(|rt| [rt.start.clone(), rt.end.clone()])
+        .collect::<Vec<_>>();
+    boundaries.sort();
+    boundaries.dedup();
+
+    let mut fragments: Vec<RangeTombstone> = vec![];
+
+    // NOTE: Start index of the fragment group that was pushed last
+    let mut last_group = 0;
+
+    // NOTE: Sweep through the boundaries, keeping track of the tombstones
+    // that cover the current fragment
+    let mut pending = tombstones.iter().peekable();
+    let mut active: Vec<&RangeTombstone> = vec![];
+
+    for window in boundaries.windows(2) {
+        let [lo, hi] = window else {
+            continue;
+        };
+
+        // NOTE: End keys are boundaries as well, so every tombstone
+        // that does not end at `lo` covers the whole fragment
+        active.retain(|rt| rt.end > *lo);
+
+        while let Some(rt) = pending.next_if(|rt| rt.start <= *lo) {
+            active.push(rt);
+        }
+
+        if active.is_empty() {
+            continue;
+        }
+
+        let mut seqnos = active.iter().map(|rt| rt.seqno).collect::<Vec<_>>();
+        seqnos.sort_by(|a, b| b.cmp(a));
+        seqnos.dedup();
+
+        // NOTE: Extend the previous group if it is adjacent and has the same sequence numbers
+        if let Some(group) = fragments.get_mut(last_group..) {
+            let is_adjacent = group.first().is_some_and(|f| f.end == lo);
+
+            if is_adjacent && group.iter().map(|f| f.seqno).eq(seqnos.iter().copied()) {
+                for fragment in group {
+                    fragment.end = hi.clone();
+                }
+                continue;
+            }
+        }
+
+        last_group = fragments.len();
+
+        fragments.extend(
+            seqnos
+                .into_iter()
+                .map(|seqno| RangeTombstone::new(lo.clone(), hi.clone(), seqno)),
+        );
+    }
+
+    fragments
+}
+
+/// Drops fragments that are not visible to any snapshot anymore.
+///
+/// Of all fragments of a key range below the GC watermark, only the newest one
+/// needs to be kept, because it deletes everything the older ones delete.
+/// It can be dropped as well if `can_evict` returns `true`, meaning there is no
+/// data left that it could delete.
+pub fn gc(
+    fragments: &[RangeTombstone],
+    gc_seqno_threshold: SeqNo,
+    can_evict: impl Fn(&RangeTombstone) -> bool,
+) -> Vec<RangeTombstone> {
+    let mut result: Vec<RangeTombstone> = Vec::with_capacity(fragments.len());
+
+    for rt in fragments {
+        if rt.seqno < gc_seqno_threshold {
+            // NOTE: Fragments are sorted by start key and sequence number (descending),
+            // so if the last fragment has the same start key, it shadows this one
+            let is_shadowed = result
+                .last()
+                .is_some_and(|prev| prev.start == rt.start && prev.seqno < gc_seqno_threshold);
+
+            if is_shadowed || can_evict(rt) {
+                continue;
+            }
+        }
+
+        result.push(rt.clone());
+    }
+
+    result
+}
+
+/// A set of fragmented range tombstones
+///
+/// Used to look up the tombstones that cover a key.
+/// Cloning the set is cheap, because the fragments are shared.
+#[derive(Clone, Debug, Default)]
+pub struct RangeTombstoneSet(Arc<[RangeTombstone]>);
+
+impl RangeTombstoneSet {
+    /// Fragments the given range tombstones into a new set.
+    pub fn new<I: IntoIterator<Item = RangeTombstone>>(tombstones: I) -> Self {
+        Self(fragment(tombstones).into())
+    }
+
+    /// Returns the fragments, sorted by start key and sequence number (descending).
+    #[must_use]
+    pub fn fragments(&self) -> &[RangeTombstone] {
+        &self.0
+    }
+
+    /// Returns the fragments that contain the key, from newest to oldest.
+    #[must_use]
+    pub fn covering(&self, key: &[u8]) -> &[RangeTombstone] {
+        let idx = self.0.partition_point(|rt| &*rt.start <= key);
+
+        let Some(last) = idx.checked_sub(1).and_then(|idx| self.0.get(idx)) else {
+            return &[];
+        };
+
+        if !last.contains_key(key) {
+            return &[];
+        }
+
+        let group_start = self.0.partition_point(|rt| rt.start < last.start);
+
+        self.0.get(group_start..idx).unwrap_or_default()
+    }
+
+    /// Returns the highest sequence number of a tombstone containing the key
+    /// that is visible at the given sequence number.
+    #[must_use]
+    pub fn max_covering_seqno(&self, key: &[u8], seqno: SeqNo) -> Option<SeqNo> {
+        self.covering(key)
+            .iter()
+            .map(|rt| rt.seqno)
+            .find(|&rt_seqno| crate::range::seqno_filter(rt_seqno, seqno))
+    }
+
+    /// Returns `true` if the given version of a key is deleted by any tombstone
+    /// that is visible at the read sequence number.
+    #[must_use]
+    pub fn is_deleted_at(&self, key: &[u8], seqno: SeqNo, read_seqno: SeqNo) -> bool {
+        self.max_covering_seqno(key, read_seqno)
+            .is_some_and(|rt_seqno| rt_seqno > seqno)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn range_tombstone_fragment_overlapping() {
+        let fragments = fragment([
+            RangeTombstone::new(*b"a", *b"d", 5),
+            RangeTombstone::new(*b"b", *b"f", 7),
+        ]);
+
+        assert_eq!(
+            vec![
+                RangeTombstone::new(*b"a", *b"b", 5),
+                RangeTombstone::new(*b"b", *b"d", 7),
+                RangeTombstone::new(*b"b", *b"d", 5),
+                RangeTombstone::new(*b"d", *b"f", 7),
+            ],
+            fragments,
+        );
+    }
+
+    #[test]
+    fn range_tombstone_fragment_adjacent() {
+        let fragments = fragment([
+            RangeTombstone::new(*b"a", *b"c", 5),
+            RangeTombstone::new(*b"c", *b"e", 5),
+            RangeTombstone::new(*b"x", *b"z", 5),
+            RangeTombstone::new(*b"x", *b"z", 5),
+            RangeTombstone::new(*b"z", *b"a", 5),
+        ]);
+
+        assert_eq!(
+            vec![
+                RangeTombstone::new(*b"a", *b"e", 5),
+                RangeTombstone::new(*b"x", *b"z", 5),
+            ],
+            fragments,
+        );
+    }
+
+    #[test]
+    fn range_tombstone_fragment_nested() {
+        let fragments = fragment([
+            RangeTombstone::new(*b"a", *b"z", 1),
+            RangeTombstone::new(*b"c", *b"e", 3),
+            RangeTombstone::new(*b"a", *b"c", 2),
+            RangeTombstone::new(*b"d", *b"e", 1),
+        ]);
+
+        assert_eq!(
+            vec![
+                RangeTombstone::new(*b"a", *b"c", 2),
+                RangeTombstone::new(*b"a", *b"c", 1),
+                RangeTombstone::new(*b"c", *b"e", 3),
+                RangeTombstone::new(*b"c", *b"e", 1),
+                RangeTombstone::new(*b"e", *b"z", 1),
+            ],
+            fragments,
+        );
+    }
+
+    #[test]
+    fn range_tombstone_gc() {
+        let fragments = fragment([
+            RangeTombstone::new(*b"a", *b"c", 3),
+            RangeTombstone::new(*b"a", *b"c", 5),
+            RangeTombstone::new(*b"a", *b"c", 9),
+            RangeTombstone::new(*b"x", *b"z", 4),
+        ]);
+
+        assert_eq!(
+            vec![
+                RangeTombstone::new(*b"a", *b"c", 9),
+                RangeTombstone::new(*b"a", *b"c", 5),
+                RangeTombstone::new(*b"x", *b"z", 4),
+            ],
+            gc(&fragments, 7, |_| false),
+        );
+
+        assert_eq!(
+            vec![RangeTombstone::new(*b"a", *b"c", 9)],
+            gc(&fragments, 7, |_| true),
+        );
+    }
+
+    #[test]
+    fn range_tombstone_set_covering() {
+        let set = RangeTombstoneSet::new([
+            RangeTombstone::new(*b"a", *b"d", 5),
+            RangeTombstone::new(*b"b", *b"f", 7),
+        ]);
+
+        assert!(set.covering(b"0").is_empty());
+        assert_eq!(1, set.covering(b"a").len());
+        assert_eq!(2, set.covering(b"c").len());
+        assert_eq!(1, set.covering(b"e").len());
+        assert!(set.covering(b"f").is_empty());
+
+        assert_eq!(Some(7), set.max_covering_seqno(b"c", 8));
+        assert_eq!(Some(5), set.max_covering_seqno(b"c", 7));
+        assert_eq!(None, set.max_covering_seqno(b"c", 5));
+
+        assert!(set.is_deleted_at(b"c", 6, SeqNo::MAX));
+        assert!(!set.is_deleted_at(b"c", 7, SeqNo::MAX));
+        assert!(!set.is_deleted_at(b"f", 0, SeqNo::MAX));
+        assert!(set.is_deleted_at(b"c", 6, 8));
+        assert!(!set.is_deleted_at(b"c", 6, 7));
+        assert!(set.is_deleted_at(b"c", 4, 7));
+    }
+
+    #[test]
+    fn range_tombstone_clip() {
+        let rt = RangeTombstone::new(*b"b", *b"f", 5);
+
+        assert_eq!(
+            Some(RangeTombstone::new(*b"c", *b"d", 5)),
+            rt.clip(Some(b"c"), Some(b"d")),
+        );
+        assert_eq!(Some(rt.clone()), rt.clip(Some(b"a"), None));
+        assert_eq!(None, rt.clip(Some(b"f"), None));
+        assert_eq!(None, rt.clip(None, Some(b"b")));
+    }
+
+    #[test]
+    fn range_tombstone_max_key() {
+        assert_eq!(
+            &*UserKey::from(*b"d"),
+            &*RangeTombstone::new(*b"a", *b"d", 0).max_key()
+        );
+        assert_eq!(
+            &*UserKey::from(*b"c"),
+            &*RangeTombstone::new(UserKey::from(*b"a"), successor(b"c"), 0).max_key(),
+        );
+    }
+}
@@ -0,0 +1,425 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    compaction::{CompactionStrategy, Leveled},
+    stop_signal::StopSignal,
+    tree::inner::TreeInner,
+    AbstractTree, AnyTree, BlobTree, SequenceNumberCounter, Tree,
+};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Condvar, Mutex, Weak},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// Options for the background [`Scheduler`]
+#[derive(Clone)]
+pub struct SchedulerOptions {
+    /// Compaction strategy that is run in the background
+    pub(crate) compaction_strategy: Arc<dyn CompactionStrategy + Send + Sync>,
+
+    /// Number of compaction threads
+    pub(crate) compaction_threads: usize,
+
+    /// Size at which the active memtable is rotated and flushed
+    pub(crate) max_memtable_size: u64,
+
+    /// Interval in which the workers check the tree for work
+    pub(crate) poll_interval: Duration,
+
+    /// Versions below this seqno may be evicted during flushes and compactions
+    pub(crate) gc_watermark: SequenceNumberCounter,
+}
+
+impl Default for SchedulerOptions {
+    fn default() -> Self {
+        Self {
+            compaction_strategy: Arc::new(Leveled::default()),
+            compaction_threads: 1,
+            max_memtable_size: /* 64 MiB */ 64 * 1_024 * 1_024,
+            poll_interval: Duration::from_millis(50),
+            gc_watermark: SequenceNumberCounter::default(),
+        }
+    }
+}
+
+impl SchedulerOptions {
+    /// Sets the compaction strategy that is run in the background.
+    ///
+    /// Default = [`Leveled`]
+    #[must_use]
+    pub fn compaction_strategy(
+        mut self,
+        strategy: Arc<dyn CompactionStrategy + Send + Sync>,
+    ) -> Self {
+        self.compaction_strategy = strategy;
+        self
+    }
+
+    /// Sets the number of compaction threads.
+    ///
+    /// Flushes are run by an additional, dedicated thread.
+    ///
+    /// Default = 1
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    #[must_use]
+    pub fn compaction_threads(mut self, n: usize) -> Self {
+        assert!(n > 0, "need at least one compaction thread");
+
+        self.compaction_threads = n;
+        self
+    }
+
+    /// Sets the size at which the active memtable is rotated and flushed.
+    ///
+    /// Default = 64 MiB
+    #[must_use]
+    pub fn max_memtable_size(mut self, bytes: u64) -> Self {
+        self.max_memtable_size = bytes;
+        self
+    }
+
+    /// Sets the interval in which the workers check the tree for work.
+    ///
+    /// Workers can be woken up earlier using [`Scheduler::notify`].
+    ///
+    /// Default = 50ms
+    #[must_use]
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Sets the garbage collection watermark.
+    ///
+    /// Flushes and compactions may evict versions of items that are older
+    /// than the counter's current value, so it should be kept at or below
+    /// the seqno of the oldest snapshot that is still in use.
+    ///
+    /// Default = a counter that stays at 0, so no versions are evicted
+    #[must_use]
+    pub fn gc_watermark(mut self, watermark: SequenceNumberCounter) -> Self {
+        self.gc_watermark = watermark;
+        self
+    }
+}
+
+/// Handle to a tree that does not keep it alive
+#[derive(Clone)]
+enum WeakTree {
+    Standard(Weak<TreeInner>),
+    Blob(Weak<TreeInner>, Arc<PathBuf>),
+}
+
+impl WeakTree {
+    fn new(tree: &AnyTree) -> Self {
+        match tree {
+            AnyTree::Standard(tree) => Self::Standard(Arc::downgrade(&tree.0)),
+            AnyTree::Blob(tree) => {
+                Self::Blob(Arc::downgrade(&tree.index.0), tree.blobs_folder.clone())
+            }
+        }
+    }
+
+    /// Returns the tree, or `None` if it has been dropped.
+    fn upgrade(&self) -> Option<AnyTree> {
+        match self {
+            Self::Standard(inner) => inner.upgrade().map(|inner| Tree(inner).into()),
+            Self::Blob(inner, blobs_folder) => inner.upgrade().map(|inner| {
+                BlobTree {
+                    index: Tree(inner),
+                    blobs_folder: blobs_folder.clone(),
+                }
+                .into()
+            }),
+        }
+    }
+}
+
+/// State shared between the scheduler and its workers
+struct Shared {
+    stop_signal: StopSignal,
+    wakeup: Condvar,
+    mutex: Mutex<()>,
+    poll_interval: Duration,
+}
+
+impl Shared {
+    /// Blocks until the poll interval has passed, or the workers are woken up.
+    fn sleep(&self) {
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let guard = self.mutex.lock().expect("lock is poisoned");
+
+        if self.stop_signal.is_stopped() {
+            return;
+        }
+
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let _guard = self
+            .wakeup
+            .wait_timeout(guard, self.poll_interval)
+            .expect("lock is poisoned");
+    }
+
+    fn wake_all(&self) {
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let _guard = self.mutex.lock().expect("lock is poisoned");
+        self.wakeup.notify_all();
+    }
+}
+
+/// Background flush and compaction scheduler
+///
+/// Owns a small thread pool that keeps a tree in shape:
+///
+/// - a flush worker rotates the active memtable once it reaches the configured size
+///   and flushes sealed memtables to tables
+/// - compaction workers run the configured compaction strategy whenever
+///   it chooses to do some work (e.g. because there are too many L0 runs)
+///
+/// The workers are stopped when the scheduler is dropped (or shut down),
+/// finishing their current flush or compaction first.
+/// The workers do not keep the tree alive, so they also stop once the tree is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # let folder = tempfile::tempdir()?;
+/// use lsm_tree::{AbstractTree, Config, Scheduler, SchedulerOptions};
+///
+/// let tree = Config::new(folder, Default::default(), Default::default()).open()?;
+///
+/// let scheduler = Scheduler::start(
+///     &tree,
+///     SchedulerOptions::default().max_memtable_size(/* 8 MiB */ 8 * 1_024 * 1_024),
+/// )?;
+///
+/// tree.insert("a", "abc", 0);
+///
+/// scheduler.shutdown();
+/// #
+/// # Ok::<(), lsm_tree::Error>(())
+/// ```
+pub struct Scheduler {
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Scheduler {
+    /// Starts the background workers for the given tree.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a worker thread could not be spawned.
+    pub fn start(tree: &AnyTree, opts: SchedulerOptions) -> crate::Result<Self> {
+        let shared = Arc::new(Shared {
+            stop_signal: StopSignal::default(),
+            wakeup: Condvar::new(),
+            mutex: Mutex::default(),
+            poll_interval: opts.poll_interval,
+        });
+
+        // NOTE: If spawning a thread fails, dropping the scheduler stops the spawned ones
+        let mut scheduler = Self {
+            shared: shared.clone(),
+            threads: Vec::with_capacity(opts.compaction_threads + 1),
+        };
+
+        let opts = Arc::new(opts);
+        let tree_id = tree.id();
+        let weak_tree = WeakTree::new(tree);
+
+        {
+            let tree = weak_tree.clone();
+            let opts = opts.clone();
+            let shared = shared.clone();
+
+            let handle = std::thread::Builder::new()
+                .name(format!("lsm-flush-{tree_id}"))
+                .spawn(move || flush_worker(&tree, &opts, &shared))?;
+
+            scheduler.threads.push(handle);
+        }
+
+        for idx in 0..opts.compaction_threads {
+            let tree = weak_tree.clone();
+            let opts = opts.clone();
+            let shared = shared.clone();
+
+            let handle = std::thread::Builder::new()
+                .name(format!("lsm-compact-{tree_id}-{idx}"))
+                .spawn(move || compaction_worker(&tree, &opts, &shared))?;
+
+            scheduler.threads.push(handle);
+        }
+
+        log::debug!(
+            "Started scheduler with {} compaction threads",
+            opts.compaction_threads,
+        );
+
+        Ok(scheduler)
+    }
+
+    /// Wakes up the workers, so they check the tree for work immediately.
+    ///
+    /// Can be used to react to writes faster than the poll interval.
+    pub fn notify(&self) {
+        self.shared.wake_all();
+    }
+
+    /// Stops the workers, blocking until their current work is done.
+    pub fn shutdown(self) {
+        drop(self);
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        log::debug!("Stopping scheduler");
+
+        {
+            #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+            let _guard = self.shared.mutex.lock().expect("lock is poisoned");
+            self.shared.stop_signal.send();
+            self.shared.wakeup.notify_all();
+        }
+
+        for handle in self.threads.drain(..) {
+            if handle.join().is_err() {
+                log::error!("Scheduler worker panicked");
+            }
+        }
+    }
+}
+
+fn flush_worker(tree: &WeakTree, opts: &SchedulerOptions, shared: &Shared) {
+    while !shared.stop_signal.is_stopped() {
+        // NOTE: The tree is only held while working, so dropping it stops the worker
+        let Some(tree) = tree.upgrade() else {
+            break;
+        };
+
+        let has_flushed = flush(&tree, opts, shared);
+        drop(tree);
+
+        if !has_flushed {
+            shared.sleep();
+        }
+    }
+
+    log::trace!("Flush worker stopped");
+}
+
+/// Flushes a sealed memtable, returning `true` if there may be more to flush.
+fn flush(tree: &AnyTree, opts: &SchedulerOptions, shared: &Shared) -> bool {
+    if tree.active_memtable().size() >= opts.max_memtable_size {
+        tree.rotate_memtable();
+    }
+
+    if tree.sealed_memtable_count() == 0 {
+        return false;
+    }
+
+    let result = {
+        let lock = tree.get_flush_lock();
+        tree.flush(&lock, opts.gc_watermark.get())
+    };
+
+    match result {
+        Ok(_) => {
+            // NOTE: The flush may have created work for the compaction workers
+            shared.wake_all();
+            true
+        }
+        Err(e) => {
+            log::error!("Background flush failed: {e:?}");
+            false
+        }
+    }
+}
+
+fn compaction_worker(tree: &WeakTree, opts: &SchedulerOptions, shared: &Shared) {
+    while !shared.stop_signal.is_stopped() {
+        // NOTE: The tree is only held while working, so dropping it stops the worker
+        let Some(tree) = tree.upgrade() else {
+            break;
+        };
+
+        let has_compacted = compact(&tree, opts);
+        drop(tree);
+
+        if !has_compacted {
+            shared.sleep();
+        }
+    }
+
+    log::trace!("Compaction worker stopped");
+}
+
+/// Runs the compaction strategy, returning `true` if it may want to do more work.
+fn compact(tree: &AnyTree, opts: &SchedulerOptions) -> bool {
+    let version_id = tree.current_version().id();
+
+    if let Err(e) = tree.compact(opts.compaction_strategy.clone(), opts.gc_watermark.get()) {
+        log::error!("Background compaction failed: {e:?}");
+        return false;
+    }
+
+    // NOTE: If the version changed, the strategy may want to do more work,
+    // otherwise there is nothing to do for now
+    tree.current_version().id() != version_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, SequenceNumberCounter};
+    use test_log::test;
+
+    #[test]
+    fn scheduler_stops_with_tree() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let tree = Config::new(
+            dir.path(),
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        let scheduler = Scheduler::start(
+            &tree,
+            SchedulerOptions::default().poll_interval(Duration::from_millis(10)),
+        )?;
+
+        tree.insert("a", "abc", 0);
+
+        let AnyTree::Standard(inner) = &tree else {
+            unreachable!("should be standard tree");
+        };
+        let stop_signal = inner.stop_signal.clone();
+        drop(tree);
+
+        let start = std::time::Instant::now();
+
+        while !scheduler.threads.iter().all(JoinHandle::is_finished) {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "workers did not stop",
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(stop_signal.is_stopped(), "tree should have been dropped");
+
+        scheduler.shutdown();
+
+        Ok(())
+    }
+}
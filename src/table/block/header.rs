@@ -3,52 +3,33 @@
 // (found in the LICENSE-* files in the repository)
 
 use super::Checksum;
-use crate::checksum::ChecksummedWriter;
+use crate::checksum::{ChecksumType, ChecksummedWriter};
 use crate::coding::{Decode, Encode};
 use crate::file::MAGIC_BYTES;
 use crate::table::block::BlockType;
 use byteorder::{ReadBytesExt, WriteBytesExt};
 use std::io::{Read, Write};
 
-struct ChecksummedReader<R: std::io::Read> {
-    inner: R,
-    hasher: xxhash_rust::xxh3::Xxh3Default,
-}
-
-impl<R: std::io::Read> ChecksummedReader<R> {
-    pub fn new(reader: R) -> Self {
-        Self {
-            inner: reader,
-            hasher: xxhash_rust::xxh3::Xxh3Default::new(),
-        }
-    }
-
-    pub fn checksum(&self) -> Checksum {
-        Checksum::from_raw(self.hasher.digest128())
-    }
-
-    /// Optionally expose the inner reader if needed
-    pub fn into_inner(self) -> R {
-        self.inner
-    }
-}
-
-impl<R: std::io::Read> std::io::Read for ChecksummedReader<R> {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let n = self.inner.read(buf)?;
+/// Flag in the block type byte that marks blocks that are stored uncompressed
+///
+/// Blocks written without the flag keep their meaning, so existing tables stay readable.
+const UNCOMPRESSED_FLAG: u8 = 0b1000_0000;
 
-        #[expect(clippy::indexing_slicing)]
-        self.hasher.update(&buf[..n]);
-
-        Ok(n)
-    }
-}
+/// Flag in the block type byte that marks blocks that are checksummed using CRC32C instead of XXH3
+const CRC32C_FLAG: u8 = 0b0100_0000;
 
 /// Header of a disk-based block
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct Header {
     pub block_type: BlockType,
 
+    /// If `true`, the data segment is stored uncompressed, regardless of the
+    /// compression type of the table, because compressing it did not save enough space
+    pub is_uncompressed: bool,
+
+    /// Checksum algorithm of both the data segment and the header
+    pub checksum_type: ChecksumType,
+
     /// Checksum value to verify integrity of data
     pub checksum: Checksum,
 
@@ -62,6 +43,13 @@ pub struct Header {
 impl Header {
     #[must_use]
     pub const fn serialized_len() -> usize {
+        Self::protected_len()
+            // Checksum
+            + std::mem::size_of::<u32>()
+    }
+
+    /// Length of the part of the header that is protected by the header checksum.
+    const fn protected_len() -> usize {
         MAGIC_BYTES.len()
             // Block type
             + std::mem::size_of::<BlockType>()
@@ -71,8 +59,6 @@ impl Header {
             + std::mem::size_of::<u32>()
             // Uncompressed data length
             + std::mem::size_of::<u32>()
-            // Checksum
-            + std::mem::size_of::<u32>()
     }
 }
 
@@ -81,13 +67,23 @@ impl Encode for Header {
         use byteorder::LE;
 
         let checksum = {
-            let mut writer = ChecksummedWriter::new(&mut writer);
+            let mut writer = ChecksummedWriter::new(&mut writer, self.checksum_type);
 
             // Write header
             writer.write_all(&MAGIC_BYTES)?;
 
             // Write block type
-            writer.write_u8(self.block_type.into())?;
+            let mut block_type = u8::from(self.block_type);
+
+            if self.is_uncompressed {
+                block_type |= UNCOMPRESSED_FLAG;
+            }
+
+            if self.checksum_type == ChecksumType::Crc32c {
+                block_type |= CRC32C_FLAG;
+            }
+
+            writer.write_u8(block_type)?;
 
             // Write data checksum
             writer.write_u128::<LE>(self.checksum.into_u128())?;
@@ -116,7 +112,12 @@ impl Decode for Header {
     fn decode_from<R: Read>(reader: &mut R) -> Result<Self, crate::Error> {
         use byteorder::LE;
 
-        let mut protected_reader = ChecksummedReader::new(reader);
+        // NOTE: The checksum type is part of the header itself,
+        // so we need to read the protected part before we can check it
+        let mut protected = [0u8; Self::protected_len()];
+        reader.read_exact(&mut protected)?;
+
+        let mut protected_reader = &protected[..];
 
         // Check header
         let mut magic = [0u8; MAGIC_BYTES.len()];
@@ -128,7 +129,15 @@ impl Decode for Header {
 
         // Read block type
         let block_type = protected_reader.read_u8()?;
-        let block_type = BlockType::try_from(block_type)?;
+        let is_uncompressed = block_type & UNCOMPRESSED_FLAG != 0;
+
+        let checksum_type = if block_type & CRC32C_FLAG == 0 {
+            ChecksumType::Xxh3
+        } else {
+            ChecksumType::Crc32c
+        };
+
+        let block_type = BlockType::try_from(block_type & !(UNCOMPRESSED_FLAG | CRC32C_FLAG))?;
 
         // Read data checksum
         let checksum = protected_reader.read_u128::<LE>()?;
@@ -144,11 +153,9 @@ impl Decode for Header {
             reason = "we purposefully only use the lower 4 bytes as checksum"
         )]
         // Get header checksum
-        let got_checksum = protected_reader.checksum().into_u128() as u32;
+        let got_checksum = checksum_type.checksum(&protected).into_u128() as u32;
         let got_checksum = Checksum::from_raw(u128::from(got_checksum));
 
-        let reader = protected_reader.into_inner();
-
         // Read & check checksum
         let header_checksum: u128 = reader.read_u32::<LE>()?.into();
         let header_checksum = Checksum::from_raw(header_checksum);
@@ -162,6 +169,8 @@ impl Decode for Header {
 
         Ok(Self {
             block_type,
+            is_uncompressed,
+            checksum_type,
             checksum: Checksum::from_raw(checksum),
             data_length,
             uncompressed_length,
@@ -181,6 +190,46 @@ mod tests {
             checksum: Checksum::from_raw(5),
             data_length: 252_356,
             uncompressed_length: 124_124_124,
+            is_uncompressed: false,
+            checksum_type: ChecksumType::Xxh3,
+        };
+
+        let bytes = header.encode_into_vec();
+
+        assert_eq!(bytes.len(), Header::serialized_len());
+        assert_eq!(header, Header::decode_from(&mut &bytes[..])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_header_serde_roundtrip_uncompressed() -> crate::Result<()> {
+        let header = Header {
+            block_type: BlockType::Index,
+            checksum: Checksum::from_raw(5),
+            data_length: 252_356,
+            uncompressed_length: 252_356,
+            is_uncompressed: true,
+            checksum_type: ChecksumType::Xxh3,
+        };
+
+        let bytes = header.encode_into_vec();
+
+        assert_eq!(bytes.len(), Header::serialized_len());
+        assert_eq!(header, Header::decode_from(&mut &bytes[..])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_header_serde_roundtrip_crc32c() -> crate::Result<()> {
+        let header = Header {
+            block_type: BlockType::Filter,
+            checksum: Checksum::from_raw(5),
+            data_length: 252_356,
+            uncompressed_length: 124_124_124,
+            is_uncompressed: true,
+            checksum_type: ChecksumType::Crc32c,
         };
 
         let bytes = header.encode_into_vec();
@@ -199,6 +248,8 @@ mod tests {
             checksum: Checksum::from_raw(5),
             data_length: 252_356,
             uncompressed_length: 124_124_124,
+            is_uncompressed: false,
+            checksum_type: ChecksumType::Xxh3,
         };
 
         let mut bytes = header.encode_into_vec();
@@ -212,4 +263,28 @@ mod tests {
             "did not detect header corruption",
         );
     }
+
+    #[test]
+    #[expect(clippy::indexing_slicing)]
+    fn block_header_detect_corruption_crc32c() {
+        let header = Header {
+            block_type: BlockType::Data,
+            checksum: Checksum::from_raw(5),
+            data_length: 252_356,
+            uncompressed_length: 124_124_124,
+            is_uncompressed: false,
+            checksum_type: ChecksumType::Crc32c,
+        };
+
+        let mut bytes = header.encode_into_vec();
+        bytes[10] += 1; // mutate data checksum
+
+        assert!(
+            matches!(
+                Header::decode_from(&mut &bytes[..]),
+                Err(crate::Error::ChecksumMismatch { .. }),
+            ),
+            "did not detect header corruption",
+        );
+    }
 }
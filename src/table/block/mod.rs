@@ -21,7 +21,7 @@ pub(crate) use trailer::{Trailer, TRAILER_START_MARKER};
 use crate::{
     coding::{Decode, Encode},
     table::BlockHandle,
-    Checksum, CompressionType, Slice,
+    Checksum, ChecksumType, CompressionDictionary, CompressionType, Slice,
 };
 use std::fs::File;
 
@@ -42,14 +42,103 @@ impl Block {
     }
 
     /// Encodes a block into a writer.
+    ///
+    /// The block is stored uncompressed if compressing it does not make it smaller.
     pub fn write_into<W: std::io::Write>(
-        mut writer: &mut W,
+        writer: &mut W,
         data: &[u8],
         block_type: BlockType,
         compression: CompressionType,
+        checksum_type: ChecksumType,
+    ) -> crate::Result<Header> {
+        Self::write_into_with_min_compression_ratio(
+            writer,
+            data,
+            block_type,
+            compression,
+            checksum_type,
+            1.0,
+        )
+    }
+
+    /// Encodes a block into a writer.
+    ///
+    /// The block is stored uncompressed if its compression ratio
+    /// (uncompressed size / compressed size) is below `min_ratio`,
+    /// or if compressing it does not make it smaller.
+    pub fn write_into_with_min_compression_ratio<W: std::io::Write>(
+        writer: &mut W,
+        data: &[u8],
+        block_type: BlockType,
+        compression: CompressionType,
+        checksum_type: ChecksumType,
+        min_ratio: f32,
+    ) -> crate::Result<Header> {
+        let compressed: Option<Vec<u8>> = match compression {
+            CompressionType::None => None,
+
+            #[cfg(feature = "lz4")]
+            CompressionType::Lz4 => Some(lz4_flex::compress(data)),
+
+            #[cfg(feature = "zstd")]
+            CompressionType::Zstd(level) => Some(zstd::bulk::compress(data, level)?),
+        };
+
+        Self::write_payload_into(
+            writer,
+            data,
+            compressed.as_deref(),
+            block_type,
+            checksum_type,
+            min_ratio,
+        )
+    }
+
+    /// Encodes a block into a writer, compressing it using a Zstd compressor
+    /// that has a compression dictionary loaded.
+    #[cfg(feature = "zstd")]
+    pub(crate) fn write_into_with_compressor<W: std::io::Write>(
+        writer: &mut W,
+        data: &[u8],
+        block_type: BlockType,
+        compressor: &mut zstd::bulk::Compressor<'_>,
+        checksum_type: ChecksumType,
+        min_ratio: f32,
     ) -> crate::Result<Header> {
+        let compressed = compressor.compress(data)?;
+        Self::write_payload_into(
+            writer,
+            data,
+            Some(&compressed),
+            block_type,
+            checksum_type,
+            min_ratio,
+        )
+    }
+
+    /// Writes the header and the payload of a block.
+    ///
+    /// The payload is the compressed data, unless compression did not save enough space.
+    fn write_payload_into<W: std::io::Write>(
+        mut writer: &mut W,
+        data: &[u8],
+        compressed: Option<&[u8]>,
+        block_type: BlockType,
+        checksum_type: ChecksumType,
+        min_ratio: f32,
+    ) -> crate::Result<Header> {
+        let (payload, is_uncompressed) = match compressed {
+            Some(compressed) if Self::is_worth_compressing(data, compressed, min_ratio) => {
+                (compressed, false)
+            }
+            Some(_) => (data, true),
+            None => (data, false),
+        };
+
         let mut header = Header {
             block_type,
+            is_uncompressed,
+            checksum_type,
             checksum: Checksum::from_raw(0), // <-- NOTE: Is set later on
             data_length: 0,                  // <-- NOTE: Is set later on
 
@@ -57,24 +146,17 @@ impl Block {
             uncompressed_length: data.len() as u32,
         };
 
-        let data = match compression {
-            CompressionType::None => data,
-
-            #[cfg(feature = "lz4")]
-            CompressionType::Lz4 => &lz4_flex::compress(data),
-        };
-
         #[expect(clippy::cast_possible_truncation, reason = "blocks are limited to u32")]
         {
-            header.data_length = data.len() as u32;
-            header.checksum = Checksum::from_raw(crate::hash::hash128(data));
+            header.data_length = payload.len() as u32;
+            header.checksum = checksum_type.checksum(payload);
         }
 
         header.encode_into(&mut writer)?;
-        writer.write_all(data)?;
+        writer.write_all(payload)?;
 
         log::trace!(
-            "Writing block with size {}B (compressed: {}B) (excluding header of {}B)",
+            "Writing block with size {}B (compressed: {}B, uncompressed={is_uncompressed}) (excluding header of {}B)",
             header.uncompressed_length,
             header.data_length,
             Header::serialized_len(),
@@ -83,15 +165,28 @@ impl Block {
         Ok(header)
     }
 
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "precision loss is acceptable for ratios"
+    )]
+    fn is_worth_compressing(data: &[u8], compressed: &[u8], min_ratio: f32) -> bool {
+        compressed.len() < data.len() && (data.len() as f32 / compressed.len() as f32) >= min_ratio
+    }
+
     /// Reads a block from a reader.
+    ///
+    /// The dictionary is only used for blocks that are compressed using Zstd.
     pub fn from_reader<R: std::io::Read>(
         reader: &mut R,
         compression: CompressionType,
+        #[cfg_attr(not(feature = "zstd"), expect(unused_variables))] dictionary: Option<
+            &CompressionDictionary,
+        >,
     ) -> crate::Result<Self> {
         let header = Header::decode_from(reader)?;
         let raw_data = Slice::from_reader(reader, header.data_length as usize)?;
 
-        let checksum = Checksum::from_raw(crate::hash::hash128(&raw_data));
+        let checksum = header.checksum_type.checksum(&raw_data);
 
         checksum.check(header.checksum).inspect_err(|_| {
             log::error!(
@@ -101,6 +196,12 @@ impl Block {
             );
         })?;
 
+        let compression = if header.is_uncompressed {
+            CompressionType::None
+        } else {
+            compression
+        };
+
         let data = match compression {
             CompressionType::None => raw_data,
 
@@ -115,6 +216,14 @@ impl Block {
 
                 builder.freeze().into()
             }
+
+            #[cfg(feature = "zstd")]
+            CompressionType::Zstd(_) => Self::decompress_zstd(
+                &raw_data,
+                header.uncompressed_length,
+                compression,
+                dictionary,
+            )?,
         };
 
         debug_assert_eq!(header.uncompressed_length, {
@@ -128,17 +237,24 @@ impl Block {
     }
 
     /// Reads a block from a file.
+    ///
+    /// The dictionary is only used for blocks that are compressed using Zstd.
     pub fn from_file(
         file: &File,
         handle: BlockHandle,
         compression: CompressionType,
+        #[cfg_attr(not(feature = "zstd"), expect(unused_variables))] dictionary: Option<
+            &CompressionDictionary,
+        >,
     ) -> crate::Result<Self> {
         let buf = crate::file::read_exact(file, *handle.offset(), handle.size() as usize)?;
 
         let header = Header::decode_from(&mut &buf[..])?;
 
         #[expect(clippy::indexing_slicing)]
-        let checksum = Checksum::from_raw(crate::hash::hash128(&buf[Header::serialized_len()..]));
+        let checksum = header
+            .checksum_type
+            .checksum(&buf[Header::serialized_len()..]);
 
         checksum.check(header.checksum).inspect_err(|_| {
             log::error!(
@@ -148,6 +264,12 @@ impl Block {
             );
         })?;
 
+        let compression = if header.is_uncompressed {
+            CompressionType::None
+        } else {
+            compression
+        };
+
         let buf = match compression {
             CompressionType::None => {
                 let value = buf.slice(Header::serialized_len()..);
@@ -176,10 +298,48 @@ impl Block {
 
                 builder.freeze().into()
             }
+
+            #[cfg(feature = "zstd")]
+            CompressionType::Zstd(_) => {
+                // NOTE: We know that a header always exists and data is never empty
+                // So the slice is fine
+                #[expect(clippy::indexing_slicing)]
+                let raw_data = &buf[Header::serialized_len()..];
+
+                Self::decompress_zstd(
+                    raw_data,
+                    header.uncompressed_length,
+                    compression,
+                    dictionary,
+                )?
+            }
         };
 
         Ok(Self { header, data: buf })
     }
+
+    #[cfg(feature = "zstd")]
+    fn decompress_zstd(
+        raw_data: &[u8],
+        uncompressed_length: u32,
+        compression: CompressionType,
+        dictionary: Option<&CompressionDictionary>,
+    ) -> crate::Result<Slice> {
+        #[warn(unsafe_code)]
+        let mut builder = unsafe { Slice::builder_unzeroed(uncompressed_length as usize) };
+
+        let len = match dictionary {
+            Some(dictionary) => dictionary.decompress_to_buffer(raw_data, &mut builder),
+            None => zstd::bulk::decompress_to_buffer(raw_data, &mut builder),
+        }
+        .map_err(|_| crate::Error::Decompress(compression))?;
+
+        if len != uncompressed_length as usize {
+            return Err(crate::Error::Decompress(compression));
+        }
+
+        Ok(builder.freeze().into())
+    }
 }
 
 #[cfg(test)]
@@ -198,16 +358,70 @@ mod tests {
             b"abcdefabcdefabcdef",
             BlockType::Data,
             CompressionType::None,
+            ChecksumType::Xxh3,
+        )?;
+
+        {
+            let mut reader = &writer[..];
+            let block = Block::from_reader(&mut reader, CompressionType::None, None)?;
+            assert_eq!(b"abcdefabcdefabcdef", &*block.data);
+        }
+
+        Ok(())
+    }
+    #[test]
+    fn block_roundtrip_crc32c() -> crate::Result<()> {
+        let mut writer = vec![];
+
+        let header = Block::write_into(
+            &mut writer,
+            b"abcdefabcdefabcdef",
+            BlockType::Data,
+            CompressionType::None,
+            ChecksumType::Crc32c,
         )?;
 
+        assert_eq!(ChecksumType::Crc32c, header.checksum_type);
+        assert_eq!(
+            ChecksumType::Crc32c.checksum(b"abcdefabcdefabcdef"),
+            header.checksum,
+        );
+
         {
             let mut reader = &writer[..];
-            let block = Block::from_reader(&mut reader, CompressionType::None)?;
+            let block = Block::from_reader(&mut reader, CompressionType::None, None)?;
+            assert_eq!(ChecksumType::Crc32c, block.header.checksum_type);
             assert_eq!(b"abcdefabcdefabcdef", &*block.data);
         }
 
         Ok(())
     }
+
+    #[test]
+    #[expect(clippy::indexing_slicing)]
+    fn block_detect_corruption_crc32c() -> crate::Result<()> {
+        let mut writer = vec![];
+
+        Block::write_into(
+            &mut writer,
+            b"abcdefabcdefabcdef",
+            BlockType::Data,
+            CompressionType::None,
+            ChecksumType::Crc32c,
+        )?;
+
+        // NOTE: Flip a byte of the data segment
+        writer[Header::serialized_len()] ^= 1;
+
+        let mut reader = &writer[..];
+        assert!(matches!(
+            Block::from_reader(&mut reader, CompressionType::None, None),
+            Err(crate::Error::ChecksumMismatch { .. }),
+        ));
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "lz4")]
     fn block_roundtrip_lz4() -> crate::Result<()> {
@@ -218,14 +432,152 @@ mod tests {
             b"abcdefabcdefabcdef",
             BlockType::Data,
             CompressionType::Lz4,
+            ChecksumType::Xxh3,
+        )?;
+
+        {
+            let mut reader = &writer[..];
+            let block = Block::from_reader(&mut reader, CompressionType::Lz4, None)?;
+            assert_eq!(b"abcdefabcdefabcdef", &*block.data);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "lz4")]
+    fn block_roundtrip_lz4_incompressible() -> crate::Result<()> {
+        let mut writer = vec![];
+
+        let header = Block::write_into(
+            &mut writer,
+            b"abcdef",
+            BlockType::Data,
+            CompressionType::Lz4,
+            ChecksumType::Xxh3,
+        )?;
+
+        assert!(header.is_uncompressed);
+        assert_eq!(6, header.data_length);
+        assert_eq!(6, header.uncompressed_length);
+
+        {
+            let mut reader = &writer[..];
+            let block = Block::from_reader(&mut reader, CompressionType::Lz4, None)?;
+            assert!(block.header.is_uncompressed);
+            assert_eq!(b"abcdef", &*block.data);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "lz4")]
+    fn block_roundtrip_lz4_min_compression_ratio() -> crate::Result<()> {
+        let data = b"abcdef".repeat(100);
+
+        let mut writer = vec![];
+
+        let header = Block::write_into_with_min_compression_ratio(
+            &mut writer,
+            &data,
+            BlockType::Data,
+            CompressionType::Lz4,
+            ChecksumType::Xxh3,
+            2.0,
+        )?;
+        assert!(!header.is_uncompressed);
+        assert!(header.data_length < header.uncompressed_length);
+
+        let header = Block::write_into_with_min_compression_ratio(
+            &mut writer,
+            &data,
+            BlockType::Data,
+            CompressionType::Lz4,
+            ChecksumType::Xxh3,
+            1_000.0,
+        )?;
+        assert!(header.is_uncompressed);
+        assert_eq!(header.data_length, header.uncompressed_length);
+
+        {
+            let mut reader = &writer[..];
+
+            let block = Block::from_reader(&mut reader, CompressionType::Lz4, None)?;
+            assert!(!block.header.is_uncompressed);
+            assert_eq!(data, &*block.data);
+
+            let block = Block::from_reader(&mut reader, CompressionType::Lz4, None)?;
+            assert!(block.header.is_uncompressed);
+            assert_eq!(data, &*block.data);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn block_roundtrip_zstd() -> crate::Result<()> {
+        let mut writer = vec![];
+
+        Block::write_into(
+            &mut writer,
+            b"abcdefabcdefabcdef",
+            BlockType::Data,
+            CompressionType::Zstd(3),
+            ChecksumType::Xxh3,
         )?;
 
         {
             let mut reader = &writer[..];
-            let block = Block::from_reader(&mut reader, CompressionType::Lz4)?;
+            let block = Block::from_reader(&mut reader, CompressionType::Zstd(3), None)?;
             assert_eq!(b"abcdefabcdefabcdef", &*block.data);
         }
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn block_roundtrip_zstd_dictionary() -> crate::Result<()> {
+        let samples = (0..1_000u32)
+            .map(|x| format!("user:{x:0>8}:name=john,mail=john{x}@example.com").into_bytes())
+            .collect::<Vec<_>>();
+
+        let dictionary = CompressionDictionary::train(&samples, 1_024)?;
+        assert!(dictionary.size() > 0);
+        assert!(dictionary.size() <= 1_024);
+
+        let data = b"user:00000042:name=john,mail=john42@example.com";
+
+        let mut writer = vec![];
+        let mut compressor = zstd::bulk::Compressor::with_dictionary(3, dictionary.as_bytes())?;
+
+        Block::write_into_with_compressor(
+            &mut writer,
+            data,
+            BlockType::Data,
+            &mut compressor,
+            ChecksumType::Xxh3,
+            1.0,
+        )?;
+
+        {
+            let mut reader = &writer[..];
+            let block =
+                Block::from_reader(&mut reader, CompressionType::Zstd(3), Some(&dictionary))?;
+            assert_eq!(data, &*block.data);
+        }
+
+        // NOTE: The block can not be decompressed without its dictionary
+        {
+            let mut reader = &writer[..];
+            assert!(matches!(
+                Block::from_reader(&mut reader, CompressionType::Zstd(3), None),
+                Err(crate::Error::Decompress(CompressionType::Zstd(3))),
+            ));
+        }
+
+        Ok(())
+    }
 }
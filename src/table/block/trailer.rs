@@ -64,6 +64,54 @@ impl<'a> Trailer<'a> {
         }
     }
 
+    /// Checks that the trailer is well-formed, and that the indexes
+    /// it points to lie inside the block.
+    ///
+    /// Unlike the other methods, this does not trust the block contents,
+    /// so it can be used on blocks that may be corrupted.
+    pub fn verify(&self) -> crate::Result<()> {
+        let Some(trailer_offset) = self.block.data.len().checked_sub(TRAILER_SIZE) else {
+            return Err(crate::Error::InvalidTrailer);
+        };
+
+        let mut reader = self.as_slice();
+
+        let restart_interval = reader.read_u8()?;
+        let binary_index_step_size = reader.read_u8()?;
+        let binary_index_len = reader.read_u32::<LittleEndian>()? as usize;
+        let binary_index_offset = reader.read_u32::<LittleEndian>()? as usize;
+        let hash_index_len = reader.read_u32::<LittleEndian>()? as usize;
+        let hash_index_offset = reader.read_u32::<LittleEndian>()? as usize;
+
+        if restart_interval == 0 || !matches!(binary_index_step_size, 2 | 4) {
+            return Err(crate::Error::InvalidTrailer);
+        }
+
+        // NOTE: The binary index directly follows the trailer start marker
+        let is_marker_valid = binary_index_offset
+            .checked_sub(1)
+            .and_then(|pos| self.block.data.get(pos))
+            .is_some_and(|&marker| marker == TRAILER_START_MARKER);
+
+        let binary_index_end = binary_index_len
+            .checked_mul(usize::from(binary_index_step_size))
+            .and_then(|len| len.checked_add(binary_index_offset));
+
+        if !is_marker_valid || binary_index_end.is_none_or(|end| end > trailer_offset) {
+            return Err(crate::Error::InvalidTrailer);
+        }
+
+        if hash_index_offset > 0
+            && hash_index_offset
+                .checked_add(hash_index_len)
+                .is_none_or(|end| end > trailer_offset)
+        {
+            return Err(crate::Error::InvalidTrailer);
+        }
+
+        Ok(())
+    }
+
     pub fn as_slice(&self) -> &[u8] {
         let start = self.trailer_offset();
 
@@ -175,10 +223,89 @@ impl<'a> Trailer<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::table::block::TRAILER_START_MARKER;
+    use super::*;
+    use crate::{
+        table::{
+            block::{BlockType, Header},
+            DataBlock,
+        },
+        Checksum, ChecksumType, InternalValue, ValueType,
+    };
     use strum::IntoEnumIterator;
     use test_log::test;
 
+    fn block(data: Vec<u8>) -> Block {
+        Block {
+            data: data.into(),
+            header: Header {
+                block_type: BlockType::Data,
+                checksum: Checksum::from_raw(0),
+                data_length: 0,
+                uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
+            },
+        }
+    }
+
+    fn encode_items() -> crate::Result<Vec<u8>> {
+        let items = [
+            InternalValue::from_components("a", "a", 0, ValueType::Value),
+            InternalValue::from_components("b", "b", 0, ValueType::Value),
+            InternalValue::from_components("c", "c", 0, ValueType::Value),
+        ];
+
+        DataBlock::encode_into_vec(&items, 2, 1.33)
+    }
+
+    #[test]
+    fn block_trailer_verify() -> crate::Result<()> {
+        let bytes = encode_items()?;
+        Trailer::new(&block(bytes)).verify()
+    }
+
+    #[test]
+    fn block_trailer_verify_too_short() {
+        assert!(matches!(
+            Trailer::new(&block(vec![0; 4])).verify(),
+            Err(crate::Error::InvalidTrailer),
+        ));
+    }
+
+    #[test]
+    fn block_trailer_verify_invalid_binary_index() -> crate::Result<()> {
+        let mut bytes = encode_items()?;
+        let trailer_offset = bytes.len() - TRAILER_SIZE;
+
+        // NOTE: Let the binary index point into the middle of the items
+        let binary_index_offset = bytes
+            .get_mut((trailer_offset + 6)..(trailer_offset + 10))
+            .expect("should exist");
+        binary_index_offset.copy_from_slice(&1u32.to_le_bytes());
+
+        assert!(matches!(
+            Trailer::new(&block(bytes)).verify(),
+            Err(crate::Error::InvalidTrailer),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_trailer_verify_invalid_restart_interval() -> crate::Result<()> {
+        let mut bytes = encode_items()?;
+        let trailer_offset = bytes.len() - TRAILER_SIZE;
+
+        *bytes.get_mut(trailer_offset).expect("should exist") = 0;
+
+        assert!(matches!(
+            Trailer::new(&block(bytes)).verify(),
+            Err(crate::Error::InvalidTrailer),
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn value_type_never_block_trailer_start_marker() {
         for variant in crate::ValueType::iter() {
@@ -8,6 +8,8 @@ pub enum BlockType {
     Index,
     Filter,
     Meta,
+    RangeTombstone,
+    CompressionDictionary,
 }
 
 impl From<BlockType> for u8 {
@@ -17,6 +19,8 @@ impl From<BlockType> for u8 {
             BlockType::Index => 1,
             BlockType::Filter => 2,
             BlockType::Meta => 3,
+            BlockType::RangeTombstone => 4,
+            BlockType::CompressionDictionary => 5,
         }
     }
 }
@@ -30,6 +34,8 @@ impl TryFrom<u8> for BlockType {
             1 => Ok(Self::Index),
             2 => Ok(Self::Filter),
             3 => Ok(Self::Meta),
+            4 => Ok(Self::RangeTombstone),
+            5 => Ok(Self::CompressionDictionary),
             _ => Err(crate::Error::InvalidTag(("BlockType", value))),
         }
     }
@@ -132,6 +132,7 @@ impl Iterator for Iter {
                     &handle.into_inner(),
                     BlockType::Index,
                     self.compression,
+                    None,
                     #[cfg(feature = "metrics")]
                     &self.metrics,
                 ));
@@ -195,6 +196,7 @@ impl DoubleEndedIterator for Iter {
                     &handle.into_inner(),
                     BlockType::Index,
                     self.compression,
+                    None,
                     #[cfg(feature = "metrics")]
                     &self.metrics,
                 ));
@@ -107,6 +107,7 @@ impl Iterator for Iter {
                 &self.handle,
                 BlockType::Index,
                 self.compression,
+                None,
                 #[cfg(feature = "metrics")]
                 &self.metrics,
             ));
@@ -147,6 +148,7 @@ impl DoubleEndedIterator for Iter {
                 &self.handle,
                 BlockType::Index,
                 self.compression,
+                None,
                 #[cfg(feature = "metrics")]
                 &self.metrics,
             ));
@@ -5,7 +5,7 @@ mod tests {
             block::{BlockType, Header, ParsedItem},
             Block, DataBlock,
         },
-        Checksum, InternalValue, Slice,
+        Checksum, ChecksumType, InternalValue, Slice,
         ValueType::{Tombstone, Value},
     };
     use test_log::test;
@@ -66,6 +66,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -140,6 +142,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -214,6 +218,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -266,6 +272,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -301,6 +309,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -340,6 +350,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -380,6 +392,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -461,6 +475,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -502,6 +518,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -542,6 +560,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -645,6 +665,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -716,6 +738,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -753,6 +777,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -793,6 +819,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -833,6 +861,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -870,6 +900,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -903,6 +935,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -991,6 +1025,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -1081,6 +1117,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -1180,6 +1218,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -1230,6 +1270,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -1263,6 +1305,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -557,7 +557,7 @@ mod tests {
             block::{BlockType, Header, ParsedItem},
             Block, DataBlock,
         },
-        Checksum, InternalValue, SeqNo, Slice,
+        Checksum, ChecksumType, InternalValue, SeqNo, Slice,
         ValueType::{Tombstone, Value},
     };
     use test_log::test;
@@ -590,6 +590,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -651,6 +653,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -692,6 +696,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -731,6 +737,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -764,6 +772,8 @@ mod tests {
                     checksum: Checksum::from_raw(0),
                     data_length: 0,
                     uncompressed_length: 0,
+                    is_uncompressed: false,
+                    checksum_type: ChecksumType::Xxh3,
                 },
             });
 
@@ -792,6 +802,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -833,6 +845,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -869,6 +883,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -905,6 +921,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -951,6 +969,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -997,6 +1017,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -1046,6 +1068,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -1095,6 +1119,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -1131,6 +1157,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -1168,6 +1196,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -1211,6 +1241,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -61,7 +61,7 @@ mod tests {
             block::{BlockType, Header, ParsedItem},
             Block, BlockHandle, BlockOffset, IndexBlock, KeyedBlockHandle,
         },
-        Checksum,
+        Checksum, ChecksumType,
     };
     use test_log::test;
 
@@ -90,6 +90,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -134,6 +136,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -176,6 +180,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -221,6 +227,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -263,6 +271,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -305,6 +315,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -350,6 +362,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -392,6 +406,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -424,6 +440,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -527,6 +545,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -577,6 +597,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
@@ -626,6 +648,8 @@ mod tests {
                 checksum: Checksum::from_raw(0),
                 data_length: 0,
                 uncompressed_length: 0,
+                is_uncompressed: false,
+                checksum_type: ChecksumType::Xxh3,
             },
         });
 
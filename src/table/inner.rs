@@ -9,9 +9,10 @@ use super::{block_index::BlockIndexImpl, meta::ParsedMeta, regions::ParsedRegion
 use crate::{
     cache::Cache,
     descriptor_table::DescriptorTable,
+    range_tombstone::RangeTombstoneSet,
     table::{filter::block::FilterBlock, IndexBlock},
     tree::inner::TreeId,
-    Checksum, GlobalTableId, SeqNo,
+    Checksum, CompressionDictionary, GlobalTableId, SeqNo,
 };
 use std::{
     path::PathBuf,
@@ -50,6 +51,12 @@ pub struct Inner {
     /// Pinned AMQ filter
     pub pinned_filter_block: Option<FilterBlock>,
 
+    /// Range tombstones, which are always kept in memory
+    pub(crate) range_tombstones: RangeTombstoneSet,
+
+    /// Compression dictionary of the data blocks, which is always kept in memory
+    pub(crate) compression_dictionary: Option<CompressionDictionary>,
+
     /// True when the table was compacted away or dropped
     ///
     /// May be kept alive until all Arcs to the table have been dropped (to facilitate snapshots)
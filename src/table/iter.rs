@@ -10,7 +10,7 @@ use crate::{
         util::load_block,
         BlockHandle,
     },
-    Cache, CompressionType, DescriptorTable, InternalValue, SeqNo, UserKey,
+    Cache, CompressionDictionary, CompressionType, DescriptorTable, InternalValue, SeqNo, UserKey,
 };
 use self_cell::self_cell;
 use std::{path::PathBuf, sync::Arc};
@@ -102,6 +102,7 @@ pub struct Iter {
     descriptor_table: Arc<DescriptorTable>,
     cache: Arc<Cache>,
     compression: CompressionType,
+    compression_dictionary: Option<CompressionDictionary>,
 
     index_initialized: bool,
 
@@ -118,6 +119,7 @@ pub struct Iter {
 }
 
 impl Iter {
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         table_id: GlobalTableId,
         global_seqno: SeqNo,
@@ -126,6 +128,7 @@ impl Iter {
         descriptor_table: Arc<DescriptorTable>,
         cache: Arc<Cache>,
         compression: CompressionType,
+        compression_dictionary: Option<CompressionDictionary>,
         #[cfg(feature = "metrics")] metrics: Arc<Metrics>,
     ) -> Self {
         Self {
@@ -138,6 +141,7 @@ impl Iter {
             descriptor_table,
             cache,
             compression,
+            compression_dictionary,
 
             index_initialized: false,
 
@@ -256,6 +260,7 @@ impl Iterator for Iter {
                         &BlockHandle::new(handle.offset(), handle.size()),
                         crate::table::block::BlockType::Data,
                         self.compression,
+                        self.compression_dictionary.as_ref(),
                         #[cfg(feature = "metrics")]
                         &self.metrics,
                     ))
@@ -377,6 +382,7 @@ impl DoubleEndedIterator for Iter {
                         &BlockHandle::new(handle.offset(), handle.size()),
                         crate::table::block::BlockType::Data,
                         self.compression,
+                        self.compression_dictionary.as_ref(),
                         #[cfg(feature = "metrics")]
                         &self.metrics,
                     ))
@@ -48,8 +48,22 @@ pub struct ParsedMeta {
     pub weak_tombstone_count: u64,
     pub weak_tombstone_reclaimable: u64,
 
+    /// Size of all data blocks as stored on disk (excluding block headers)
+    ///
+    /// Is `None` for tables that were written before the size was tracked.
+    pub compressed_data_size: Option<u64>,
+
+    /// Size of all data blocks before compression
+    pub uncompressed_data_size: u64,
+
     pub data_block_compression: CompressionType,
     pub index_block_compression: CompressionType,
+
+    /// Checksum algorithm of the blocks and the full file checksum
+    pub checksum_type: ChecksumType,
+
+    /// Name of the prefix extractor whose prefixes are contained in the filter
+    pub prefix_extractor: Option<String>,
 }
 
 macro_rules! read_u8 {
@@ -77,7 +91,7 @@ macro_rules! read_u64 {
 impl ParsedMeta {
     #[expect(clippy::expect_used, clippy::too_many_lines)]
     pub fn load_with_handle(file: &File, handle: &BlockHandle) -> crate::Result<Self> {
-        let block = Block::from_file(file, *handle, CompressionType::None)?;
+        let block = Block::from_file(file, *handle, CompressionType::None, None)?;
 
         if block.header.block_type != BlockType::Meta {
             return Err(crate::Error::InvalidTag((
@@ -117,19 +131,7 @@ impl ParsedMeta {
             );
         }
 
-        {
-            let hash_type = block
-                .point_read(b"checksum_type", SeqNo::MAX)
-                .expect("Checksum type should exist")
-                .value;
-
-            assert_eq!(
-                &[u8::from(ChecksumType::Xxh3)],
-                &*hash_type,
-                "invalid checksum type: {:?}",
-                std::str::from_utf8(&hash_type),
-            );
-        }
+        let checksum_type = ChecksumType::try_from(read_u8!(block, b"checksum_type"))?;
 
         assert_eq!(
             read_u8!(block, b"restart_interval#index"),
@@ -146,6 +148,7 @@ impl ParsedMeta {
         let file_size = read_u64!(block, b"file_size");
         let weak_tombstone_count = read_u64!(block, b"weak_tombstone_count");
         let weak_tombstone_reclaimable = read_u64!(block, b"weak_tombstone_reclaimable");
+        let uncompressed_data_size = read_u64!(block, b"user_data_size");
 
         let created_at = {
             let bytes = block
@@ -207,6 +210,20 @@ impl ParsedMeta {
             CompressionType::decode_from(&mut bytes)?
         };
 
+        // NOTE: Older tables do not store the compressed size
+        let compressed_data_size = block
+            .point_read(b"compressed_size#data", SeqNo::MAX)
+            .map(|item| {
+                let mut bytes = &item.value[..];
+                bytes.read_u64::<LittleEndian>()
+            })
+            .transpose()?;
+
+        let prefix_extractor = block
+            .point_read(b"prefix_extractor", SeqNo::MAX)
+            .filter(|item| !item.value.is_empty())
+            .map(|item| String::from_utf8_lossy(&item.value).into_owned());
+
         Ok(Self {
             id,
             created_at,
@@ -219,8 +236,12 @@ impl ParsedMeta {
             tombstone_count,
             weak_tombstone_count,
             weak_tombstone_reclaimable,
+            compressed_data_size,
+            uncompressed_data_size,
             data_block_compression,
             index_block_compression,
+            checksum_type,
+            prefix_extractor,
         })
     }
 }
@@ -30,6 +30,7 @@ pub use writer::Writer;
 use crate::{
     cache::Cache,
     descriptor_table::DescriptorTable,
+    range_tombstone::{RangeTombstone, RangeTombstoneSet},
     table::{
         block::{BlockType, ParsedItem},
         block_index::{BlockIndex, FullBlockIndex, TwoLevelBlockIndex, VolatileBlockIndex},
@@ -37,7 +38,7 @@ use crate::{
         regions::ParsedRegions,
         writer::LinkedFile,
     },
-    Checksum, CompressionType, InternalValue, SeqNo, TreeId, UserKey,
+    Checksum, CompressionDictionary, CompressionType, InternalValue, SeqNo, TreeId, UserKey,
 };
 use block_index::BlockIndexImpl;
 use inner::Inner;
@@ -56,6 +57,30 @@ use crate::metrics::Metrics;
 
 pub type TableInner = Inner;
 
+/// Which blocks of a table are loaded and pinned in memory when it is recovered
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Pinning {
+    /// Pin the filter block (if the filter is not partitioned)
+    pub filter: bool,
+
+    /// Pin the top level index of a partitioned filter
+    pub filter_tli: bool,
+
+    /// Pin the full block index (if the index is not partitioned)
+    pub index: bool,
+}
+
+impl Pinning {
+    /// Gets the pinning policies of the given level.
+    pub(crate) fn from_config(config: &crate::Config, level: usize) -> Self {
+        Self {
+            filter: config.filter_block_pinning_policy.get(level),
+            filter_tli: config.top_level_filter_block_pinning_policy.get(level),
+            index: config.index_block_pinning_policy.get(level),
+        }
+    }
+}
+
 /// A disk segment (a.k.a. `Table`, `SSTable`, `SST`, `sorted string table`) that is located on disk
 ///
 /// A table is an immutable list of key-value pairs, split into compressed blocks.
@@ -185,6 +210,14 @@ impl Table {
         }
     }
 
+    #[must_use]
+    pub fn pinned_compression_dictionary_size(&self) -> usize {
+        self.compression_dictionary
+            .as_ref()
+            .map(CompressionDictionary::memory_size)
+            .unwrap_or_default()
+    }
+
     /// Gets the table ID.
     ///
     /// The table ID is unique for this tree, but not
@@ -200,6 +233,13 @@ impl Table {
         block_type: BlockType,
         compression: CompressionType,
     ) -> crate::Result<Block> {
+        // NOTE: Only data blocks are compressed using the compression dictionary
+        let dictionary = if block_type == BlockType::Data {
+            self.compression_dictionary.as_ref()
+        } else {
+            None
+        };
+
         load_block(
             self.global_id(),
             &self.path,
@@ -208,6 +248,7 @@ impl Table {
             handle,
             block_type,
             compression,
+            dictionary,
             #[cfg(feature = "metrics")]
             &self.metrics,
         )
@@ -233,9 +274,6 @@ impl Table {
         seqno: SeqNo,
         key_hash: u64,
     ) -> crate::Result<Option<InternalValue>> {
-        #[cfg(feature = "metrics")]
-        use std::sync::atomic::Ordering::Relaxed;
-
         // Translate seqno to "our" seqno
         let seqno = seqno.saturating_sub(self.global_seqno());
 
@@ -243,28 +281,86 @@ impl Table {
             return Ok(None);
         }
 
-        let filter_block = if let Some(block) = &self.pinned_filter_block {
-            Some(Cow::Borrowed(block))
-        } else if let Some(filter_idx) = &self.pinned_filter_index {
-            let mut iter = filter_idx.iter();
-            iter.seek(key, seqno);
+        if let Some(filter_block) = self.load_filter_block(key, seqno)? {
+            if !self.filter_may_contain(&filter_block, key_hash)? {
+                return Ok(None);
+            }
+        }
 
-            if let Some(filter_block_handle) = iter.next() {
-                let filter_block_handle = filter_block_handle.materialize(filter_idx.as_slice());
+        self.point_read(key, seqno)
+    }
 
-                let block = self.load_block(
-                    &filter_block_handle.into_inner(),
-                    BlockType::Filter,
-                    CompressionType::None, // NOTE: We never write a filter block with compression
-                )?;
-                let block = FilterBlock::new(block);
+    /// Retrieves multiple items from the table.
+    ///
+    /// The keys need to be sorted in ascending order and come with their precomputed filter hashes.
+    /// Lookups that land in the same data block share the block load.
+    ///
+    /// The results are returned in the order of the given keys.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn multi_get(
+        &self,
+        keys: &[(&[u8], u64)],
+        seqno: SeqNo,
+    ) -> crate::Result<Vec<Option<InternalValue>>> {
+        // Translate seqno to "our" seqno
+        let seqno = seqno.saturating_sub(self.global_seqno());
 
-                Some(Cow::Owned(block))
-            } else {
-                None
-            }
-        } else if let Some(_filter_tli_handle) = &self.regions.filter_tli {
-            unimplemented!("unpinned filter TLI not supported");
+        if self.metadata.seqnos.0 >= seqno {
+            return Ok(keys.iter().map(|_| None).collect());
+        }
+
+        // NOTE: A full filter is the same for every key, so we only load it once
+        let full_filter_block = if self.regions.filter_tli.is_none() {
+            self.load_filter_block(&[], seqno)?
+        } else {
+            None
+        };
+
+        let mut last_block = None;
+
+        keys.iter()
+            .map(|&(key, key_hash)| {
+                let filter_block = match &full_filter_block {
+                    Some(block) => Some(Cow::Borrowed(&**block)),
+                    None => self.load_filter_block(key, seqno)?,
+                };
+
+                if let Some(filter_block) = filter_block {
+                    if !self.filter_may_contain(&filter_block, key_hash)? {
+                        return Ok(None);
+                    }
+                }
+
+                self.point_read_with_block(key, seqno, &mut last_block)
+            })
+            .collect()
+    }
+
+    /// Loads the filter block that may contain the given key, if the table has a filter.
+    fn load_filter_block(
+        &self,
+        key: &[u8],
+        seqno: SeqNo,
+    ) -> crate::Result<Option<Cow<'_, FilterBlock>>> {
+        let filter_block = if let Some(block) = &self.pinned_filter_block {
+            Some(Cow::Borrowed(block))
+        } else if let Some(filter_idx) = &self.pinned_filter_index {
+            self.load_filter_partition(filter_idx, key, seqno)?
+                .map(Cow::Owned)
+        } else if let Some(filter_tli_handle) = &self.regions.filter_tli {
+            let filter_idx = self
+                .load_block(
+                    filter_tli_handle,
+                    BlockType::Index,
+                    self.metadata.index_block_compression,
+                )
+                .map(IndexBlock::new)?;
+
+            self.load_filter_partition(&filter_idx, key, seqno)?
+                .map(Cow::Owned)
         } else if let Some(filter_block_handle) = &self.regions.filter {
             let block = self.load_block(
                 filter_block_handle,
@@ -278,19 +374,88 @@ impl Table {
             None
         };
 
-        if let Some(filter_block) = filter_block {
-            #[cfg(feature = "metrics")]
-            self.metrics.filter_queries.fetch_add(1, Relaxed);
+        Ok(filter_block)
+    }
 
-            if !filter_block.maybe_contains_hash(key_hash)? {
-                #[cfg(feature = "metrics")]
-                self.metrics.io_skipped_by_filter.fetch_add(1, Relaxed);
+    /// Queries the filter, returns `false` if the key is definitely not contained.
+    #[cfg_attr(
+        not(feature = "metrics"),
+        expect(clippy::unused_self, reason = "self is only needed for metrics")
+    )]
+    fn filter_may_contain(&self, filter_block: &FilterBlock, key_hash: u64) -> crate::Result<bool> {
+        #[cfg(feature = "metrics")]
+        use std::sync::atomic::Ordering::Relaxed;
 
-                return Ok(None);
-            }
+        #[cfg(feature = "metrics")]
+        self.metrics.filter_queries.fetch_add(1, Relaxed);
+
+        let contained = filter_block.maybe_contains_hash(key_hash)?;
+
+        #[cfg(feature = "metrics")]
+        if !contained {
+            self.metrics.io_skipped_by_filter.fetch_add(1, Relaxed);
         }
 
-        self.point_read(key, seqno)
+        Ok(contained)
+    }
+
+    /// Loads the filter partition that may contain the given key.
+    fn load_filter_partition(
+        &self,
+        filter_idx: &IndexBlock,
+        key: &[u8],
+        seqno: SeqNo,
+    ) -> crate::Result<Option<FilterBlock>> {
+        let mut iter = filter_idx.iter();
+        iter.seek(key, seqno);
+
+        let Some(filter_block_handle) = iter.next() else {
+            return Ok(None);
+        };
+
+        let filter_block_handle = filter_block_handle.materialize(filter_idx.as_slice());
+
+        self.load_block(
+            &filter_block_handle.into_inner(),
+            BlockType::Filter,
+            CompressionType::None, // NOTE: We never write a filter block with compression
+        )
+        .map(FilterBlock::new)
+        .map(Some)
+    }
+
+    /// Returns `false` if the table definitely does not contain any key with the given prefix.
+    ///
+    /// `extractor_name` is the name of the prefix extractor that produced the prefix,
+    /// tables that were written using another (or no) prefix extractor are never skipped.
+    pub fn maybe_contains_prefix(
+        &self,
+        extractor_name: &str,
+        prefix_hash: u64,
+    ) -> crate::Result<bool> {
+        if self.metadata.prefix_extractor.as_deref() != Some(extractor_name) {
+            return Ok(true);
+        }
+
+        // NOTE: Partitioned filters do not contain prefixes
+        if self.regions.filter_tli.is_some() {
+            return Ok(true);
+        }
+
+        let filter_block = if let Some(block) = &self.pinned_filter_block {
+            Cow::Borrowed(block)
+        } else if let Some(filter_block_handle) = &self.regions.filter {
+            let block = self.load_block(
+                filter_block_handle,
+                BlockType::Filter,
+                CompressionType::None, // NOTE: We never write a filter block with compression
+            )?;
+            Cow::Owned(FilterBlock::new(block))
+        } else {
+            return Ok(true);
+        };
+
+        self.filter_may_contain(&filter_block, prefix_hash)
     }
 
     // TODO: maybe we can skip Fuse costs of the user key
@@ -298,14 +463,32 @@ impl Table {
     // TODO: we would need to return something like ValueType + Value
     // TODO: so the caller can decide whether to return the value or not
     fn point_read(&self, key: &[u8], seqno: SeqNo) -> crate::Result<Option<InternalValue>> {
+        self.point_read_with_block(key, seqno, &mut None)
+    }
+
+    /// Point read that reuses the last loaded data block if the key lands in it.
+    fn point_read_with_block(
+        &self,
+        key: &[u8],
+        seqno: SeqNo,
+        last_block: &mut Option<(BlockOffset, DataBlock)>,
+    ) -> crate::Result<Option<InternalValue>> {
         let Some(iter) = self.block_index.forward_reader(key, seqno) else {
             return Ok(None);
         };
 
         for block_handle in iter {
             let block_handle = block_handle?;
-
-            let block = self.load_data_block(block_handle.as_ref())?;
+            let offset = block_handle.as_ref().offset();
+
+            let block = match last_block {
+                Some((last_offset, block)) if *last_offset == offset => block.clone(),
+                _ => {
+                    let block = self.load_data_block(block_handle.as_ref())?;
+                    *last_block = Some((offset, block.clone()));
+                    block
+                }
+            };
 
             if let Some(item) = block.point_read(key, seqno) {
                 return Ok(Some(item));
@@ -348,6 +531,7 @@ impl Table {
             &self.path,
             block_count,
             self.metadata.data_block_compression,
+            self.compression_dictionary.clone(),
             self.global_seqno(),
         )
     }
@@ -384,6 +568,7 @@ impl Table {
             self.descriptor_table.clone(),
             self.cache.clone(),
             self.metadata.data_block_compression,
+            self.compression_dictionary.clone(),
             #[cfg(feature = "metrics")]
             self.metrics.clone(),
         );
@@ -409,10 +594,6 @@ impl Table {
     /// This is used for merge operator support to collect all merge operands from a table.
     #[doc(hidden)]
     pub fn range_for_key(&self, key: &[u8], seqno: SeqNo) -> crate::Result<Vec<InternalValue>> {
-        if seqno == 0 {
-            return Ok(Vec::new());
-        }
-
         let mut results = Vec::new();
         let user_key: UserKey = key.into();
 
@@ -434,6 +615,50 @@ impl Table {
         Ok(results)
     }
 
+    fn read_range_tombstones(
+        handle: BlockHandle,
+        file: &File,
+        compression: CompressionType,
+        global_seqno: SeqNo,
+    ) -> crate::Result<RangeTombstoneSet> {
+        log::trace!("Reading range tombstone block, with ptr={handle:?}");
+
+        let block = Block::from_file(file, handle, compression, None)?;
+
+        if block.header.block_type != BlockType::RangeTombstone {
+            return Err(crate::Error::InvalidTag((
+                "BlockType",
+                block.header.block_type.into(),
+            )));
+        }
+
+        let block = DataBlock::new(block);
+
+        Ok(RangeTombstoneSet::new(block.iter().map(|item| {
+            let mut rt = RangeTombstone::from_internal_value(item.materialize(&block.inner.data));
+            rt.seqno += global_seqno;
+            rt
+        })))
+    }
+
+    fn read_compression_dictionary(
+        handle: BlockHandle,
+        file: &File,
+    ) -> crate::Result<CompressionDictionary> {
+        log::trace!("Reading compression dictionary, with ptr={handle:?}");
+
+        let block = Block::from_file(file, handle, CompressionType::None, None)?;
+
+        if block.header.block_type != BlockType::CompressionDictionary {
+            return Err(crate::Error::InvalidTag((
+                "BlockType",
+                block.header.block_type.into(),
+            )));
+        }
+
+        Ok(CompressionDictionary::new(block.data))
+    }
+
     fn read_tli(
         regions: &ParsedRegions,
         file: &File,
@@ -441,7 +666,7 @@ impl Table {
     ) -> crate::Result<IndexBlock> {
         log::trace!("Reading TLI block, with tli_ptr={:?}", regions.tli);
 
-        let block = Block::from_file(file, regions.tli, compression)?;
+        let block = Block::from_file(file, regions.tli, compression, None)?;
 
         if block.header.block_type != BlockType::Index {
             return Err(crate::Error::InvalidTag((
@@ -466,8 +691,7 @@ impl Table {
         tree_id: TreeId,
         cache: Arc<Cache>,
         descriptor_table: Arc<DescriptorTable>,
-        pin_filter: bool,
-        pin_index: bool,
+        pinning: Pinning,
         #[cfg(feature = "metrics")] metrics: Arc<Metrics>,
     ) -> crate::Result<Self> {
         use meta::ParsedMeta;
@@ -507,7 +731,7 @@ impl Table {
                 #[cfg(feature = "metrics")]
                 metrics: metrics.clone(),
             })
-        } else if pin_index {
+        } else if pinning.index {
             log::trace!(
                 "Creating pinned, full block index, with tli_ptr={:?}",
                 regions.tli,
@@ -531,16 +755,25 @@ impl Table {
             })
         };
 
-        let pinned_filter_index = if let Some(filter_tli_handle) = regions.filter_tli {
-            let block =
-                Block::from_file(&file, filter_tli_handle, metadata.index_block_compression)?;
-            Some(IndexBlock::new(block))
-        } else {
-            None
+        let pinned_filter_index = match regions.filter_tli {
+            Some(filter_tli_handle) if pinning.filter_tli => {
+                log::debug!(
+                    "Loading and pinning filter top level index, with filter_tli_ptr={filter_tli_handle:?}"
+                );
+
+                let block = Block::from_file(
+                    &file,
+                    filter_tli_handle,
+                    metadata.index_block_compression,
+                    None,
+                )?;
+                Some(IndexBlock::new(block))
+            }
+            _ => None,
         };
 
         // TODO: FilterBlock newtype
-        let pinned_filter_block = if pinned_filter_index.is_none() && pin_filter {
+        let pinned_filter_block = if regions.filter_tli.is_none() && pinning.filter {
             regions
                 .filter
                 .map(|filter_handle| {
@@ -552,6 +785,7 @@ impl Table {
                         &file,
                         filter_handle,
                         crate::CompressionType::None, // NOTE: We never write a filter block with compression
+                        None,
                     )
                     .and_then(|block| {
                         if block.header.block_type == BlockType::Filter {
@@ -571,6 +805,24 @@ impl Table {
             None
         };
 
+        let range_tombstones = regions
+            .range_tombstones
+            .map(|handle| {
+                Self::read_range_tombstones(
+                    handle,
+                    &file,
+                    metadata.data_block_compression,
+                    global_seqno,
+                )
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let compression_dictionary = regions
+            .compression_dictionary
+            .map(|handle| Self::read_compression_dictionary(handle, &file))
+            .transpose()?;
+
         descriptor_table.insert_for_table((tree_id, metadata.id).into(), Arc::new(file));
 
         log::trace!("Table #{} recovered", metadata.id);
@@ -594,6 +846,10 @@ impl Table {
 
             pinned_filter_block,
 
+            range_tombstones,
+
+            compression_dictionary,
+
             is_deleted: AtomicBool::default(),
 
             checksum,
@@ -622,6 +878,19 @@ impl Table {
         self.metadata.key_range.overlaps_with_bounds(bounds)
     }
 
+    /// Returns the (fragmented) range tombstones of the table.
+    #[must_use]
+    #[doc(hidden)]
+    pub fn range_tombstones(&self) -> &[RangeTombstone] {
+        self.range_tombstones.fragments()
+    }
+
+    /// Returns the highest sequence number of a range tombstone
+    /// containing the key that is visible at the given sequence number.
+    pub(crate) fn max_covering_seqno(&self, key: &[u8], seqno: SeqNo) -> Option<SeqNo> {
+        self.range_tombstones.max_covering_seqno(key, seqno)
+    }
+
     /// Returns the highest sequence number in the table.
     #[must_use]
     pub fn get_highest_seqno(&self) -> SeqNo {
@@ -653,8 +922,61 @@ impl Table {
     #[must_use]
     #[doc(hidden)]
     pub fn tombstone_ratio(&self) -> f32 {
-        todo!()
+        if self.metadata.item_count == 0 {
+            return 0.0;
+        }
 
-        //  self.metadata.tombstone_count as f32 / self.metadata.key_count as f32
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "precision loss is acceptable for ratios"
+        )]
+        {
+            self.metadata.tombstone_count as f32 / self.metadata.item_count as f32
+        }
+    }
+
+    /// Returns the ratio of items in the `Table` that can be dropped by rewriting it
+    /// into the last level, once its items are below the GC watermark.
+    ///
+    /// This includes all tombstones, and the values shadowed by weak tombstones.
+    #[must_use]
+    #[doc(hidden)]
+    pub fn reclaimable_ratio(&self) -> f32 {
+        if self.metadata.item_count == 0 {
+            return 0.0;
+        }
+
+        let reclaimable = self.metadata.tombstone_count + self.metadata.weak_tombstone_reclaimable;
+
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "precision loss is acceptable for ratios"
+        )]
+        {
+            (reclaimable as f32 / self.metadata.item_count as f32).min(1.0)
+        }
+    }
+
+    /// Returns the compression ratio (uncompressed size / compressed size)
+    /// of the data blocks in the `Table`.
+    ///
+    /// Data blocks that are stored uncompressed are included, so this is
+    /// the ratio that is actually achieved on disk.
+    ///
+    /// Returns `None` if the table was written before the compressed size was tracked.
+    #[must_use]
+    #[doc(hidden)]
+    pub fn compression_ratio(&self) -> Option<f32> {
+        let compressed_data_size = self.metadata.compressed_data_size?;
+
+        if compressed_data_size == 0 {
+            return Some(1.0);
+        }
+
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "precision loss is acceptable for ratios"
+        )]
+        Some(self.metadata.uncompressed_data_size as f32 / compressed_data_size as f32)
     }
 }
@@ -4,10 +4,56 @@
 
 use super::{filter::BloomConstructionPolicy, writer::Writer};
 use crate::{
-    blob_tree::handle::BlobIndirection, table::writer::LinkedFile, value::InternalValue,
-    vlog::BlobFileId, Checksum, CompressionType, HashMap, SequenceNumberCounter, TableId, UserKey,
+    blob_tree::handle::BlobIndirection,
+    prefix::PrefixExtractor,
+    range_tombstone::{successor, RangeTombstone},
+    table::writer::LinkedFile,
+    value::InternalValue,
+    vlog::BlobFileId,
+    Checksum, ChecksumType, CompressionType, HashMap, SequenceNumberCounter, TableId, UserKey,
 };
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
+
+/// Tracks how many bytes of the grandparent level (the level below the destination level)
+/// the current table overlaps
+struct GrandparentOverlap {
+    /// Largest key and size of every grandparent table, sorted by key
+    boundaries: Vec<(UserKey, u64)>,
+
+    /// Index of the first grandparent table that has not been passed yet
+    idx: usize,
+
+    /// Bytes of grandparent tables the current table overlaps
+    overlapped_bytes: u64,
+
+    /// Maximum number of overlapped bytes before a new table is started
+    limit: u64,
+
+    seen_key: bool,
+}
+
+impl GrandparentOverlap {
+    /// Advances to the given key, and returns `true` if the current table
+    /// should be cut before it.
+    fn should_cut_before(&mut self, key: &[u8]) -> bool {
+        while let Some((max_key, size)) = self.boundaries.get(self.idx) {
+            if key <= max_key.as_ref() {
+                break;
+            }
+
+            // NOTE: Grandparent tables before the first key of the table do not overlap it
+            if self.seen_key {
+                self.overlapped_bytes += size;
+            }
+
+            self.idx += 1;
+        }
+
+        self.seen_key = true;
+
+        self.overlapped_bytes > self.limit
+    }
+}
 
 /// Like `Writer` but will rotate to a new table, once a table grows larger than `target_size`
 ///
@@ -40,14 +86,37 @@ pub struct MultiWriter {
     pub data_block_compression: CompressionType,
     pub index_block_compression: CompressionType,
 
+    /// Minimum compression ratio a data block needs to reach to be stored compressed
+    data_block_compression_ratio: f32,
+
+    checksum_type: ChecksumType,
+
     bloom_policy: BloomConstructionPolicy,
 
+    prefix_extractor: Option<Arc<dyn PrefixExtractor>>,
+
     current_key: Option<UserKey>,
 
     linked_blobs: HashMap<BlobFileId, LinkedFile>,
 
+    /// Range tombstones that are distributed over the tables
+    range_tombstones: Vec<RangeTombstone>,
+
+    /// Lowest key that the current table is responsible for
+    ///
+    /// Range tombstones are clipped to the table boundaries,
+    /// so the tables' key ranges stay disjoint.
+    lower_bound: Option<UserKey>,
+
     /// Level the tables are written to
     initial_level: u8,
+
+    /// If set, tables are also rotated when they overlap too many bytes in the grandparent level
+    grandparent_overlap: Option<GrandparentOverlap>,
+
+    /// Maximum size of the compression dictionary of each table (0 = disabled)
+    #[cfg(feature = "zstd")]
+    compression_dictionary_size: u32,
 }
 
 impl MultiWriter {
@@ -83,14 +152,28 @@ impl MultiWriter {
             data_block_compression: CompressionType::None,
             index_block_compression: CompressionType::None,
 
+            data_block_compression_ratio: 1.0,
+
+            checksum_type: ChecksumType::Xxh3,
+
             use_partitioned_index: false,
             use_partitioned_filter: false,
 
             bloom_policy: BloomConstructionPolicy::default(),
 
+            prefix_extractor: None,
+
             current_key: None,
 
             linked_blobs: HashMap::default(),
+
+            range_tombstones: Vec::new(),
+            lower_bound: None,
+
+            grandparent_overlap: None,
+
+            #[cfg(feature = "zstd")]
+            compression_dictionary_size: 0,
         })
     }
 
@@ -110,6 +193,46 @@ impl MultiWriter {
             });
     }
 
+    /// Sets the range tombstones that should be written into the tables.
+    #[must_use]
+    pub fn use_range_tombstones(mut self, tombstones: Vec<RangeTombstone>) -> Self {
+        self.range_tombstones = tombstones;
+        self
+    }
+
+    /// Limits the bytes a table may overlap in the grandparent level.
+    ///
+    /// `grandparents` contains the largest key and size of every table in the grandparent level.
+    ///
+    /// Tables are cut at grandparent table boundaries, so the compaction
+    /// that eventually merges a table into the grandparent level stays small.
+    #[must_use]
+    pub fn use_grandparent_overlap_limit(
+        mut self,
+        mut grandparents: Vec<(UserKey, u64)>,
+        limit: u64,
+    ) -> Self {
+        grandparents.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        self.grandparent_overlap = Some(GrandparentOverlap {
+            boundaries: grandparents,
+            idx: 0,
+            overlapped_bytes: 0,
+            limit,
+            seen_key: false,
+        });
+        self
+    }
+
+    /// Writes the range tombstones that overlap `[lower_bound, upper_bound)` into the current table.
+    fn write_range_tombstones(&mut self, upper_bound: Option<&[u8]>) {
+        for rt in &self.range_tombstones {
+            if let Some(rt) = rt.clip(self.lower_bound.as_deref(), upper_bound) {
+                self.writer.write_range_tombstone(rt);
+            }
+        }
+    }
+
     #[must_use]
     pub fn use_partitioned_index(mut self) -> Self {
         self.use_partitioned_index = true;
@@ -163,6 +286,13 @@ impl MultiWriter {
         self
     }
 
+    #[must_use]
+    pub fn use_data_block_compression_ratio(mut self, ratio: f32) -> Self {
+        self.data_block_compression_ratio = ratio;
+        self.writer = self.writer.use_data_block_compression_ratio(ratio);
+        self
+    }
+
     #[must_use]
     pub fn use_index_block_compression(mut self, compression: CompressionType) -> Self {
         self.index_block_compression = compression;
@@ -170,6 +300,21 @@ impl MultiWriter {
         self
     }
 
+    #[cfg(feature = "zstd")]
+    #[must_use]
+    pub fn use_compression_dictionary_size(mut self, size: u32) -> Self {
+        self.compression_dictionary_size = size;
+        self.writer = self.writer.use_compression_dictionary_size(size);
+        self
+    }
+
+    #[must_use]
+    pub fn use_checksum_type(mut self, checksum_type: ChecksumType) -> Self {
+        self.checksum_type = checksum_type;
+        self.writer = self.writer.use_checksum_type(checksum_type);
+        self
+    }
+
     #[must_use]
     pub fn use_bloom_policy(mut self, bloom_policy: BloomConstructionPolicy) -> Self {
         self.bloom_policy = bloom_policy;
@@ -177,20 +322,41 @@ impl MultiWriter {
         self
     }
 
+    #[must_use]
+    pub fn use_prefix_extractor(mut self, extractor: Option<Arc<dyn PrefixExtractor>>) -> Self {
+        self.prefix_extractor.clone_from(&extractor);
+        self.writer = self.writer.use_prefix_extractor(extractor);
+        self
+    }
+
     /// Flushes the current writer, stores its metadata, and sets up a new writer for the next table
-    fn rotate(&mut self) -> crate::Result<()> {
+    ///
+    /// `last_key` is the last key that was written to the current table.
+    fn rotate(&mut self, last_key: &[u8]) -> crate::Result<()> {
         log::debug!("Rotating table writer");
 
+        // NOTE: The current table covers everything up to (and including) its last key
+        let upper_bound = successor(last_key);
+        self.write_range_tombstones(Some(&upper_bound));
+        self.lower_bound = Some(upper_bound);
+
+        if let Some(grandparent_overlap) = &mut self.grandparent_overlap {
+            grandparent_overlap.overlapped_bytes = 0;
+        }
+
         let new_table_id = self.table_id_generator.next();
         let path = self.base_path.join(new_table_id.to_string());
 
         let mut new_writer = Writer::new(path, new_table_id, self.initial_level)?
             .use_data_block_compression(self.data_block_compression)
+            .use_data_block_compression_ratio(self.data_block_compression_ratio)
+            .use_checksum_type(self.checksum_type)
             .use_index_block_compression(self.index_block_compression)
             .use_data_block_size(self.data_block_size)
             .use_data_block_restart_interval(self.data_block_restart_interval)
             .use_index_block_restart_interval(self.index_block_restart_interval)
             .use_bloom_policy(self.bloom_policy)
+            .use_prefix_extractor(self.prefix_extractor.clone())
             .use_data_block_hash_ratio(self.data_block_hash_ratio);
 
         if self.use_partitioned_index {
@@ -200,6 +366,12 @@ impl MultiWriter {
             new_writer = new_writer.use_partitioned_filter();
         }
 
+        #[cfg(feature = "zstd")]
+        {
+            new_writer =
+                new_writer.use_compression_dictionary_size(self.compression_dictionary_size);
+        }
+
         let mut old_writer = std::mem::replace(&mut self.writer, new_writer);
 
         for linked in self.linked_blobs.values() {
@@ -224,10 +396,17 @@ impl MultiWriter {
         let is_next_key = self.current_key.as_ref() < Some(&item.key.user_key);
 
         if is_next_key {
-            self.current_key = Some(item.key.user_key.clone());
+            let grandparent_limit_reached = self
+                .grandparent_overlap
+                .as_mut()
+                .is_some_and(|x| x.should_cut_before(&item.key.user_key));
+
+            let last_key = self.current_key.replace(item.key.user_key.clone());
 
-            if *self.writer.meta.file_pos >= self.target_size {
-                self.rotate()?;
+            if let Some(last_key) = last_key {
+                if self.writer.file_size() >= self.target_size || grandparent_limit_reached {
+                    self.rotate(&last_key)?;
+                }
             }
         }
 
@@ -240,6 +419,8 @@ impl MultiWriter {
     ///
     /// Returns the metadata of created tables
     pub fn finish(mut self) -> crate::Result<Vec<(TableId, Checksum)>> {
+        self.write_range_tombstones(None);
+
         for linked in self.linked_blobs.values() {
             self.writer.link_blob_file(
                 linked.blob_file_id,
@@ -329,4 +510,109 @@ mod tests {
 
         Ok(())
     }
+
+    /// Writes 10 disjoint tables into L2 and a single overlapping table into L1,
+    /// then merges a table covering all keys from L0 into L1
+    ///
+    /// Returns the size of the largest grandparent table
+    fn compact_over_grandparents(tree: &crate::AnyTree) -> crate::Result<u64> {
+        use crate::compaction::{Leveled, MoveDown};
+        use std::sync::Arc;
+
+        for table_idx in 0..10u64 {
+            for key in (table_idx * 10)..((table_idx + 1) * 10) {
+                tree.insert(key.to_be_bytes(), "v".repeat(100), 0);
+            }
+            tree.flush_active_memtable(0)?;
+        }
+        tree.compact(Arc::new(MoveDown(0, 2)), 0)?;
+
+        tree.insert(50u64.to_be_bytes(), "v", 1);
+        tree.flush_active_memtable(0)?;
+        tree.compact(Arc::new(MoveDown(0, 1)), 0)?;
+
+        for key in 0..100u64 {
+            tree.insert(key.to_be_bytes(), "w", 2);
+        }
+        tree.flush_active_memtable(0)?;
+        tree.compact(Arc::new(Leveled::default().with_l0_threshold(1)), 0)?;
+
+        assert_eq!(Some(0), tree.level_table_count(0));
+        assert_eq!(Some(10), tree.level_table_count(2));
+        assert_eq!(100, tree.len(SeqNo::MAX, None)?);
+
+        Ok(tree
+            .current_version()
+            .level(2)
+            .expect("level should exist")
+            .iter()
+            .flat_map(|run| run.iter())
+            .map(crate::Table::file_size)
+            .max()
+            .unwrap_or_default())
+    }
+
+    #[test]
+    fn table_multi_writer_grandparent_overlap_unlimited() -> crate::Result<()> {
+        let folder = tempfile::tempdir()?;
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        compact_over_grandparents(&tree)?;
+        assert_eq!(Some(1), tree.level_table_count(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn table_multi_writer_grandparent_overlap_limit() -> crate::Result<()> {
+        let folder = tempfile::tempdir()?;
+
+        // NOTE: The grandparent tables are all roughly the same size
+        let grandparent_size = {
+            let folder = tempfile::tempdir()?;
+            let tree = Config::new(
+                &folder,
+                SequenceNumberCounter::default(),
+                SequenceNumberCounter::default(),
+            )
+            .open()?;
+            compact_over_grandparents(&tree)?
+        };
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .max_grandparent_overlap_bytes(Some(2 * grandparent_size))
+        .open()?;
+
+        compact_over_grandparents(&tree)?;
+
+        let version = tree.current_version();
+        let l1 = version.level(1).expect("level should exist");
+        let l2 = version.level(2).expect("level should exist");
+
+        assert!(l1.table_count() > 1);
+        assert!(l1.is_disjoint());
+
+        for table in l1.iter().flat_map(|run| run.iter()) {
+            let overlap = l2
+                .iter()
+                .flat_map(|run| run.get_overlapping(&table.metadata.key_range))
+                .count();
+
+            // NOTE: The limit is crossed once, and the last key may
+            // straddle into one more grandparent table
+            assert!(overlap <= 4, "table overlaps {overlap} grandparents");
+        }
+
+        Ok(())
+    }
 }
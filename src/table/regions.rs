@@ -25,6 +25,8 @@ fn toc_entry_to_handle(entry: &TocEntry) -> BlockHandle {
 /// ----------------
 /// |     data     | <- implicitly start at 0
 /// |--------------|
+/// |   range del  | <- may not exist
+/// |--------------|
 /// |      tli     |
 /// |--------------|
 /// |     index    | <- may not exist (if full block index is used, TLI will be dense)
@@ -35,6 +37,8 @@ fn toc_entry_to_handle(entry: &TocEntry) -> BlockHandle {
 /// |--------------|
 /// | linked blobs | <- may not exist
 /// |--------------|
+/// |  dictionary  | <- may not exist
+/// |--------------|
 /// |     meta     |
 /// |--------------|
 /// |     toc      |
@@ -47,7 +51,9 @@ pub struct ParsedRegions {
     pub index: Option<BlockHandle>,
     pub filter_tli: Option<BlockHandle>,
     pub filter: Option<BlockHandle>,
+    pub range_tombstones: Option<BlockHandle>,
     pub linked_blob_files: Option<BlockHandle>,
+    pub compression_dictionary: Option<BlockHandle>,
     pub metadata: BlockHandle,
 }
 
@@ -64,7 +70,11 @@ impl ParsedRegions {
                 })?,
             index: toc.section(b"index").map(toc_entry_to_handle),
             filter: toc.section(b"filter").map(toc_entry_to_handle),
+            range_tombstones: toc.section(b"range_tombstones").map(toc_entry_to_handle),
             linked_blob_files: toc.section(b"linked_blob_files").map(toc_entry_to_handle),
+            compression_dictionary: toc
+                .section(b"compression_dictionary")
+                .map(toc_entry_to_handle),
             metadata: toc
                 .section(b"meta")
                 .map(toc_entry_to_handle)
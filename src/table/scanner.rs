@@ -5,7 +5,7 @@
 use super::{Block, DataBlock};
 use crate::{
     table::{block::BlockType, iter::OwnedDataBlockIter},
-    CompressionType, InternalValue, SeqNo,
+    CompressionDictionary, CompressionType, InternalValue, SeqNo,
 };
 use std::{fs::File, io::BufReader, path::Path};
 
@@ -15,6 +15,7 @@ pub struct Scanner {
     iter: OwnedDataBlockIter,
 
     compression: CompressionType,
+    compression_dictionary: Option<CompressionDictionary>,
     block_count: usize,
     read_count: usize,
 
@@ -26,12 +27,14 @@ impl Scanner {
         path: &Path,
         block_count: usize,
         compression: CompressionType,
+        compression_dictionary: Option<CompressionDictionary>,
         global_seqno: SeqNo,
     ) -> crate::Result<Self> {
         // TODO: a larger buffer size may be better for HDD, maybe make this configurable
         let mut reader = BufReader::with_capacity(8 * 4_096, File::open(path)?);
 
-        let block = Self::fetch_next_block(&mut reader, compression)?;
+        let block =
+            Self::fetch_next_block(&mut reader, compression, compression_dictionary.as_ref())?;
         let iter = OwnedDataBlockIter::new(block, DataBlock::iter);
 
         Ok(Self {
@@ -39,6 +42,7 @@ impl Scanner {
             iter,
 
             compression,
+            compression_dictionary,
             block_count,
             read_count: 1,
 
@@ -49,8 +53,9 @@ impl Scanner {
     fn fetch_next_block(
         reader: &mut BufReader<File>,
         compression: CompressionType,
+        compression_dictionary: Option<&CompressionDictionary>,
     ) -> crate::Result<DataBlock> {
-        let block = Block::from_reader(reader, compression);
+        let block = Block::from_reader(reader, compression, compression_dictionary);
 
         match block {
             Ok(block) => {
@@ -83,7 +88,11 @@ impl Iterator for Scanner {
             }
 
             // Init new block
-            let block = fail_iter!(Self::fetch_next_block(&mut self.reader, self.compression));
+            let block = fail_iter!(Self::fetch_next_block(
+                &mut self.reader,
+                self.compression,
+                self.compression_dictionary.as_ref(),
+            ));
             self.iter = OwnedDataBlockIter::new(block, DataBlock::iter);
 
             self.read_count += 1;
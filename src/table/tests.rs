@@ -52,8 +52,7 @@ fn test_with_table(
                 0,
                 Arc::new(Cache::with_capacity_bytes(1_000_000)),
                 Arc::new(DescriptorTable::new(10)),
-                false,
-                false,
+                Pinning::default(),
                 #[cfg(feature = "metrics")]
                 metrics,
             )?;
@@ -78,8 +77,11 @@ fn test_with_table(
                 0,
                 Arc::new(Cache::with_capacity_bytes(1_000_000)),
                 Arc::new(DescriptorTable::new(10)),
-                true,
-                false,
+                Pinning {
+                    filter: true,
+                    filter_tli: true,
+                    index: false,
+                },
                 #[cfg(feature = "metrics")]
                 metrics,
             )?;
@@ -104,8 +106,11 @@ fn test_with_table(
                 0,
                 Arc::new(Cache::with_capacity_bytes(1_000_000)),
                 Arc::new(DescriptorTable::new(10)),
-                false,
-                true,
+                Pinning {
+                    filter: false,
+                    filter_tli: false,
+                    index: true,
+                },
                 #[cfg(feature = "metrics")]
                 metrics,
             )?;
@@ -130,8 +135,11 @@ fn test_with_table(
                 0,
                 Arc::new(Cache::with_capacity_bytes(1_000_000)),
                 Arc::new(DescriptorTable::new(10)),
-                true,
-                true,
+                Pinning {
+                    filter: true,
+                    filter_tli: true,
+                    index: true,
+                },
                 #[cfg(feature = "metrics")]
                 metrics,
             )?;
@@ -176,8 +184,7 @@ fn test_with_table(
                 0,
                 Arc::new(Cache::with_capacity_bytes(1_000_000)),
                 Arc::new(DescriptorTable::new(10)),
-                false,
-                false,
+                Pinning::default(),
                 #[cfg(feature = "metrics")]
                 metrics,
             )?;
@@ -201,8 +208,11 @@ fn test_with_table(
                 0,
                 Arc::new(Cache::with_capacity_bytes(1_000_000)),
                 Arc::new(DescriptorTable::new(10)),
-                true,
-                false,
+                Pinning {
+                    filter: true,
+                    filter_tli: true,
+                    index: false,
+                },
                 #[cfg(feature = "metrics")]
                 metrics,
             )?;
@@ -226,8 +236,11 @@ fn test_with_table(
                 0,
                 Arc::new(Cache::with_capacity_bytes(1_000_000)),
                 Arc::new(DescriptorTable::new(10)),
-                false,
-                true,
+                Pinning {
+                    filter: false,
+                    filter_tli: false,
+                    index: true,
+                },
                 #[cfg(feature = "metrics")]
                 metrics,
             )?;
@@ -252,8 +265,11 @@ fn test_with_table(
                 0,
                 Arc::new(Cache::with_capacity_bytes(1_000_000)),
                 Arc::new(DescriptorTable::new(10)),
-                true,
-                true,
+                Pinning {
+                    filter: true,
+                    filter_tli: true,
+                    index: true,
+                },
                 #[cfg(feature = "metrics")]
                 metrics,
             )?;
@@ -1136,8 +1152,11 @@ fn table_read_fuzz_1() -> crate::Result<()> {
         0,
         Arc::new(crate::Cache::with_capacity_bytes(0)),
         Arc::new(crate::DescriptorTable::new(10)),
-        true,
-        true,
+        Pinning {
+            filter: true,
+            filter_tli: true,
+            index: true,
+        },
     )
     .unwrap();
 
@@ -1210,8 +1229,11 @@ fn table_partitioned_index() -> crate::Result<()> {
         0,
         Arc::new(crate::Cache::with_capacity_bytes(0)),
         Arc::new(crate::DescriptorTable::new(10)),
-        true,
-        true,
+        Pinning {
+            filter: true,
+            filter_tli: true,
+            index: true,
+        },
         #[cfg(feature = "metrics")]
         Default::default(),
     )
@@ -1320,8 +1342,11 @@ fn table_global_seqno() -> crate::Result<()> {
         0,
         Arc::new(crate::Cache::with_capacity_bytes(0)),
         Arc::new(crate::DescriptorTable::new(10)),
-        true,
-        true,
+        Pinning {
+            filter: true,
+            filter_tli: true,
+            index: true,
+        },
         #[cfg(feature = "metrics")]
         Default::default(),
     )
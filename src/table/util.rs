@@ -4,8 +4,8 @@
 
 use super::{Block, BlockHandle, GlobalTableId};
 use crate::{
-    table::block::BlockType, version::run::Ranged, Cache, CompressionType, DescriptorTable,
-    KeyRange, Table,
+    table::block::BlockType, version::run::Ranged, Cache, CompressionDictionary, CompressionType,
+    DescriptorTable, KeyRange, Table,
 };
 use std::{path::Path, sync::Arc};
 
@@ -28,7 +28,7 @@ pub struct SliceIndexes(pub usize, pub usize);
 /// Loads a block from disk or block cache, if cached.
 ///
 /// Also handles file descriptor opening and caching.
-#[warn(clippy::too_many_arguments)]
+#[expect(clippy::too_many_arguments)]
 pub fn load_block(
     table_id: GlobalTableId,
     path: &Path,
@@ -37,6 +37,7 @@ pub fn load_block(
     handle: &BlockHandle,
     block_type: BlockType,
     compression: CompressionType,
+    dictionary: Option<&CompressionDictionary>,
     #[cfg(feature = "metrics")] metrics: &Metrics,
 ) -> crate::Result<Block> {
     #[cfg(feature = "metrics")]
@@ -79,7 +80,7 @@ pub fn load_block(
         Arc::new(fd)
     };
 
-    let block = Block::from_file(&fd, *handle, compression)?;
+    let block = Block::from_file(&fd, *handle, compression, dictionary)?;
 
     if block.header.block_type != block_type {
         return Err(crate::Error::InvalidTag((
@@ -4,7 +4,7 @@
 
 use super::FilterWriter;
 use crate::{
-    checksum::ChecksummedWriter,
+    checksum::{ChecksumType, ChecksummedWriter},
     config::BloomConstructionPolicy,
     table::{filter::standard_bloom::Builder, Block},
     CompressionType, UserKey,
@@ -16,6 +16,8 @@ pub struct FullFilterWriter {
     pub bloom_hash_buffer: Vec<u64>,
 
     bloom_policy: BloomConstructionPolicy,
+
+    checksum_type: ChecksumType,
 }
 
 impl FullFilterWriter {
@@ -23,6 +25,7 @@ impl FullFilterWriter {
         Self {
             bloom_hash_buffer: Vec::new(),
             bloom_policy,
+            checksum_type: ChecksumType::Xxh3,
         }
     }
 }
@@ -36,6 +39,14 @@ impl<W: std::io::Write + std::io::Seek> FilterWriter<W> for FullFilterWriter {
         self
     }
 
+    fn use_checksum_type(
+        mut self: Box<Self>,
+        checksum_type: ChecksumType,
+    ) -> Box<dyn FilterWriter<W>> {
+        self.checksum_type = checksum_type;
+        self
+    }
+
     fn set_filter_policy(
         mut self: Box<Self>,
         policy: BloomConstructionPolicy,
@@ -49,6 +60,11 @@ impl<W: std::io::Write + std::io::Seek> FilterWriter<W> for FullFilterWriter {
         Ok(())
     }
 
+    fn register_prefix(&mut self, prefix: &[u8]) -> crate::Result<()> {
+        self.bloom_hash_buffer.push(Builder::get_hash(prefix));
+        Ok(())
+    }
+
     fn finish(
         self: Box<Self>,
         file_writer: &mut sfa::Writer<ChecksummedWriter<BufWriter<File>>>,
@@ -88,6 +104,7 @@ impl<W: std::io::Write + std::io::Seek> FilterWriter<W> for FullFilterWriter {
                 &filter_bytes,
                 crate::table::block::BlockType::Filter,
                 CompressionType::None,
+                self.checksum_type,
             )?;
         }
 
@@ -9,16 +9,21 @@ pub use full::FullFilterWriter;
 pub use partitioned::PartitionedFilterWriter;
 
 use crate::{
-    checksum::ChecksummedWriter, config::BloomConstructionPolicy, CompressionType, UserKey,
+    checksum::{ChecksumType, ChecksummedWriter},
+    config::BloomConstructionPolicy,
+    CompressionType, UserKey,
 };
 use std::{fs::File, io::BufWriter};
 
-pub trait FilterWriter<W: std::io::Write> {
+pub trait FilterWriter<W: std::io::Write>: Send {
     // NOTE: We purposefully use a UserKey instead of &[u8]
     // so we can clone it without heap allocation, if needed
     /// Registers a key in the block index.
     fn register_key(&mut self, key: &UserKey) -> crate::Result<()>;
 
+    /// Registers a key prefix in the filter.
+    fn register_prefix(&mut self, prefix: &[u8]) -> crate::Result<()>;
+
     /// Writes the filter to a file.
     ///
     /// Returns the number of filter blocks written (always 1 in case of full filter block).
@@ -37,5 +42,7 @@ pub trait FilterWriter<W: std::io::Write> {
         compression: CompressionType,
     ) -> Box<dyn FilterWriter<W>>;
 
+    fn use_checksum_type(self: Box<Self>, checksum_type: ChecksumType) -> Box<dyn FilterWriter<W>>;
+
     fn use_partition_size(self: Box<Self>, size: u32) -> Box<dyn FilterWriter<W>>;
 }
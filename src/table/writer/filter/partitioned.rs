@@ -4,7 +4,7 @@
 
 use super::FilterWriter;
 use crate::{
-    checksum::ChecksummedWriter,
+    checksum::{ChecksumType, ChecksummedWriter},
     config::BloomConstructionPolicy,
     table::{
         block::Header as BlockHeader, filter::standard_bloom::Builder, Block, BlockHandle,
@@ -35,6 +35,8 @@ pub struct PartitionedFilterWriter {
     last_key: Option<UserKey>,
 
     compression: CompressionType,
+
+    checksum_type: ChecksumType,
 }
 
 impl PartitionedFilterWriter {
@@ -54,6 +56,8 @@ impl PartitionedFilterWriter {
             last_key: None,
 
             compression: CompressionType::None,
+
+            checksum_type: ChecksumType::Xxh3,
         }
     }
 
@@ -73,6 +77,7 @@ impl PartitionedFilterWriter {
             &filter_bytes,
             crate::table::block::BlockType::Filter,
             CompressionType::None,
+            self.checksum_type,
         )?;
 
         #[expect(
@@ -119,6 +124,7 @@ impl PartitionedFilterWriter {
             &bytes,
             crate::table::block::BlockType::Index,
             self.compression,
+            self.checksum_type,
         )?;
 
         #[expect(
@@ -152,6 +158,14 @@ impl<W: std::io::Write + std::io::Seek> FilterWriter<W> for PartitionedFilterWri
         self
     }
 
+    fn use_checksum_type(
+        mut self: Box<Self>,
+        checksum_type: ChecksumType,
+    ) -> Box<dyn FilterWriter<W>> {
+        self.checksum_type = checksum_type;
+        self
+    }
+
     fn set_filter_policy(
         mut self: Box<Self>,
         policy: BloomConstructionPolicy,
@@ -176,6 +190,12 @@ impl<W: std::io::Write + std::io::Seek> FilterWriter<W> for PartitionedFilterWri
         Ok(())
     }
 
+    fn register_prefix(&mut self, _prefix: &[u8]) -> crate::Result<()> {
+        // NOTE: The keys of a prefix may be spread over multiple partitions,
+        // so prefix filtering is only supported for full filters
+        Ok(())
+    }
+
     fn finish(
         mut self: Box<Self>,
         file_writer: &mut sfa::Writer<ChecksummedWriter<BufWriter<File>>>,
@@ -3,7 +3,7 @@
 // (found in the LICENSE-* files in the repository)
 
 use crate::{
-    checksum::ChecksummedWriter,
+    checksum::{ChecksumType, ChecksummedWriter},
     table::{
         block::Header as BlockHeader, index_block::KeyedBlockHandle,
         writer::index::BlockIndexWriter, Block, IndexBlock,
@@ -14,6 +14,7 @@ use std::{fs::File, io::BufWriter};
 
 pub struct FullIndexWriter {
     compression: CompressionType,
+    checksum_type: ChecksumType,
     block_handles: Vec<KeyedBlockHandle>,
 }
 
@@ -21,6 +22,7 @@ impl FullIndexWriter {
     pub fn new() -> Self {
         Self {
             compression: CompressionType::None,
+            checksum_type: ChecksumType::Xxh3,
             block_handles: Vec::new(),
         }
     }
@@ -39,6 +41,14 @@ impl<W: std::io::Write + std::io::Seek> BlockIndexWriter<W> for FullIndexWriter
         self
     }
 
+    fn use_checksum_type(
+        mut self: Box<Self>,
+        checksum_type: ChecksumType,
+    ) -> Box<dyn BlockIndexWriter<W>> {
+        self.checksum_type = checksum_type;
+        self
+    }
+
     fn register_data_block(&mut self, block_handle: KeyedBlockHandle) -> crate::Result<()> {
         log::trace!(
             "Registering block at {:?} with size {} [end_key={:?}]",
@@ -66,6 +76,7 @@ impl<W: std::io::Write + std::io::Seek> BlockIndexWriter<W> for FullIndexWriter
             &bytes,
             crate::table::block::BlockType::Index,
             self.compression,
+            self.checksum_type,
         )?;
 
         #[expect(
@@ -8,10 +8,14 @@ mod partitioned;
 pub use full::FullIndexWriter;
 pub use partitioned::PartitionedIndexWriter;
 
-use crate::{checksum::ChecksummedWriter, table::index_block::KeyedBlockHandle, CompressionType};
+use crate::{
+    checksum::{ChecksumType, ChecksummedWriter},
+    table::index_block::KeyedBlockHandle,
+    CompressionType,
+};
 use std::{fs::File, io::BufWriter};
 
-pub trait BlockIndexWriter<W: std::io::Write> {
+pub trait BlockIndexWriter<W: std::io::Write>: Send {
     /// Registers a data block in the block index.
     fn register_data_block(&mut self, block_handle: KeyedBlockHandle) -> crate::Result<()>;
 
@@ -28,5 +32,10 @@ pub trait BlockIndexWriter<W: std::io::Write> {
         compression: CompressionType,
     ) -> Box<dyn BlockIndexWriter<W>>;
 
+    fn use_checksum_type(
+        self: Box<Self>,
+        checksum_type: ChecksumType,
+    ) -> Box<dyn BlockIndexWriter<W>>;
+
     fn use_partition_size(self: Box<Self>, size: u32) -> Box<dyn BlockIndexWriter<W>>;
 }
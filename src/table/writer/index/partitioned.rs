@@ -3,7 +3,7 @@
 // (found in the LICENSE-* files in the repository)
 
 use crate::{
-    checksum::ChecksummedWriter,
+    checksum::{ChecksumType, ChecksummedWriter},
     table::{
         block::Header as BlockHeader, index_block::KeyedBlockHandle,
         writer::index::BlockIndexWriter, Block, BlockHandle, BlockOffset, IndexBlock,
@@ -19,6 +19,7 @@ pub struct PartitionedIndexWriter {
     relative_file_pos: u64,
 
     compression: CompressionType,
+    checksum_type: ChecksumType,
 
     tli_handles: Vec<KeyedBlockHandle>,
     data_block_handles: Vec<KeyedBlockHandle>,
@@ -42,6 +43,7 @@ impl PartitionedIndexWriter {
 
             partition_size: 4_096,
             compression: CompressionType::None,
+            checksum_type: ChecksumType::Xxh3,
 
             tli_handles: Vec::new(),
             data_block_handles: Vec::new(),
@@ -60,6 +62,7 @@ impl PartitionedIndexWriter {
             &bytes,
             crate::table::block::BlockType::Index,
             self.compression,
+            self.checksum_type,
         )?;
 
         #[expect(
@@ -122,6 +125,7 @@ impl PartitionedIndexWriter {
             &bytes,
             crate::table::block::BlockType::Index,
             self.compression,
+            self.checksum_type,
         )?;
 
         #[expect(
@@ -155,6 +159,14 @@ impl<W: std::io::Write + std::io::Seek> BlockIndexWriter<W> for PartitionedIndex
         self
     }
 
+    fn use_checksum_type(
+        mut self: Box<Self>,
+        checksum_type: ChecksumType,
+    ) -> Box<dyn BlockIndexWriter<W>> {
+        self.checksum_type = checksum_type;
+        self
+    }
+
     fn register_data_block(&mut self, block_handle: KeyedBlockHandle) -> crate::Result<()> {
         log::trace!(
             "Registering block at {:?} with size {} [end_key={:?}]",
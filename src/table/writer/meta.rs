@@ -29,6 +29,9 @@ pub struct Metadata {
     /// Only takes user data into account
     pub uncompressed_size: u64,
 
+    /// Size of all data blocks as stored on disk (excluding block headers)
+    pub compressed_size: u64,
+
     /// First encountered key
     pub first_key: Option<UserKey>,
 
@@ -54,6 +57,7 @@ impl Default for Metadata {
             key_count: 0,
             file_pos: BlockOffset(0),
             uncompressed_size: 0,
+            compressed_size: 0,
 
             first_key: None,
             last_key: None,
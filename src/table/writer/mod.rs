@@ -14,6 +14,8 @@ use crate::{
     checksum::{ChecksumType, ChecksummedWriter},
     coding::Encode,
     file::fsync_directory,
+    prefix::PrefixExtractor,
+    range_tombstone::{fragment, RangeTombstone},
     table::{
         writer::{
             filter::{FilterWriter, FullFilterWriter},
@@ -23,10 +25,10 @@ use crate::{
     },
     time::unix_timestamp,
     vlog::BlobFileId,
-    Checksum, CompressionType, InternalValue, TableId, UserKey, ValueType,
+    Checksum, CompressionType, InternalValue, SeqNo, TableId, UserKey, ValueType,
 };
 use index::BlockIndexWriter;
-use std::{fs::File, io::BufWriter, path::PathBuf};
+use std::{fs::File, io::BufWriter, path::PathBuf, sync::Arc};
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, std::hash::Hash)]
 pub struct LinkedFile {
@@ -36,6 +38,19 @@ pub struct LinkedFile {
     pub len: usize,
 }
 
+/// Zstd recommends training dictionaries on about 100x the dictionary size of samples
+#[cfg(feature = "zstd")]
+const DICTIONARY_SAMPLE_RATIO: usize = 100;
+
+/// Data block that is held back until the compression dictionary is trained
+#[cfg(feature = "zstd")]
+struct BufferedBlock {
+    data: Vec<u8>,
+    last_key: UserKey,
+    seqno: SeqNo,
+    item_count: usize,
+}
+
 /// Serializes and compresses values into blocks and writes them to disk as a table
 pub struct Writer {
     /// Table file path
@@ -55,6 +70,12 @@ pub struct Writer {
     /// Compression to use for data blocks
     data_block_compression: CompressionType,
 
+    /// Minimum compression ratio a data block needs to reach to be stored compressed
+    data_block_compression_ratio: f32,
+
+    /// Checksum algorithm of the blocks and the full file checksum
+    checksum_type: ChecksumType,
+
     /// Compression to use for data blocks
     index_block_compression: CompressionType,
 
@@ -86,18 +107,49 @@ pub struct Writer {
 
     bloom_policy: BloomConstructionPolicy,
 
+    /// Prefix extractor, if the filter should contain key prefixes
+    prefix_extractor: Option<Arc<dyn PrefixExtractor>>,
+
+    /// Hash of the previously registered prefix, so every prefix is only registered once
+    last_prefix_hash: Option<u64>,
+
     /// Tracks the previously written item to detect weak tombstone/value pairs
     previous_item: Option<(UserKey, ValueType)>,
 
     linked_blob_files: Vec<LinkedFile>,
 
+    /// Range tombstones to store in the table
+    range_tombstones: Vec<RangeTombstone>,
+
+    /// Maximum size of the compression dictionary (0 = disabled)
+    #[cfg(feature = "zstd")]
+    compression_dictionary_size: u32,
+
+    /// Data blocks that are buffered as samples for the compression dictionary
+    #[cfg(feature = "zstd")]
+    buffered_blocks: Vec<BufferedBlock>,
+
+    #[cfg(feature = "zstd")]
+    buffered_bytes: usize,
+
+    /// Set once the compression dictionary was trained (or training failed)
+    #[cfg(feature = "zstd")]
+    is_dictionary_trained: bool,
+
+    /// Trained compression dictionary, and a compressor that has it loaded
+    #[cfg(feature = "zstd")]
+    compression_dictionary: Option<(
+        crate::CompressionDictionary,
+        zstd::bulk::Compressor<'static>,
+    )>,
+
     initial_level: u8,
 }
 
 impl Writer {
     pub fn new(path: PathBuf, table_id: TableId, initial_level: u8) -> crate::Result<Self> {
         let writer = BufWriter::with_capacity(u16::MAX.into(), File::create_new(&path)?);
-        let writer = ChecksummedWriter::new(writer);
+        let writer = ChecksummedWriter::new(writer, ChecksumType::Xxh3);
         let mut writer = sfa::Writer::from_writer(writer);
         writer.start("data")?;
 
@@ -118,6 +170,8 @@ impl Writer {
             data_block_size: 4_096,
 
             data_block_compression: CompressionType::None,
+            data_block_compression_ratio: 1.0,
+            checksum_type: ChecksumType::Xxh3,
             index_block_compression: CompressionType::None,
 
             path: std::path::absolute(path)?,
@@ -137,9 +191,29 @@ impl Writer {
 
             bloom_policy: BloomConstructionPolicy::default(),
 
+            prefix_extractor: None,
+            last_prefix_hash: None,
+
             previous_item: None,
 
             linked_blob_files: Vec::new(),
+
+            range_tombstones: Vec::new(),
+
+            #[cfg(feature = "zstd")]
+            compression_dictionary_size: 0,
+
+            #[cfg(feature = "zstd")]
+            buffered_blocks: Vec::new(),
+
+            #[cfg(feature = "zstd")]
+            buffered_bytes: 0,
+
+            #[cfg(feature = "zstd")]
+            is_dictionary_trained: false,
+
+            #[cfg(feature = "zstd")]
+            compression_dictionary: None,
         })
     }
 
@@ -158,17 +232,26 @@ impl Writer {
         });
     }
 
+    /// Adds a range tombstone to the table.
+    ///
+    /// The table's key range is extended to cover the tombstone.
+    pub fn write_range_tombstone(&mut self, tombstone: RangeTombstone) {
+        self.range_tombstones.push(tombstone);
+    }
+
     #[must_use]
     pub fn use_partitioned_filter(mut self) -> Self {
         self.filter_writer = Box::new(filter::PartitionedFilterWriter::new(self.bloom_policy))
-            .use_tli_compression(self.index_block_compression);
+            .use_tli_compression(self.index_block_compression)
+            .use_checksum_type(self.checksum_type);
         self
     }
 
     #[must_use]
     pub fn use_partitioned_index(mut self) -> Self {
         self.index_writer = Box::new(index::PartitionedIndexWriter::new())
-            .use_compression(self.index_block_compression);
+            .use_compression(self.index_block_compression)
+            .use_checksum_type(self.checksum_type);
         self
     }
 
@@ -218,6 +301,30 @@ impl Writer {
         self
     }
 
+    /// Sets the minimum compression ratio (uncompressed size / compressed size)
+    /// a data block needs to reach to be stored compressed.
+    ///
+    /// Data blocks that do not reach the ratio are stored uncompressed,
+    /// so they do not need to be decompressed when reading.
+    #[must_use]
+    pub fn use_data_block_compression_ratio(mut self, ratio: f32) -> Self {
+        self.data_block_compression_ratio = ratio;
+        self
+    }
+
+    /// Sets the maximum size of the compression dictionary that is trained for the data blocks.
+    ///
+    /// The first data blocks are buffered as training samples, after which
+    /// all data blocks of the table are compressed using the dictionary.
+    ///
+    /// Only used if data blocks are compressed using Zstd, 0 disables dictionaries.
+    #[cfg(feature = "zstd")]
+    #[must_use]
+    pub fn use_compression_dictionary_size(mut self, size: u32) -> Self {
+        self.compression_dictionary_size = size;
+        self
+    }
+
     #[must_use]
     pub fn use_index_block_compression(mut self, compression: CompressionType) -> Self {
         self.index_block_compression = compression;
@@ -226,6 +333,18 @@ impl Writer {
         self
     }
 
+    /// Sets the checksum algorithm of the blocks and the full file checksum.
+    ///
+    /// Needs to be set before any item is written.
+    #[must_use]
+    pub fn use_checksum_type(mut self, checksum_type: ChecksumType) -> Self {
+        self.checksum_type = checksum_type;
+        self.file_writer.get_mut().set_checksum_type(checksum_type);
+        self.index_writer = self.index_writer.use_checksum_type(checksum_type);
+        self.filter_writer = self.filter_writer.use_checksum_type(checksum_type);
+        self
+    }
+
     #[must_use]
     pub fn use_bloom_policy(mut self, bloom_policy: BloomConstructionPolicy) -> Self {
         self.bloom_policy = bloom_policy;
@@ -233,6 +352,35 @@ impl Writer {
         self
     }
 
+    /// Sets the prefix extractor, so the prefixes of keys are inserted into the filter.
+    #[must_use]
+    pub fn use_prefix_extractor(mut self, extractor: Option<Arc<dyn PrefixExtractor>>) -> Self {
+        self.prefix_extractor = extractor;
+        self
+    }
+
+    /// Registers the prefix of a key in the filter, if it differs from the previous one.
+    fn register_prefix(&mut self, key: &[u8]) -> crate::Result<()> {
+        use crate::table::filter::standard_bloom::Builder;
+
+        let Some(prefix) = self
+            .prefix_extractor
+            .as_ref()
+            .and_then(|extractor| extractor.extract(key))
+        else {
+            return Ok(());
+        };
+
+        let hash = Builder::get_hash(prefix);
+
+        if self.last_prefix_hash != Some(hash) {
+            self.last_prefix_hash = Some(hash);
+            self.filter_writer.register_prefix(prefix)?;
+        }
+
+        Ok(())
+    }
+
     /// Writes an item.
     ///
     /// # Note
@@ -274,6 +422,7 @@ impl Writer {
 
             if self.bloom_policy.is_active() {
                 self.filter_writer.register_key(&user_key)?;
+                self.register_prefix(&user_key)?;
             }
         }
 
@@ -295,6 +444,20 @@ impl Writer {
         Ok(())
     }
 
+    /// Returns the size of the table file so far.
+    ///
+    /// Data blocks that are held back until the compression dictionary is trained
+    /// are counted with their uncompressed size.
+    pub(crate) fn file_size(&self) -> u64 {
+        #[cfg(feature = "zstd")]
+        let buffered_bytes = self.buffered_bytes as u64;
+
+        #[cfg(not(feature = "zstd"))]
+        let buffered_bytes = 0;
+
+        *self.meta.file_pos + buffered_bytes
+    }
+
     /// Writes a compressed block to disk.
     ///
     /// This is triggered when a `Writer::write` causes the buffer to grow to the configured `block_size`.
@@ -305,6 +468,10 @@ impl Writer {
             return Ok(());
         };
 
+        let last_key = last.key.user_key.clone();
+        let last_seqno = last.key.seqno;
+        let item_count = self.chunk.len();
+
         self.block_buffer.clear();
 
         DataBlock::encode_into(
@@ -314,14 +481,83 @@ impl Writer {
             self.data_block_hash_ratio,
         )?;
 
-        let header = Block::write_into(
+        #[cfg(feature = "zstd")]
+        if self.is_buffering_blocks() {
+            self.buffer_block(last_key, last_seqno, item_count)?;
+        } else {
+            let data = std::mem::take(&mut self.block_buffer);
+            self.write_data_block(&data, last_key, last_seqno, item_count)?;
+            self.block_buffer = data;
+        }
+
+        #[cfg(not(feature = "zstd"))]
+        {
+            let data = std::mem::take(&mut self.block_buffer);
+            self.write_data_block(&data, last_key, last_seqno, item_count)?;
+            self.block_buffer = data;
+        }
+
+        // Set last key
+        self.meta.last_key = Some(
+            // NOTE: We are allowed to remove the last item
+            // to get ownership of it, because the chunk is cleared after
+            // this anyway
+            #[expect(clippy::expect_used, reason = "chunk is not empty")]
+            self.chunk
+                .pop()
+                .expect("chunk should not be empty")
+                .key
+                .user_key,
+        );
+
+        // IMPORTANT: Clear chunk after everything else
+        self.chunk.clear();
+        self.chunk_size = 0;
+
+        Ok(())
+    }
+
+    /// Compresses a serialized data block and writes it to disk.
+    fn write_data_block(
+        &mut self,
+        data: &[u8],
+        last_key: UserKey,
+        last_seqno: SeqNo,
+        item_count: usize,
+    ) -> crate::Result<()> {
+        #[cfg(feature = "zstd")]
+        let header = if let Some((_, compressor)) = &mut self.compression_dictionary {
+            Block::write_into_with_compressor(
+                &mut self.file_writer,
+                data,
+                super::block::BlockType::Data,
+                compressor,
+                self.checksum_type,
+                self.data_block_compression_ratio,
+            )?
+        } else {
+            Block::write_into_with_min_compression_ratio(
+                &mut self.file_writer,
+                data,
+                super::block::BlockType::Data,
+                self.data_block_compression,
+                self.checksum_type,
+                self.data_block_compression_ratio,
+            )?
+        };
+
+        #[cfg(not(feature = "zstd"))]
+        let header = Block::write_into_with_min_compression_ratio(
             &mut self.file_writer,
-            &self.block_buffer,
+            data,
             super::block::BlockType::Data,
             self.data_block_compression,
+            self.checksum_type,
+            self.data_block_compression_ratio,
         )?;
 
         self.meta.uncompressed_size += u64::from(header.uncompressed_length);
+        self.meta.compressed_size += u64::from(header.data_length);
 
         #[expect(
             clippy::cast_possible_truncation,
@@ -331,36 +567,147 @@ impl Writer {
 
         self.index_writer
             .register_data_block(KeyedBlockHandle::new(
-                last.key.user_key.clone(),
-                last.key.seqno,
+                last_key,
+                last_seqno,
                 BlockHandle::new(self.meta.file_pos, bytes_written),
             ))?;
 
         // Adjust metadata
         self.meta.file_pos += u64::from(bytes_written);
-        self.meta.item_count += self.chunk.len();
+        self.meta.item_count += item_count;
         self.meta.data_block_count += 1;
 
         // Back link stuff
         self.prev_pos.0 = self.prev_pos.1;
         self.prev_pos.1 += u64::from(bytes_written);
 
-        // Set last key
-        self.meta.last_key = Some(
-            // NOTE: We are allowed to remove the last item
-            // to get ownership of it, because the chunk is cleared after
-            // this anyway
-            #[expect(clippy::expect_used, reason = "chunk is not empty")]
-            self.chunk
-                .pop()
-                .expect("chunk should not be empty")
-                .key
-                .user_key,
-        );
+        Ok(())
+    }
 
-        // IMPORTANT: Clear chunk after everything else
-        self.chunk.clear();
-        self.chunk_size = 0;
+    #[cfg(feature = "zstd")]
+    fn is_buffering_blocks(&self) -> bool {
+        self.compression_dictionary_size > 0
+            && !self.is_dictionary_trained
+            && matches!(self.data_block_compression, CompressionType::Zstd(_))
+    }
+
+    /// Holds back the serialized data block as a training sample,
+    /// and trains the compression dictionary once there are enough samples.
+    #[cfg(feature = "zstd")]
+    fn buffer_block(
+        &mut self,
+        last_key: UserKey,
+        last_seqno: SeqNo,
+        item_count: usize,
+    ) -> crate::Result<()> {
+        self.buffered_bytes += self.block_buffer.len();
+
+        self.buffered_blocks.push(BufferedBlock {
+            data: self.block_buffer.clone(),
+            last_key,
+            seqno: last_seqno,
+            item_count,
+        });
+
+        if self.buffered_bytes
+            >= self.compression_dictionary_size as usize * DICTIONARY_SAMPLE_RATIO
+        {
+            self.train_compression_dictionary()?;
+        }
+
+        Ok(())
+    }
+
+    /// Trains the compression dictionary on the buffered data blocks,
+    /// and writes them to disk.
+    ///
+    /// If there are not enough samples to train on, the data blocks
+    /// are compressed without a dictionary instead.
+    #[cfg(feature = "zstd")]
+    fn train_compression_dictionary(&mut self) -> crate::Result<()> {
+        self.is_dictionary_trained = true;
+
+        let blocks = std::mem::take(&mut self.buffered_blocks);
+        self.buffered_bytes = 0;
+
+        if let CompressionType::Zstd(level) = self.data_block_compression {
+            let samples = blocks.iter().map(|block| &block.data).collect::<Vec<_>>();
+
+            match crate::CompressionDictionary::train(
+                &samples,
+                self.compression_dictionary_size as usize,
+            ) {
+                Ok(dictionary) => {
+                    log::debug!(
+                        "Trained compression dictionary of {}B for table #{} on {} data blocks",
+                        dictionary.size(),
+                        self.table_id,
+                        blocks.len(),
+                    );
+
+                    let compressor =
+                        zstd::bulk::Compressor::with_dictionary(level, dictionary.as_bytes())?;
+
+                    self.compression_dictionary = Some((dictionary, compressor));
+                }
+                Err(e) => {
+                    log::debug!(
+                        "Could not train compression dictionary for table #{}, compressing without dictionary: {e:?}",
+                        self.table_id,
+                    );
+                }
+            }
+        }
+
+        for block in blocks {
+            self.write_data_block(&block.data, block.last_key, block.seqno, block.item_count)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the (fragmented) range tombstones into their own region,
+    /// extending the key range and sequence numbers of the table to cover them.
+    fn write_range_tombstones(&mut self, range_tombstones: &[RangeTombstone]) -> crate::Result<()> {
+        for rt in range_tombstones {
+            if self
+                .meta
+                .first_key
+                .as_ref()
+                .is_none_or(|key| rt.start < key)
+            {
+                self.meta.first_key = Some(rt.start.clone());
+            }
+
+            let max_key = rt.max_key();
+
+            if self.meta.last_key.as_ref().is_none_or(|key| max_key > key) {
+                self.meta.last_key = Some(max_key);
+            }
+
+            self.meta.lowest_seqno = self.meta.lowest_seqno.min(rt.seqno);
+            self.meta.highest_seqno = self.meta.highest_seqno.max(rt.seqno);
+        }
+
+        let items = range_tombstones
+            .iter()
+            .map(RangeTombstone::to_internal_value)
+            .collect::<Vec<_>>();
+
+        self.file_writer.start("range_tombstones")?;
+
+        self.block_buffer.clear();
+        DataBlock::encode_into(&mut self.block_buffer, &items, 1, 0.0)?;
+
+        Block::write_into(
+            &mut self.file_writer,
+            &self.block_buffer,
+            super::block::BlockType::RangeTombstone,
+            self.data_block_compression,
+            self.checksum_type,
+        )?;
+
+        log::trace!("Written {} range tombstone fragments", items.len());
 
         Ok(())
     }
@@ -371,14 +718,34 @@ impl Writer {
     pub fn finish(mut self) -> crate::Result<Option<(TableId, Checksum)>> {
         use std::io::Write;
 
+        let range_tombstones = fragment(std::mem::take(&mut self.range_tombstones));
+
+        // NOTE: A table needs at least one item, so a table that only consists
+        // of range tombstones gets a point tombstone at the start of the first one,
+        // which is deleted by the range tombstone anyway
+        if self.chunk.is_empty() && self.meta.item_count == 0 {
+            if let Some(rt) = range_tombstones.first() {
+                self.write(InternalValue::new_tombstone(rt.start.clone(), rt.seqno))?;
+            }
+        }
+
         self.spill_block()?;
 
+        #[cfg(feature = "zstd")]
+        if !self.buffered_blocks.is_empty() {
+            self.train_compression_dictionary()?;
+        }
+
         // No items written! Just delete table file and return nothing
         if self.meta.item_count == 0 {
             std::fs::remove_file(&self.path)?;
             return Ok(None);
         }
 
+        if !range_tombstones.is_empty() {
+            self.write_range_tombstones(&range_tombstones)?;
+        }
+
         // Write index
         log::trace!("Finishing index writer");
         let index_block_count = self.index_writer.finish(&mut self.file_writer)?;
@@ -407,6 +774,19 @@ impl Writer {
             }
         }
 
+        #[cfg(feature = "zstd")]
+        if let Some((dictionary, _)) = &self.compression_dictionary {
+            self.file_writer.start("compression_dictionary")?;
+
+            Block::write_into(
+                &mut self.file_writer,
+                dictionary.as_bytes(),
+                super::block::BlockType::CompressionDictionary,
+                CompressionType::None,
+                self.checksum_type,
+            )?;
+        }
+
         self.file_writer.start("table_version")?;
         self.file_writer.write_all(&[0x3])?;
 
@@ -431,7 +811,11 @@ impl Writer {
                     "block_count#index",
                     &(index_block_count as u64).to_le_bytes(),
                 ),
-                meta("checksum_type", &[u8::from(ChecksumType::Xxh3)]),
+                meta("checksum_type", &[u8::from(self.checksum_type)]),
+                meta(
+                    "compressed_size#data",
+                    &self.meta.compressed_size.to_le_bytes(),
+                ),
                 meta(
                     "compression#data",
                     &self.data_block_compression.encode_into_vec(),
@@ -464,6 +848,14 @@ impl Writer {
                     self.meta.first_key.as_ref().expect("should exist"),
                 ),
                 meta("key_count", &(self.meta.key_count as u64).to_le_bytes()),
+                meta(
+                    "prefix_extractor",
+                    self.prefix_extractor
+                        .as_ref()
+                        .map(|extractor| extractor.name())
+                        .unwrap_or_default()
+                        .as_bytes(),
+                ),
                 meta("prefix_truncation#data", &[1]), // NOTE: currently prefix truncation can not be disabled
                 meta("prefix_truncation#index", &[1]), // NOTE: currently prefix truncation can not be disabled
                 meta(
@@ -510,6 +902,7 @@ impl Writer {
                 &self.block_buffer,
                 crate::table::block::BlockType::Meta,
                 CompressionType::None,
+                self.checksum_type,
             )?;
         };
 
@@ -33,3 +33,18 @@ pub fn set_unix_timestamp_for_test(value: Option<std::time::Duration>) {
     let cell = NOW_OVERRIDE.get_or_init(|| Mutex::new(None));
     *cell.lock().expect("lock is poisoned") = value;
 }
+
+#[cfg(test)]
+static CLOCK_LOCK: Mutex<()> = Mutex::new(());
+
+/// Serializes tests that override the clock.
+///
+/// The override is global, so it also applies to compaction threads,
+/// but tests running in parallel would see each other's time.
+/// The guard should be held for the whole test.
+#[cfg(test)]
+pub fn lock_clock_for_test() -> std::sync::MutexGuard<'static, ()> {
+    CLOCK_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
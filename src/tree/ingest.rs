@@ -55,7 +55,7 @@ impl<'a> Ingestion<'a> {
         .use_bloom_policy({
             if tree.config.expect_point_read_hits {
                 crate::config::BloomConstructionPolicy::BitsPerKey(0.0)
-            } else if let FilterPolicyEntry::Bloom(p) =
+            } else if let FilterPolicyEntry::Bloom(p) | FilterPolicyEntry::PrefixBloom(p) =
                 tree.config.filter_policy.get(INITIAL_CANONICAL_LEVEL)
             {
                 p
@@ -63,6 +63,10 @@ impl<'a> Ingestion<'a> {
                 crate::config::BloomConstructionPolicy::BitsPerKey(0.0)
             }
         })
+        .use_prefix_extractor(
+            tree.config
+                .prefix_extractor_for_level(INITIAL_CANONICAL_LEVEL),
+        )
         .use_data_block_size(
             tree.config
                 .data_block_size_policy
@@ -78,6 +82,12 @@ impl<'a> Ingestion<'a> {
                 .data_block_compression_policy
                 .get(INITIAL_CANONICAL_LEVEL),
         )
+        .use_data_block_compression_ratio(
+            tree.config
+                .data_block_compression_ratio_policy
+                .get(INITIAL_CANONICAL_LEVEL),
+        )
+        .use_checksum_type(tree.config.checksum_type)
         .use_index_block_compression(
             tree.config
                 .index_block_compression_policy
@@ -101,6 +111,15 @@ impl<'a> Ingestion<'a> {
             writer = writer.use_partitioned_filter();
         }
 
+        #[cfg(feature = "zstd")]
+        {
+            writer = writer.use_compression_dictionary_size(
+                tree.config
+                    .compression_dictionary_size_policy
+                    .get(INITIAL_CANONICAL_LEVEL),
+            );
+        }
+
         Ok(Self {
             folder,
             tree,
@@ -230,7 +249,7 @@ impl<'a> Ingestion<'a> {
     /// Will return `Err` if an IO error occurs.
     #[allow(clippy::significant_drop_tightening)]
     pub fn finish(self) -> crate::Result<()> {
-        use crate::{AbstractTree, Table};
+        use crate::{table::Pinning, AbstractTree, Table};
 
         if self.last_key.is_none() {
             log::trace!("No data written to Ingestion, returning early");
@@ -298,8 +317,7 @@ impl<'a> Ingestion<'a> {
                     self.tree.id,
                     self.tree.config.cache.clone(),
                     self.tree.config.descriptor_table.clone(),
-                    false,
-                    false,
+                    Pinning::default(),
                     #[cfg(feature = "metrics")]
                     self.tree.metrics.clone(),
                 )
@@ -315,6 +333,7 @@ impl<'a> Ingestion<'a> {
         // already assigned to the recovered tables.
         version_lock.upgrade_version_with_seqno(
             &self.tree.config.path,
+            self.tree.config.checksum_type,
             |current| {
                 let mut copy = current.clone();
                 copy.version = copy.version.with_new_l0_run(&created_tables, None, None);
@@ -5,6 +5,8 @@
 use crate::{
     compaction::state::CompactionState,
     config::Config,
+    file::JOURNALS_FOLDER,
+    journal::Journal,
     merge_operator::MergeOperator,
     stop_signal::StopSignal,
     version::{persist_version, SuperVersions, Version},
@@ -70,6 +72,10 @@ pub struct TreeInner {
 
     /// Serializes flush operations.
     pub(crate) flush_lock: Mutex<()>,
+
+    /// Write-ahead journal of the memtables, if enabled
+    pub(crate) journal: Option<Journal>,
+
     #[doc(hidden)]
     #[cfg(feature = "metrics")]
     pub metrics: Arc<Metrics>,
@@ -85,10 +91,17 @@ impl TreeInner {
                 crate::TreeType::Standard
             },
         );
-        persist_version(&config.path, &version)?;
+        persist_version(&config.path, &version, config.checksum_type)?;
 
         let merge_op = config.merge_operator.clone();
 
+        let journal = config
+            .journal_sync_policy
+            .map(|sync_policy| {
+                Journal::create_new(config.path.join(JOURNALS_FOLDER), sync_policy, 0)
+            })
+            .transpose()?;
+
         Ok(Self {
             id: get_next_tree_id(),
             memtable_id_counter: SequenceNumberCounter::new(1),
@@ -100,6 +113,7 @@ impl TreeInner {
             stop_signal: StopSignal::default(),
             major_compaction_lock: RwLock::default(),
             flush_lock: Mutex::default(),
+            journal,
             compaction_state: Arc::new(Mutex::new(CompactionState::default())),
 
             #[cfg(feature = "metrics")]
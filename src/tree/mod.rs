@@ -9,16 +9,18 @@ pub mod sealed;
 use crate::{
     compaction::{drop_range::OwnedBounds, state::CompactionState, CompactionStrategy},
     config::Config,
-    file::CURRENT_VERSION_FILE,
+    file::{CURRENT_VERSION_FILE, JOURNALS_FOLDER},
     format_version::FormatVersion,
     iter_guard::{IterGuard, IterGuardImpl},
+    journal::{Journal, Record},
     manifest::Manifest,
     memtable::Memtable,
     merge_operator::{MergeOperator, MergeResult},
+    prefix::PrefixExtractor,
     slice::Slice,
-    table::Table,
+    table::{Pinning, Table},
     value::InternalValue,
-    version::{recovery::recover, SuperVersion, SuperVersions, Version},
+    version::{recovery::recover, Level, SuperVersion, SuperVersions, Version},
     vlog::BlobFile,
     AbstractTree, Checksum, KvPair, SeqNo, SequenceNumberCounter, TableId, UserKey, UserValue,
     ValueType,
@@ -127,7 +129,6 @@ impl AbstractTree for Tree {
                     return Self::resolve_merge_operands(
                         &super_version,
                         key,
-                        seqno,
                         item.clone(),
                         merge_op.as_ref(),
                     );
@@ -234,21 +235,34 @@ impl AbstractTree for Tree {
         self.inner_compact(strategy, 0)
     }
 
+    #[expect(
+        clippy::significant_drop_tightening,
+        reason = "journal needs to be reset under the version lock"
+    )]
     fn clear(&self) -> crate::Result<()> {
         let mut versions = self.get_version_history_lock();
 
+        let memtable_id = self.memtable_id_counter.next();
+
         versions.upgrade_version(
             &self.config.path,
+            self.config.checksum_type,
             |v| {
                 let mut copy = v.clone();
-                copy.active_memtable = Arc::new(Memtable::new(self.memtable_id_counter.next()));
+                copy.active_memtable = Arc::new(Memtable::new(memtable_id));
                 copy.sealed_memtables = Arc::default();
                 copy.version = Version::new(v.version.id() + 1, self.tree_type());
                 Ok(copy)
             },
             &self.config.seqno,
             &self.config.visible_seqno,
-        )
+        )?;
+
+        if let Some(journal) = &self.journal {
+            journal.reset(memtable_id);
+        }
+
+        Ok(())
     }
 
     #[doc(hidden)]
@@ -267,6 +281,58 @@ impl AbstractTree for Tree {
         self.inner_compact(strategy, seqno_threshold)
     }
 
+    fn compact_range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+        target_size: u64,
+        seqno_threshold: SeqNo,
+    ) -> crate::Result<()> {
+        use crate::compaction::compact_range::{overlapping_tables, Strategy};
+
+        let (bounds, is_empty) = Self::range_bounds_to_owned_bounds(&range);
+
+        if is_empty {
+            return Ok(());
+        }
+
+        // IMPORTANT: Write lock so we can be the only compaction going on
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let _lock = self
+            .0
+            .major_compaction_lock
+            .write()
+            .expect("lock is poisoned");
+
+        log::info!("Starting compact_range compaction");
+
+        let last_level_idx = self.config.level_count - 1;
+        let mut merged_into_last_level = false;
+
+        for level_idx in 0..=last_level_idx {
+            let has_overlap = self
+                .current_version()
+                .level(usize::from(level_idx))
+                .is_some_and(|level| overlapping_tables(level, &bounds).next().is_some());
+
+            if !has_overlap {
+                continue;
+            }
+
+            // NOTE: The last level was already rewritten by merging the level above into it
+            if level_idx == last_level_idx && merged_into_last_level {
+                break;
+            }
+
+            let strategy = Arc::new(Strategy::new(bounds.clone(), level_idx, target_size));
+
+            self.inner_compact(strategy, seqno_threshold)?;
+
+            merged_into_last_level = level_idx + 1 == last_level_idx;
+        }
+
+        Ok(())
+    }
+
     fn l0_run_count(&self) -> usize {
         self.current_version()
             .level(0)
@@ -301,6 +367,13 @@ impl AbstractTree for Tree {
             .sum()
     }
 
+    fn pinned_compression_dictionary_size(&self) -> usize {
+        self.current_version()
+            .iter_tables()
+            .map(Table::pinned_compression_dictionary_size)
+            .sum()
+    }
+
     fn sealed_memtable_count(&self) -> usize {
         #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
         self.version_history
@@ -314,6 +387,7 @@ impl AbstractTree for Tree {
     fn flush_to_tables(
         &self,
         stream: impl Iterator<Item = crate::Result<InternalValue>>,
+        range_tombstones: Vec<crate::RangeTombstone>,
     ) -> crate::Result<Option<(Vec<Table>, Option<Vec<BlobFile>>)>> {
         use crate::{file::TABLES_FOLDER, table::multi_writer::MultiWriter};
         use std::time::Instant;
@@ -328,6 +402,7 @@ impl AbstractTree for Tree {
         let index_block_restart_interval = self.config.index_block_restart_interval_policy.get(0);
 
         let data_block_compression = self.config.data_block_compression_policy.get(0);
+        let data_block_compression_ratio = self.config.data_block_compression_ratio_policy.get(0);
         let index_block_compression = self.config.index_block_compression_policy.get(0);
 
         let data_block_hash_ratio = self.config.data_block_hash_ratio_policy.get(0);
@@ -349,18 +424,21 @@ impl AbstractTree for Tree {
         .use_data_block_restart_interval(data_block_restart_interval)
         .use_index_block_restart_interval(index_block_restart_interval)
         .use_data_block_compression(data_block_compression)
+        .use_data_block_compression_ratio(data_block_compression_ratio)
+        .use_checksum_type(self.config.checksum_type)
         .use_index_block_compression(index_block_compression)
         .use_data_block_size(data_block_size)
         .use_data_block_hash_ratio(data_block_hash_ratio)
         .use_bloom_policy({
-            use crate::config::FilterPolicyEntry::{Bloom, None};
+            use crate::config::FilterPolicyEntry::{Bloom, None, PrefixBloom};
             use crate::table::filter::BloomConstructionPolicy;
 
             match self.config.filter_policy.get(0) {
-                Bloom(policy) => policy,
+                Bloom(policy) | PrefixBloom(policy) => policy,
                 None => BloomConstructionPolicy::BitsPerKey(0.0),
             }
-        });
+        })
+        .use_prefix_extractor(self.config.prefix_extractor_for_level(0));
 
         if index_partitioning {
             table_writer = table_writer.use_partitioned_index();
@@ -369,6 +447,15 @@ impl AbstractTree for Tree {
             table_writer = table_writer.use_partitioned_filter();
         }
 
+        #[cfg(feature = "zstd")]
+        {
+            table_writer = table_writer.use_compression_dictionary_size(
+                self.config.compression_dictionary_size_policy.get(0),
+            );
+        }
+
+        table_writer = table_writer.use_range_tombstones(range_tombstones);
+
         for item in stream {
             table_writer.write(item?)?;
         }
@@ -377,8 +464,7 @@ impl AbstractTree for Tree {
 
         log::debug!("Flushed memtable(s) in {:?}", start.elapsed());
 
-        let pin_filter = self.config.filter_block_pinning_policy.get(0);
-        let pin_index = self.config.index_block_pinning_policy.get(0);
+        let pinning = Pinning::from_config(&self.config, 0);
 
         // Load tables
         let tables = result
@@ -391,8 +477,7 @@ impl AbstractTree for Tree {
                     self.id,
                     self.config.cache.clone(),
                     self.config.descriptor_table.clone(),
-                    pin_filter,
-                    pin_index,
+                    pinning,
                     #[cfg(feature = "metrics")]
                     self.metrics.clone(),
                 )
@@ -424,6 +509,7 @@ impl AbstractTree for Tree {
 
         version_lock.upgrade_version(
             &self.config.path,
+            self.config.checksum_type,
             |current| {
                 let mut copy = current.clone();
 
@@ -448,6 +534,11 @@ impl AbstractTree for Tree {
             log::warn!("Version GC failed: {e:?}");
         }
 
+        // NOTE: The flushed memtables are persisted now, so their journals are not needed anymore
+        if let Some(journal) = &self.journal {
+            journal.delete(sealed_memtables_to_delete);
+        }
+
         Ok(())
     }
 
@@ -462,13 +553,19 @@ impl AbstractTree for Tree {
             return;
         }
 
+        let memtable_id = self.memtable_id_counter.next();
+
         let mut copy = version_history_lock.latest_version();
-        copy.active_memtable = Arc::new(Memtable::new(self.memtable_id_counter.next()));
+        copy.active_memtable = Arc::new(Memtable::new(memtable_id));
         copy.sealed_memtables = Arc::new(SealedMemtables::default());
 
         // Rotate does not modify the memtable, so it cannot break snapshots
         copy.seqno = super_version.seqno;
 
+        if let Some(journal) = &self.journal {
+            journal.reset(memtable_id);
+        }
+
         version_history_lock.replace_latest_version(copy);
 
         log::trace!("cleared active memtable");
@@ -525,14 +622,21 @@ impl AbstractTree for Tree {
 
         let yanked_memtable = super_version.active_memtable;
 
+        let memtable_id = self.memtable_id_counter.next();
+
         let mut copy = version_history_lock.latest_version();
-        copy.active_memtable = Arc::new(Memtable::new(self.memtable_id_counter.next()));
+        copy.active_memtable = Arc::new(Memtable::new(memtable_id));
         copy.sealed_memtables =
             Arc::new(super_version.sealed_memtables.add(yanked_memtable.clone()));
 
         // Rotate does not modify the memtable so it cannot break snapshots
         copy.seqno = super_version.seqno;
 
+        // NOTE: Rotating under the write lock keeps journals and memtables in sync
+        if let Some(journal) = &self.journal {
+            journal.rotate(memtable_id);
+        }
+
         version_history_lock.replace_latest_version(copy);
 
         log::trace!(
@@ -543,6 +647,13 @@ impl AbstractTree for Tree {
         Some(yanked_memtable)
     }
 
+    fn sync_journal(&self) -> crate::Result<()> {
+        match &self.journal {
+            Some(journal) => journal.sync(),
+            None => Ok(()),
+        }
+    }
+
     fn table_count(&self) -> usize {
         self.current_version().table_count()
     }
@@ -551,6 +662,35 @@ impl AbstractTree for Tree {
         self.current_version().level(idx).map(|x| x.table_count())
     }
 
+    fn level_compression_ratio(&self, idx: usize) -> Option<f32> {
+        let version = self.current_version();
+        let level = version.level(idx)?;
+
+        // NOTE: Tables written before the compressed size was tracked are skipped
+        let (uncompressed, compressed) = level
+            .iter()
+            .flat_map(|run| run.iter())
+            .filter_map(|table| {
+                table
+                    .metadata
+                    .compressed_data_size
+                    .map(|compressed| (table.metadata.uncompressed_data_size, compressed))
+            })
+            .fold((0, 0), |(uncompressed, compressed), (u, c)| {
+                (uncompressed + u, compressed + c)
+            });
+
+        if compressed == 0 {
+            return None;
+        }
+
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "precision loss is acceptable for ratios"
+        )]
+        Some(uncompressed as f32 / compressed as f32)
+    }
+
     fn approximate_len(&self) -> usize {
         #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
         let super_version = self
@@ -618,6 +758,38 @@ impl AbstractTree for Tree {
             .map(|x| x.value))
     }
 
+    fn multi_get<K: AsRef<[u8]>>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        seqno: SeqNo,
+    ) -> crate::Result<Vec<Option<UserValue>>> {
+        let keys = keys.into_iter().collect::<Vec<_>>();
+        let keys = keys.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let super_version = self
+            .version_history
+            .read()
+            .expect("lock is poisoned")
+            .get_version_for_snapshot(seqno);
+
+        let entries = Self::multi_get_internal_entries_from_version(&super_version, &keys, seqno)?;
+
+        let merge_operator = self.get_merge_operator();
+
+        entries
+            .into_iter()
+            .zip(keys)
+            .map(|(entry, key)| match (entry, &merge_operator) {
+                (Some(item), Some(merge_op)) if item.key.value_type == ValueType::Merge => {
+                    Self::resolve_merge_operands(&super_version, key, item, merge_op.as_ref())
+                        .map(|x| x.map(|x| x.value))
+                }
+                (entry, _) => Ok(entry.map(|x| x.value)),
+            })
+            .collect()
+    }
+
     fn insert<K: Into<UserKey>, V: Into<UserValue>>(
         &self,
         key: K,
@@ -628,16 +800,100 @@ impl AbstractTree for Tree {
         self.append_entry(value)
     }
 
+    fn try_insert<K: Into<UserKey>, V: Into<UserValue>>(
+        &self,
+        key: K,
+        value: V,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)> {
+        let value = InternalValue::from_components(key, value, seqno, ValueType::Value);
+        self.write_entry(value, true)
+    }
+
     fn remove<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> (u64, u64) {
         let value = InternalValue::new_tombstone(key, seqno);
         self.append_entry(value)
     }
 
+    fn try_remove<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> crate::Result<(u64, u64)> {
+        let value = InternalValue::new_tombstone(key, seqno);
+        self.write_entry(value, true)
+    }
+
+    fn remove_range<K: Into<UserKey>>(
+        &self,
+        range: std::ops::Range<K>,
+        seqno: SeqNo,
+    ) -> (u64, u64) {
+        let tombstone = crate::RangeTombstone::new(range.start, range.end, seqno);
+
+        #[expect(clippy::expect_used, reason = "non-strict writes cannot fail")]
+        self.write_range_tombstone(tombstone, false)
+            .expect("should write range tombstone")
+    }
+
+    fn try_remove_range<K: Into<UserKey>>(
+        &self,
+        range: std::ops::Range<K>,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)> {
+        let tombstone = crate::RangeTombstone::new(range.start, range.end, seqno);
+        self.write_range_tombstone(tombstone, true)
+    }
+
+    #[expect(
+        clippy::significant_drop_tightening,
+        reason = "lock needs to be held until the whole batch is applied"
+    )]
+    fn apply_batch(&self, batch: crate::WriteBatch, seqno: SeqNo) -> crate::Result<(u64, u64)> {
+        // NOTE: Holding the read lock prevents the active memtable from being rotated,
+        // so the batch cannot be split across memtables
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let version_history_lock = self.version_history.read().expect("lock is poisoned");
+        let active_memtable = version_history_lock.latest_version().active_memtable;
+
+        let merge_operator = self.get_merge_operator();
+        let (items, range_tombstones) = batch.into_writes(seqno, merge_operator.as_deref())?;
+
+        let record = Record {
+            items: items.collect(),
+            range_tombstones: range_tombstones.collect(),
+        };
+
+        if let Some(journal) = &self.journal {
+            if !record.is_empty() {
+                journal.append(&record, true)?;
+            }
+        }
+
+        let mut batch_size = 0;
+        let mut memtable_size = active_memtable.size();
+
+        for item in record.items {
+            let (item_size, size) = active_memtable.insert(item);
+            batch_size += item_size;
+            memtable_size = size;
+        }
+
+        for tombstone in record.range_tombstones {
+            let (item_size, size) = active_memtable.insert_range_tombstone(tombstone);
+            batch_size += item_size;
+            memtable_size = size;
+        }
+
+        Ok((batch_size, memtable_size))
+    }
+
     fn remove_weak<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> (u64, u64) {
         let value = InternalValue::new_weak_tombstone(key, seqno);
         self.append_entry(value)
     }
 
+    fn try_remove_weak<K: Into<UserKey>>(&self, key: K, seqno: SeqNo) -> crate::Result<(u64, u64)> {
+        let value = InternalValue::new_weak_tombstone(key, seqno);
+        self.write_entry(value, true)
+    }
+
     fn merge<K: Into<UserKey>, V: Into<UserValue>>(
         &self,
         key: K,
@@ -648,11 +904,29 @@ impl AbstractTree for Tree {
         self.append_entry(value)
     }
 
+    fn try_merge<K: Into<UserKey>, V: Into<UserValue>>(
+        &self,
+        key: K,
+        operand: V,
+        seqno: SeqNo,
+    ) -> crate::Result<(u64, u64)> {
+        let value = InternalValue::from_components(key, operand, seqno, ValueType::Merge);
+        self.write_entry(value, true)
+    }
+
     fn set_merge_operator(&self, operator: Option<Arc<dyn MergeOperator>>) {
         #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
         let mut guard = self.merge_operator.write().expect("lock is poisoned");
         *guard = operator;
     }
+
+    fn get_merge_operator(&self) -> Option<Arc<dyn MergeOperator>> {
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        self.merge_operator
+            .read()
+            .expect("lock is poisoned")
+            .clone()
+    }
 }
 
 impl Tree {
@@ -662,6 +936,50 @@ impl Tree {
         range: &'a R,
         seqno: SeqNo,
         ephemeral: Option<(Arc<Memtable>, SeqNo)>,
+        merge_operator: Option<Arc<dyn MergeOperator>>,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<InternalValue>> + 'static {
+        Self::create_internal_range_with_filter(
+            version,
+            range,
+            seqno,
+            ephemeral,
+            merge_operator,
+            None,
+        )
+    }
+
+    #[doc(hidden)]
+    #[must_use]
+    pub fn create_internal_prefix(
+        version: SuperVersion,
+        prefix: &[u8],
+        seqno: SeqNo,
+        ephemeral: Option<(Arc<Memtable>, SeqNo)>,
+        merge_operator: Option<Arc<dyn MergeOperator>>,
+        prefix_extractor: Option<&dyn PrefixExtractor>,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<InternalValue>> + 'static {
+        use crate::range::{prefix_to_range, PrefixFilter};
+
+        let range = prefix_to_range(prefix);
+        let prefix_filter = prefix_extractor.and_then(|x| PrefixFilter::new(x, prefix));
+
+        Self::create_internal_range_with_filter(
+            version,
+            &range,
+            seqno,
+            ephemeral,
+            merge_operator,
+            prefix_filter,
+        )
+    }
+
+    fn create_internal_range_with_filter<'a, K: AsRef<[u8]> + 'a, R: RangeBounds<K> + 'a>(
+        version: SuperVersion,
+        range: &'a R,
+        seqno: SeqNo,
+        ephemeral: Option<(Arc<Memtable>, SeqNo)>,
+        merge_operator: Option<Arc<dyn MergeOperator>>,
+        prefix_filter: Option<crate::range::PrefixFilter>,
     ) -> impl DoubleEndedIterator<Item = crate::Result<InternalValue>> + 'static {
         use crate::range::{IterState, TreeIter};
         use std::ops::Bound::{self, Excluded, Included, Unbounded};
@@ -680,7 +998,14 @@ impl Tree {
 
         let bounds: (Bound<UserKey>, Bound<UserKey>) = (lo, hi);
 
-        let iter_state = { IterState { version, ephemeral } };
+        let iter_state = {
+            IterState {
+                version,
+                ephemeral,
+                merge_operator,
+                prefix_filter,
+            }
+        };
 
         TreeIter::create_range(iter_state, bounds, seqno)
     }
@@ -689,6 +1014,150 @@ impl Tree {
         super_version: &SuperVersion,
         key: &[u8],
         seqno: SeqNo,
+    ) -> crate::Result<Option<InternalValue>> {
+        let Some(entry) = Self::get_latest_entry_from_version(super_version, key, seqno)? else {
+            return Ok(None);
+        };
+
+        // NOTE: The entry may be deleted by a newer range tombstone
+        if Self::get_range_tombstone_seqno(super_version, key, seqno)
+            .is_some_and(|rt_seqno| rt_seqno > entry.key.seqno)
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(entry))
+    }
+
+    /// Batched version of [`Tree::get_internal_entry_from_version`].
+    ///
+    /// The keys are looked up in sorted order, so lookups can be grouped per table.
+    /// The results are returned in the order of the given keys.
+    pub(crate) fn multi_get_internal_entries_from_version(
+        super_version: &SuperVersion,
+        keys: &[&[u8]],
+        seqno: SeqNo,
+    ) -> crate::Result<Vec<Option<InternalValue>>> {
+        let mut sorted = keys.iter().copied().enumerate().collect::<Vec<_>>();
+        sorted.sort_by_key(|(_, key)| *key);
+
+        let mut results = keys.iter().map(|_| None).collect::<Vec<_>>();
+
+        let mut pending = Vec::with_capacity(sorted.len());
+
+        for (idx, key) in sorted {
+            let entry = super_version.active_memtable.get(key, seqno).or_else(|| {
+                Self::get_internal_entry_from_sealed_memtables(super_version, key, seqno)
+            });
+
+            if let Some(entry) = entry {
+                if let Some(slot) = results.get_mut(idx) {
+                    *slot = ignore_tombstone_value(entry);
+                }
+            } else {
+                // NOTE: Create key hash for hash sharing
+                // https://fjall-rs.github.io/post/bloom-filter-hash-sharing/
+                let key_hash = crate::table::filter::standard_bloom::Builder::get_hash(key);
+                pending.push((idx, key, key_hash));
+            }
+        }
+
+        Self::multi_get_internal_entries_from_tables(
+            &super_version.version,
+            pending,
+            seqno,
+            &mut results,
+        )?;
+
+        // NOTE: Entries may be deleted by a newer range tombstone
+        for (slot, key) in results.iter_mut().zip(keys) {
+            if slot.as_ref().is_some_and(|entry| {
+                Self::get_range_tombstone_seqno(super_version, key, seqno)
+                    .is_some_and(|rt_seqno| rt_seqno > entry.key.seqno)
+            }) {
+                *slot = None;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Looks up the sorted `(index, key, key hash)` lookups in the tables, run by run.
+    fn multi_get_internal_entries_from_tables(
+        version: &Version,
+        mut pending: Vec<(usize, &[u8], u64)>,
+        seqno: SeqNo,
+        results: &mut [Option<InternalValue>],
+    ) -> crate::Result<()> {
+        for run in version.iter_levels().flat_map(|lvl| lvl.iter()) {
+            if pending.is_empty() {
+                break;
+            }
+
+            let lookups = pending
+                .into_iter()
+                .map(|lookup| (lookup, run.get_for_key(lookup.1)))
+                .collect::<Vec<_>>();
+
+            pending = Vec::with_capacity(lookups.len());
+
+            // NOTE: Keys are sorted and the tables of a run are disjoint,
+            // so all keys of a table are next to each other
+            for group in lookups.chunk_by(|(_, a), (_, b)| match (a, b) {
+                (Some(a), Some(b)) => a.id() == b.id(),
+                (None, None) => true,
+                _ => false,
+            }) {
+                let Some((_, Some(table))) = group.first() else {
+                    pending.extend(group.iter().map(|(lookup, _)| *lookup));
+                    continue;
+                };
+
+                let keys = group
+                    .iter()
+                    .map(|((_, key, key_hash), _)| (*key, *key_hash))
+                    .collect::<Vec<_>>();
+
+                for ((lookup, _), item) in group.iter().zip(table.multi_get(&keys, seqno)?) {
+                    match item {
+                        Some(item) => {
+                            if let Some(slot) = results.get_mut(lookup.0) {
+                                *slot = ignore_tombstone_value(item);
+                            }
+                        }
+                        None => pending.push(*lookup),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the highest sequence number of a range tombstone that deletes the key
+    /// and is visible at the given sequence number.
+    fn get_range_tombstone_seqno(
+        super_version: &SuperVersion,
+        key: &[u8],
+        seqno: SeqNo,
+    ) -> Option<SeqNo> {
+        let memtables = std::iter::once(&super_version.active_memtable)
+            .chain(super_version.sealed_memtables.iter())
+            .filter_map(|mt| mt.max_covering_seqno(key, seqno));
+
+        let tables = super_version
+            .version
+            .iter_levels()
+            .flat_map(Level::range_tombstone_tables)
+            .filter_map(|table| table.max_covering_seqno(key, seqno));
+
+        memtables.chain(tables).max()
+    }
+
+    fn get_latest_entry_from_version(
+        super_version: &SuperVersion,
+        key: &[u8],
+        seqno: SeqNo,
     ) -> crate::Result<Option<InternalValue>> {
         if let Some(entry) = super_version.active_memtable.get(key, seqno) {
             return Ok(ignore_tombstone_value(entry));
@@ -743,10 +1212,9 @@ impl Tree {
 
     /// Resolves merge operands for a key by collecting all merge operands
     /// and the base value (if any), then applying the merge operator.
-    fn resolve_merge_operands(
+    pub(crate) fn resolve_merge_operands(
         super_version: &SuperVersion,
         key: &[u8],
-        seqno: SeqNo,
         first_merge: InternalValue,
         merge_op: &dyn MergeOperator,
     ) -> crate::Result<Option<InternalValue>> {
@@ -757,50 +1225,62 @@ impl Tree {
         let mut operands = vec![first_merge.value];
         let mut base_value: Option<InternalValue> = None;
 
+        // NOTE: Versions that are deleted by a range tombstone end the merge chain
+        let rt_seqno =
+            Self::get_range_tombstone_seqno(super_version, key, head_seqno + 1).unwrap_or(0);
+
         // We need to continue scanning from the seqno before the first merge
         // to find older merge operands and the base value
-        let continue_seqno = head_seqno.saturating_sub(1);
-
-        // Continue looking in active memtable for older entries
-        Self::collect_merge_operands_from_memtable(
-            &super_version.active_memtable,
-            key,
-            continue_seqno,
-            &mut operands,
-            &mut base_value,
-        );
+        //
+        // NOTE: If the first merge has seqno 0, there cannot be any older versions
+        if let Some(continue_seqno) = head_seqno.checked_sub(1) {
+            // Continue looking in active memtable for older entries
+            let mut chain_ended = Self::collect_merge_operands_from_memtable(
+                &super_version.active_memtable,
+                key,
+                continue_seqno,
+                rt_seqno,
+                &mut operands,
+                &mut base_value,
+            );
 
-        // If we haven't found a base value yet, continue looking in sealed memtables
-        if base_value.is_none() || base_value.as_ref().is_some_and(|v| v.key.value_type == ValueType::Merge) {
-            for mt in super_version.sealed_memtables.iter().rev() {
-                Self::collect_merge_operands_from_memtable(
-                    mt,
+            // If we haven't found a base value yet, continue looking in sealed memtables
+            if !chain_ended {
+                for mt in super_version.sealed_memtables.iter().rev() {
+                    chain_ended = Self::collect_merge_operands_from_memtable(
+                        mt,
+                        key,
+                        continue_seqno,
+                        rt_seqno,
+                        &mut operands,
+                        &mut base_value,
+                    );
+
+                    if chain_ended {
+                        break;
+                    }
+                }
+            }
+
+            // If we still haven't found a base value, look in tables
+            if !chain_ended {
+                Self::collect_merge_operands_from_tables(
+                    &super_version.version,
                     key,
                     continue_seqno,
+                    rt_seqno,
                     &mut operands,
                     &mut base_value,
-                );
-                if base_value.as_ref().is_some_and(|v| v.key.value_type != ValueType::Merge) {
-                    break;
-                }
+                )?;
             }
         }
 
-        // If we still haven't found a base value, look in tables
-        if base_value.is_none() || base_value.as_ref().is_some_and(|v| v.key.value_type == ValueType::Merge) {
-            Self::collect_merge_operands_from_tables(
-                &super_version.version,
-                key,
-                continue_seqno,
-                &mut operands,
-                &mut base_value,
-            )?;
-        }
-
         // Extract the base value if it's not a merge operand
+        //
+        // NOTE: A tombstone ends the merge chain, so it acts as if there was no base value
         let base_value_ref = base_value
             .as_ref()
-            .filter(|v| v.key.value_type != ValueType::Merge)
+            .filter(|v| v.key.value_type != ValueType::Merge && !v.is_tombstone())
             .map(|v| &v.value);
 
         // Apply the merge operator (operands are in newest-to-oldest order, reverse for oldest-to-newest)
@@ -834,26 +1314,34 @@ impl Tree {
 
     /// Collects merge operands from a memtable.
     /// Updates `operands` with any Merge entries found, and `base_value` if a non-Merge entry is found.
+    ///
+    /// Entries below `rt_seqno` are deleted by a range tombstone.
+    ///
+    /// Returns `true` if the merge chain ended.
     fn collect_merge_operands_from_memtable(
         memtable: &Memtable,
         key: &[u8],
         seqno: SeqNo,
+        rt_seqno: SeqNo,
         operands: &mut Vec<UserValue>,
         base_value: &mut Option<InternalValue>,
-    ) {
+    ) -> bool {
         // The memtable stores entries by (key, seqno DESC)
         // We need to iterate through all entries for this key with seqno <= our seqno
         for entry in memtable.range_for_key(key, seqno) {
-            match entry.key.value_type {
-                ValueType::Merge => {
-                    operands.push(entry.value);
-                }
-                _ => {
-                    *base_value = Some(entry);
-                    return;
-                }
+            if entry.key.seqno < rt_seqno {
+                return true;
+            }
+
+            if entry.key.value_type == ValueType::Merge {
+                operands.push(entry.value);
+            } else {
+                *base_value = Some(entry);
+                return true;
             }
         }
+
+        false
     }
 
     /// Collects merge operands from disk tables.
@@ -861,6 +1349,7 @@ impl Tree {
         version: &Version,
         key: &[u8],
         seqno: SeqNo,
+        rt_seqno: SeqNo,
         operands: &mut Vec<UserValue>,
         base_value: &mut Option<InternalValue>,
     ) -> crate::Result<()> {
@@ -873,14 +1362,15 @@ impl Tree {
             let entries = table.range_for_key(key, seqno)?;
 
             for item in entries {
-                match item.key.value_type {
-                    ValueType::Merge => {
-                        operands.push(item.value);
-                    }
-                    _ => {
-                        *base_value = Some(item);
-                        return Ok(());
-                    }
+                if item.key.seqno < rt_seqno {
+                    return Ok(());
+                }
+
+                if item.key.value_type == ValueType::Merge {
+                    operands.push(item.value);
+                } else {
+                    *base_value = Some(item);
+                    return Ok(());
                 }
             }
         }
@@ -939,8 +1429,8 @@ impl Tree {
     /// occupied by an LSM-tree, including the previous configuration.
     /// If not, a new tree will be initialized with the given config.
     ///
-    /// After recovering a previous state, use [`Tree::set_active_memtable`]
-    /// to fill the memtable with data from a write-ahead log for full durability.
+    /// If the journal is enabled, it is replayed into the active memtable,
+    /// see [`Config::with_journal`].
     ///
     /// # Errors
     ///
@@ -1017,10 +1507,14 @@ impl Tree {
             .expect("lock is poisoned")
             .get_version_for_snapshot(seqno);
 
-        Self::create_internal_range(super_version, range, seqno, ephemeral).map(|item| match item {
-            Ok(kv) => Ok((kv.key.user_key, kv.value)),
-            Err(e) => Err(e),
-        })
+        let merge_operator = self.get_merge_operator();
+
+        Self::create_internal_range(super_version, range, seqno, ephemeral, merge_operator).map(
+            |item| match item {
+                Ok(kv) => Ok((kv.key.user_key, kv.value)),
+                Err(e) => Err(e),
+            },
+        )
     }
 
     #[doc(hidden)]
@@ -1030,25 +1524,107 @@ impl Tree {
         seqno: SeqNo,
         ephemeral: Option<(Arc<Memtable>, SeqNo)>,
     ) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> + 'static {
-        use crate::range::prefix_to_range;
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let super_version = self
+            .version_history
+            .read()
+            .expect("lock is poisoned")
+            .get_version_for_snapshot(seqno);
+
+        let merge_operator = self.get_merge_operator();
 
-        let range = prefix_to_range(prefix.as_ref());
-        self.create_range(&range, seqno, ephemeral)
+        Self::create_internal_prefix(
+            super_version,
+            prefix.as_ref(),
+            seqno,
+            ephemeral,
+            merge_operator,
+            self.config.prefix_extractor.as_deref(),
+        )
+        .map(|item| match item {
+            Ok(kv) => Ok((kv.key.user_key, kv.value)),
+            Err(e) => Err(e),
+        })
     }
 
     /// Adds an item to the active memtable.
     ///
     /// Returns the added item's size and new size of the memtable.
+    ///
+    /// If the item cannot be written to the journal, it is still applied;
+    /// the error is logged, and reported by [`AbstractTree::sync_journal`].
     #[doc(hidden)]
     #[must_use]
     pub fn append_entry(&self, value: InternalValue) -> (u64, u64) {
+        #[expect(clippy::expect_used, reason = "non-strict writes cannot fail")]
+        self.write_entry(value, false).expect("should write entry")
+    }
+
+    /// Writes an item to the journal, if enabled, and adds it to the active memtable.
+    ///
+    /// If `strict` is set, the item is not applied if it cannot be written to the journal.
+    fn write_entry(&self, value: InternalValue, strict: bool) -> crate::Result<(u64, u64)> {
         #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
-        self.version_history
-            .read()
-            .expect("lock is poisoned")
+        let version_history_lock = self.version_history.read().expect("lock is poisoned");
+
+        // NOTE: Holding the read lock prevents the journal from being rotated
+        // between writing the journal and the memtable
+        if let Some(journal) = &self.journal {
+            let result = journal.append(
+                &Record {
+                    items: vec![value.clone()],
+                    range_tombstones: vec![],
+                },
+                false,
+            );
+
+            if strict {
+                result?;
+            }
+        }
+
+        Ok(version_history_lock
             .latest_version()
             .active_memtable
-            .insert(value)
+            .insert(value))
+    }
+
+    /// Writes a range tombstone to the journal, if enabled, and adds it to the active memtable.
+    ///
+    /// If `strict` is set, the tombstone is not applied if it cannot be written to the journal.
+    #[expect(
+        clippy::significant_drop_tightening,
+        reason = "lock needs to be held until the journal and memtable are written"
+    )]
+    fn write_range_tombstone(
+        &self,
+        tombstone: crate::RangeTombstone,
+        strict: bool,
+    ) -> crate::Result<(u64, u64)> {
+        #[expect(clippy::expect_used, reason = "lock is expected to not be poisoned")]
+        let version_history_lock = self.version_history.read().expect("lock is poisoned");
+        let active_memtable = version_history_lock.latest_version().active_memtable;
+
+        // NOTE: An empty range does not delete anything
+        if tombstone.start >= tombstone.end {
+            return Ok((0, active_memtable.size()));
+        }
+
+        if let Some(journal) = &self.journal {
+            let result = journal.append(
+                &Record {
+                    items: vec![],
+                    range_tombstones: vec![tombstone.clone()],
+                },
+                false,
+            );
+
+            if strict {
+                result?;
+            }
+        }
+
+        Ok(active_memtable.insert_range_tombstone(tombstone))
     }
 
     /// Recovers previous state, by loading the level manifest, tables and blob files.
@@ -1119,17 +1695,38 @@ impl Tree {
 
         let merge_op = config.merge_operator.clone();
 
+        let mut version_history = SuperVersions::new(version);
+
+        let journal = if let Some(sync_policy) = config.journal_sync_policy {
+            let (journal, memtable) =
+                Journal::recover(config.path.join(JOURNALS_FOLDER), sync_policy)?;
+
+            let mut copy = version_history.latest_version();
+            copy.active_memtable = Arc::new(memtable);
+            version_history.replace_latest_version(copy);
+
+            Some(journal)
+        } else {
+            if config.path.join(JOURNALS_FOLDER).try_exists()? {
+                log::warn!("Journal is disabled, existing journals are not replayed");
+            }
+            None
+        };
+
+        let active_memtable_id = version_history.latest_version().active_memtable.id();
+
         let inner = TreeInner {
             id: tree_id,
-            memtable_id_counter: SequenceNumberCounter::default(),
+            memtable_id_counter: SequenceNumberCounter::new(active_memtable_id + 1),
             table_id_counter: SequenceNumberCounter::new(highest_table_id + 1),
             blob_file_id_counter: SequenceNumberCounter::default(),
-            version_history: Arc::new(RwLock::new(SuperVersions::new(version))),
+            version_history: Arc::new(RwLock::new(version_history)),
             stop_signal: StopSignal::default(),
             config: Arc::new(config),
             merge_operator: RwLock::new(merge_op),
             major_compaction_lock: RwLock::default(),
             flush_lock: Mutex::default(),
+            journal,
             compaction_state: Arc::new(Mutex::new(CompactionState::default())),
 
             #[cfg(feature = "metrics")]
@@ -1167,15 +1764,17 @@ impl Tree {
         config: &Config,
         #[cfg(feature = "metrics")] metrics: &Arc<Metrics>,
     ) -> crate::Result<Version> {
-        use crate::{file::fsync_directory, file::TABLES_FOLDER, TableId};
+        use crate::{file::fsync_directory, file::TABLES_FOLDER, ChecksumType, TableId};
 
         let tree_path = tree_path.as_ref();
 
         let recovery = recover(tree_path)?;
 
         let table_map = {
-            let mut result: crate::HashMap<TableId, (u8 /* Level index */, Checksum, SeqNo)> =
-                crate::HashMap::default();
+            let mut result: crate::HashMap<
+                TableId,
+                (u8 /* Level index */, ChecksumType, Checksum, SeqNo),
+            > = crate::HashMap::default();
 
             for (level_idx, table_ids) in recovery.table_ids.iter().enumerate() {
                 for run in table_ids {
@@ -1190,6 +1789,7 @@ impl Tree {
                                 level_idx
                                     .try_into()
                                     .expect("there are less than 256 levels"),
+                                table.checksum_type,
                                 table.checksum,
                                 table.global_seqno,
                             ),
@@ -1249,10 +1849,9 @@ impl Tree {
                 crate::Error::Unrecoverable
             })?;
 
-            if let Some(&(level_idx, checksum, global_seqno)) = table_map.get(&table_id) {
-                let pin_filter = config.filter_block_pinning_policy.get(level_idx.into());
-                let pin_index = config.index_block_pinning_policy.get(level_idx.into());
-
+            if let Some(&(level_idx, checksum_type, checksum, global_seqno)) =
+                table_map.get(&table_id)
+            {
                 let table = Table::recover(
                     table_file_path,
                     checksum,
@@ -1260,12 +1859,22 @@ impl Tree {
                     tree_id,
                     config.cache.clone(),
                     config.descriptor_table.clone(),
-                    pin_filter,
-                    pin_index,
+                    Pinning::from_config(config, level_idx.into()),
                     #[cfg(feature = "metrics")]
                     metrics.clone(),
                 )?;
 
+                if table.metadata.checksum_type != checksum_type {
+                    log::error!(
+                        "Table #{table_id} uses checksum type {}, but version records {checksum_type}",
+                        table.metadata.checksum_type,
+                    );
+                    return Err(crate::Error::ChecksumTypeMismatch {
+                        got: table.metadata.checksum_type,
+                        expected: checksum_type,
+                    });
+                }
+
                 tables.push(table);
 
                 if idx % progress_mod == 0 {
@@ -0,0 +1,513 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    coding::Decode,
+    table::{
+        block::{BlockType, Header, Trailer},
+        Block,
+    },
+    version::Version,
+    vlog::{
+        blob_file::{meta::METADATA_HEADER_MAGIC, writer::BLOB_HEADER_MAGIC},
+        BlobFileId,
+    },
+    BlobFile, Checksum, ChecksumType, CompressionDictionary, CompressionType, Table, TableId,
+};
+use byteorder::{ReadBytesExt, LE};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// File in which a corruption was found
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CorruptedFile {
+    /// Table file
+    Table(TableId),
+
+    /// Blob file
+    BlobFile(BlobFileId),
+}
+
+/// A corruption that was found while verifying a tree
+#[derive(Debug)]
+pub struct Corruption {
+    /// File that is corrupted
+    pub file: CorruptedFile,
+
+    /// Path of the corrupted file
+    pub path: PathBuf,
+
+    /// Offset of the corrupted block or blob in the file
+    ///
+    /// Is `None` if the corruption cannot be pinned down, e.g. if the full file checksum does not match.
+    pub offset: Option<u64>,
+
+    /// Error that was encountered
+    pub error: crate::Error,
+}
+
+/// A blob file that is referenced by a table, but is not part of the tree
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MissingBlobFile {
+    /// Table that references the blob file
+    pub table_id: TableId,
+
+    /// ID of the missing blob file
+    pub blob_file_id: BlobFileId,
+}
+
+/// Result of verifying the integrity of a tree
+///
+/// Verification does not stop at the first corruption,
+/// so a report may list multiple corruptions, even in the same file.
+#[derive(Debug, Default)]
+pub struct VerificationReport {
+    /// Number of tables that were verified
+    pub table_count: usize,
+
+    /// Number of blob files that were verified
+    pub blob_file_count: usize,
+
+    /// Corruptions that were found
+    pub corruptions: Vec<Corruption>,
+
+    /// Blob files that are referenced by tables, but do not exist
+    pub missing_blob_files: Vec<MissingBlobFile>,
+}
+
+impl VerificationReport {
+    /// Returns `true` if no corruption was found.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.corruptions.is_empty() && self.missing_blob_files.is_empty()
+    }
+
+    fn push(&mut self, file: CorruptedFile, path: &Path, offset: Option<u64>, error: crate::Error) {
+        log::error!(
+            "Verification found corruption in {file:?} at {}, offset={offset:?}: {error:?}",
+            path.display(),
+        );
+
+        self.corruptions.push(Corruption {
+            file,
+            path: path.into(),
+            offset,
+            error,
+        });
+    }
+}
+
+/// Verifies all tables and blob files of a version.
+pub fn verify_version(version: &Version) -> VerificationReport {
+    let mut report = VerificationReport::default();
+
+    for table in version.iter_tables() {
+        verify_table(table, version, &mut report);
+        report.table_count += 1;
+    }
+
+    for blob_file in version.blob_files.iter() {
+        verify_blob_file(blob_file, &mut report);
+        report.blob_file_count += 1;
+    }
+
+    report
+}
+
+/// Returns the compression of the blocks in a table section,
+/// or `None` if the section does not consist of blocks.
+fn section_compression<'a>(
+    table: &'a Table,
+    section: &[u8],
+) -> Option<(CompressionType, Option<&'a CompressionDictionary>)> {
+    match section {
+        b"data" => Some((
+            table.metadata.data_block_compression,
+            table.compression_dictionary.as_ref(),
+        )),
+        b"range_tombstones" => Some((table.metadata.data_block_compression, None)),
+        b"tli" | b"index" | b"filter_tli" => Some((table.metadata.index_block_compression, None)),
+        b"filter" | b"compression_dictionary" | b"meta" => Some((CompressionType::None, None)),
+        _ => None,
+    }
+}
+
+fn verify_table(table: &Table, version: &Version, report: &mut VerificationReport) {
+    let file = CorruptedFile::Table(table.id());
+    let path = &*table.path;
+
+    match File::open(path) {
+        Ok(f) => {
+            let mut reader = BufReader::new(f);
+
+            verify_file_checksum(
+                &mut reader,
+                table.metadata.checksum_type,
+                table.checksum(),
+                file,
+                path,
+                report,
+            );
+
+            match sfa::Reader::from_reader(&mut reader) {
+                Ok(archive) => {
+                    for section in archive.toc().iter() {
+                        if let Some((compression, dictionary)) =
+                            section_compression(table, section.name())
+                        {
+                            verify_blocks(
+                                &mut reader,
+                                section.pos()..(section.pos() + section.len()),
+                                compression,
+                                dictionary,
+                                file,
+                                path,
+                                report,
+                            );
+                        }
+                    }
+                }
+                Err(e) => report.push(file, path, None, e.into()),
+            }
+        }
+        Err(e) => report.push(file, path, None, e.into()),
+    }
+
+    match table.list_blob_file_references() {
+        Ok(linked_blob_files) => {
+            for linked_file in linked_blob_files.unwrap_or_default() {
+                if !version.blob_files.contains_key(linked_file.blob_file_id) {
+                    log::error!(
+                        "Table {:?} references missing blob file {:?}",
+                        table.id(),
+                        linked_file.blob_file_id,
+                    );
+
+                    report.missing_blob_files.push(MissingBlobFile {
+                        table_id: table.id(),
+                        blob_file_id: linked_file.blob_file_id,
+                    });
+                }
+            }
+        }
+        Err(e) => {
+            let offset = table
+                .regions
+                .linked_blob_files
+                .map(|handle| *handle.offset());
+
+            report.push(file, path, offset, e);
+        }
+    }
+}
+
+fn verify_blob_file(blob_file: &BlobFile, report: &mut VerificationReport) {
+    let file = CorruptedFile::BlobFile(blob_file.id());
+    let path = blob_file.path();
+
+    let mut reader = match File::open(path) {
+        Ok(f) => BufReader::new(f),
+        Err(e) => {
+            report.push(file, path, None, e.into());
+            return;
+        }
+    };
+
+    verify_file_checksum(
+        &mut reader,
+        blob_file.checksum_type(),
+        blob_file.checksum(),
+        file,
+        path,
+        report,
+    );
+
+    let archive = match sfa::Reader::from_reader(&mut reader) {
+        Ok(archive) => archive,
+        Err(e) => {
+            report.push(file, path, None, e.into());
+            return;
+        }
+    };
+
+    if let Some(section) = archive.toc().section(b"data") {
+        verify_blobs(
+            &mut reader,
+            section.pos()..(section.pos() + section.len()),
+            blob_file.checksum_type(),
+            file,
+            path,
+            report,
+        );
+    }
+
+    if let Some(section) = archive.toc().section(b"meta") {
+        let pos = section.pos();
+
+        let mut magic = [0; METADATA_HEADER_MAGIC.len()];
+
+        let is_magic_valid = reader
+            .seek(SeekFrom::Start(pos))
+            .and_then(|_| reader.read_exact(&mut magic))
+            .is_ok_and(|()| magic == METADATA_HEADER_MAGIC);
+
+        if is_magic_valid {
+            verify_blocks(
+                &mut reader,
+                (pos + METADATA_HEADER_MAGIC.len() as u64)..(pos + section.len()),
+                CompressionType::None,
+                None,
+                file,
+                path,
+                report,
+            );
+        } else {
+            report.push(
+                file,
+                path,
+                Some(pos),
+                crate::Error::InvalidHeader("BlobFileMeta"),
+            );
+        }
+    }
+}
+
+fn verify_file_checksum<R: BufRead + Seek>(
+    reader: &mut R,
+    checksum_type: ChecksumType,
+    expected: Checksum,
+    file: CorruptedFile,
+    path: &Path,
+    report: &mut VerificationReport,
+) {
+    let got = reader
+        .rewind()
+        .and_then(|()| checksum_stream(reader, checksum_type))
+        .map_err(crate::Error::from)
+        .and_then(|(got, _)| got.check(expected));
+
+    if let Err(e) = got {
+        report.push(file, path, None, e);
+    }
+}
+
+/// Computes the checksum of the remaining bytes of a reader,
+/// returning the checksum and the number of bytes read.
+fn checksum_stream<R: BufRead>(
+    reader: &mut R,
+    checksum_type: ChecksumType,
+) -> std::io::Result<(Checksum, u64)> {
+    let mut hasher = checksum_type.hasher();
+    let mut len = 0;
+
+    loop {
+        let buf = reader.fill_buf()?;
+
+        if buf.is_empty() {
+            return Ok((hasher.checksum(), len));
+        }
+
+        hasher.update(buf);
+
+        let n = buf.len();
+        reader.consume(n);
+        len += n as u64;
+    }
+}
+
+/// Verifies the consecutive blocks in the given range of a file.
+///
+/// Checks the header and data checksum of every block, and the trailer of blocks
+/// that have one. The walk stops at the first invalid header, because the
+/// position of the next block cannot be known.
+fn verify_blocks<R: BufRead + Seek>(
+    reader: &mut R,
+    range: Range<u64>,
+    compression: CompressionType,
+    dictionary: Option<&CompressionDictionary>,
+    file: CorruptedFile,
+    path: &Path,
+    report: &mut VerificationReport,
+) {
+    if let Err(e) = reader.seek(SeekFrom::Start(range.start)) {
+        report.push(file, path, Some(range.start), e.into());
+        return;
+    }
+
+    let mut reader = reader.take(range.end.saturating_sub(range.start));
+
+    while reader.limit() > 0 {
+        let offset = Some(range.end - reader.limit());
+
+        // NOTE: Read the header on its own first, so we know where the next block starts,
+        // even if the block itself is corrupted
+        let mut header_bytes = [0; Header::serialized_len()];
+
+        let header = match reader
+            .read_exact(&mut header_bytes)
+            .map_err(crate::Error::from)
+            .and_then(|()| Header::decode_from(&mut &header_bytes[..]))
+        {
+            Ok(header) => header,
+            Err(e) => {
+                report.push(file, path, offset, e);
+                return;
+            }
+        };
+
+        if u64::from(header.data_length) > reader.limit() {
+            report.push(file, path, offset, crate::Error::InvalidHeader("Block"));
+            return;
+        }
+
+        let mut block_reader = header_bytes.as_slice().chain(&mut reader);
+
+        match Block::from_reader(&mut block_reader, compression, dictionary) {
+            Ok(block) => {
+                let has_trailer = matches!(
+                    header.block_type,
+                    BlockType::Data
+                        | BlockType::Index
+                        | BlockType::Meta
+                        | BlockType::RangeTombstone
+                );
+
+                if has_trailer {
+                    if let Err(e) = Trailer::new(&block).verify() {
+                        report.push(file, path, offset, e);
+                    }
+                }
+            }
+            Err(e) => report.push(file, path, offset, e),
+        }
+    }
+}
+
+/// Verifies the consecutive blobs in the given range of a blob file.
+///
+/// The walk stops at the first invalid blob header, because the
+/// position of the next blob cannot be known.
+fn verify_blobs<R: BufRead + Seek>(
+    reader: &mut R,
+    range: Range<u64>,
+    checksum_type: ChecksumType,
+    file: CorruptedFile,
+    path: &Path,
+    report: &mut VerificationReport,
+) {
+    if let Err(e) = reader.seek(SeekFrom::Start(range.start)) {
+        report.push(file, path, Some(range.start), e.into());
+        return;
+    }
+
+    let mut reader = reader.take(range.end.saturating_sub(range.start));
+
+    while reader.limit() > 0 {
+        let offset = Some(range.end - reader.limit());
+
+        match verify_blob(&mut reader, checksum_type) {
+            Ok(()) => {}
+            Err(e @ crate::Error::ChecksumMismatch { .. }) => {
+                // NOTE: The lengths are not covered by the checksum,
+                // but are our best guess to find the next blob
+                report.push(file, path, offset, e);
+            }
+            Err(e) => {
+                report.push(file, path, offset, e);
+                return;
+            }
+        }
+    }
+}
+
+/// Verifies a single blob, leaving the reader at the start of the next blob.
+fn verify_blob<R: BufRead>(reader: &mut R, checksum_type: ChecksumType) -> crate::Result<()> {
+    let mut magic = [0; BLOB_HEADER_MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+
+    if magic != BLOB_HEADER_MAGIC {
+        return Err(crate::Error::InvalidHeader("Blob"));
+    }
+
+    let expected_checksum = Checksum::from_raw(reader.read_u128::<LE>()?);
+    let _seqno = reader.read_u64::<LE>()?;
+    let key_len = reader.read_u16::<LE>()?;
+    let _real_val_len = reader.read_u32::<LE>()?;
+    let on_disk_val_len = reader.read_u32::<LE>()?;
+
+    let data_len = u64::from(key_len) + u64::from(on_disk_val_len);
+
+    let (got, len) = checksum_stream(&mut reader.take(data_len), checksum_type)?;
+
+    if len != data_len {
+        return Err(crate::Error::InvalidHeader("Blob"));
+    }
+
+    got.check(expected_checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        version::{BlobFileList, Level},
+        AbstractTree, Config, KvSeparationOptions, SequenceNumberCounter,
+    };
+    use test_log::test;
+
+    #[test]
+    fn verify_missing_blob_file() -> crate::Result<()> {
+        let folder = tempfile::tempdir()?;
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+        .open()?;
+
+        tree.insert("a", "a".repeat(100), 0);
+        tree.flush_active_memtable(0)?;
+
+        let version = tree.current_version();
+
+        let report = verify_version(&version);
+        assert!(report.is_ok());
+        assert_eq!(1, report.table_count);
+        assert_eq!(1, report.blob_file_count);
+
+        let table_id = version.iter_tables().next().expect("should exist").id();
+        let blob_file_id = version.blob_files.iter().next().expect("should exist").id();
+
+        let version_without_blob_files = Version::from_levels(
+            version.id() + 1,
+            version.tree_type(),
+            version
+                .iter_levels()
+                .map(|level| Level::from_runs(level.iter().cloned().collect()))
+                .collect(),
+            BlobFileList::default(),
+            version.gc_stats().clone(),
+        );
+
+        let report = verify_version(&version_without_blob_files);
+        assert!(!report.is_ok());
+        assert!(report.corruptions.is_empty());
+        assert_eq!(0, report.blob_file_count);
+        assert_eq!(
+            vec![MissingBlobFile {
+                table_id,
+                blob_file_id,
+            }],
+            report.missing_blob_files,
+        );
+
+        Ok(())
+    }
+}
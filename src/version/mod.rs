@@ -78,13 +78,19 @@ impl<T: Ranged> GenericLevel<T> {
 }
 
 #[derive(Clone)]
-pub struct Level(Arc<GenericLevel<Table>>);
+pub struct Level {
+    inner: Arc<GenericLevel<Table>>,
+
+    /// Tables that contain range tombstones, so point reads
+    /// do not need to look at every table of the level
+    range_tombstone_tables: Arc<[Table]>,
+}
 
 impl std::ops::Deref for Level {
     type Target = GenericLevel<Table>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.inner
     }
 }
 
@@ -94,7 +100,22 @@ impl Level {
     }
 
     pub fn from_runs(runs: Vec<Arc<Run<Table>>>) -> Self {
-        Self(Arc::new(GenericLevel { runs }))
+        let range_tombstone_tables = runs
+            .iter()
+            .flat_map(|run| run.iter())
+            .filter(|table| !table.range_tombstones().is_empty())
+            .cloned()
+            .collect();
+
+        Self {
+            inner: Arc::new(GenericLevel { runs }),
+            range_tombstone_tables,
+        }
+    }
+
+    /// Returns the tables that contain range tombstones.
+    pub fn range_tombstone_tables(&self) -> &[Table] {
+        &self.range_tombstone_tables
     }
 
     pub fn list_ids(&self) -> HashSet<TableId> {
@@ -110,7 +131,7 @@ impl Level {
 
     /// Returns the on-disk size of the level.
     pub fn size(&self) -> u64 {
-        self.0
+        self.inner
             .iter()
             .flat_map(|x| x.iter())
             .map(Table::file_size)
@@ -673,7 +694,7 @@ impl Version {
                 // Tables
                 for table in run.iter() {
                     writer.write_u64::<LittleEndian>(table.id())?;
-                    writer.write_u8(0)?; // Checksum type, 0 = XXH3
+                    writer.write_u8(table.metadata.checksum_type.into())?;
                     writer.write_u128::<LittleEndian>(table.checksum().into_u128())?;
                     writer.write_u64::<LittleEndian>(table.global_seqno())?;
                 }
@@ -691,7 +712,7 @@ impl Version {
 
         for file in self.blob_files.iter() {
             writer.write_u64::<LittleEndian>(file.id())?;
-            writer.write_u8(0)?; // Checksum type, 0 = XXH3
+            writer.write_u8(file.0.meta.checksum_type.into())?;
             writer.write_u128::<LittleEndian>(file.0.checksum.into_u128())?;
         }
 
@@ -12,15 +12,25 @@ pub fn optimize_runs<T: Clone + Ranged>(runs: Vec<Run<T>>) -> Vec<Run<T>> {
         let mut new_runs: Vec<Run<T>> = Vec::new();
 
         for run in runs.iter().rev() {
-            'run: for table in run.iter().rev() {
-                for existing_run in new_runs.iter_mut().rev() {
-                    if existing_run
-                        .iter()
-                        .all(|x| !table.key_range().overlaps_with_key_range(x.key_range()))
-                    {
-                        existing_run.push(table.clone());
-                        continue 'run;
-                    }
+            for table in run.iter().rev() {
+                // NOTE: The table is newer than all tables seen so far, so it may only
+                // be put into a run that is newer than every run it overlaps with,
+                // otherwise reads would see older versions first
+                let first_overlapping_idx = new_runs
+                    .iter()
+                    .position(|existing_run| {
+                        existing_run
+                            .iter()
+                            .any(|x| table.key_range().overlaps_with_key_range(x.key_range()))
+                    })
+                    .unwrap_or(new_runs.len());
+
+                if let Some(existing_run) = first_overlapping_idx
+                    .checked_sub(1)
+                    .and_then(|idx| new_runs.get_mut(idx))
+                {
+                    existing_run.push(table.clone());
+                    continue;
                 }
 
                 #[expect(
@@ -145,6 +155,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn optimize_runs_no_sink_below_overlap() {
+        let runs = vec![
+            Run::new(vec![s(2, "d", "e")]).unwrap(),
+            Run::new(vec![s(1, "b", "z")]).unwrap(),
+            Run::new(vec![s(0, "a", "c")]).unwrap(),
+        ];
+        let runs = optimize_runs::<FakeTable>(runs);
+
+        assert_eq!(
+            vec![
+                Run::new(vec![s(2, "d", "e")]).unwrap(),
+                Run::new(vec![s(1, "b", "z")]).unwrap(),
+                Run::new(vec![s(0, "a", "c")]).unwrap(),
+            ],
+            &*runs,
+        );
+    }
+
     #[test]
     fn optimize_runs_two_disjoint_2() {
         let runs = vec![
@@ -1,12 +1,16 @@
 use crate::{
-    checksum::ChecksummedWriter,
+    checksum::{ChecksumType, ChecksummedWriter},
     file::{fsync_directory, rewrite_atomic, CURRENT_VERSION_FILE},
     version::Version,
 };
 use byteorder::{LittleEndian, WriteBytesExt};
 use std::{io::BufWriter, path::Path};
 
-pub fn persist_version(folder: &Path, version: &Version) -> crate::Result<()> {
+pub fn persist_version(
+    folder: &Path,
+    version: &Version,
+    checksum_type: ChecksumType,
+) -> crate::Result<()> {
     log::trace!(
         "Persisting version {} in {}",
         version.id(),
@@ -16,7 +20,7 @@ pub fn persist_version(folder: &Path, version: &Version) -> crate::Result<()> {
     let path = folder.join(format!("v{}", version.id()));
     let file = std::fs::File::create_new(path)?;
     let writer = BufWriter::new(file);
-    let mut writer = ChecksummedWriter::new(writer);
+    let mut writer = ChecksummedWriter::new(writer, checksum_type);
 
     {
         let mut writer = sfa::Writer::from_writer(&mut writer);
@@ -37,7 +41,7 @@ pub fn persist_version(folder: &Path, version: &Version) -> crate::Result<()> {
     let mut current_file_content = vec![];
     current_file_content.write_u64::<LittleEndian>(version.id())?;
     current_file_content.write_u128::<LittleEndian>(checksum.into_u128())?;
-    current_file_content.write_u8(0)?; // 0 = xxh3
+    current_file_content.write_u8(checksum_type.into())?;
 
     rewrite_atomic(&folder.join(CURRENT_VERSION_FILE), &current_file_content)?;
 
@@ -3,8 +3,8 @@
 // (found in the LICENSE-* files in the repository)
 
 use crate::{
-    coding::Decode, file::CURRENT_VERSION_FILE, version::VersionId, vlog::BlobFileId, Checksum,
-    SeqNo, TableId, TreeType,
+    checksum::ChecksumType, coding::Decode, file::CURRENT_VERSION_FILE, version::VersionId,
+    vlog::BlobFileId, Checksum, SeqNo, TableId, TreeType,
 };
 use byteorder::{LittleEndian, ReadBytesExt};
 use std::path::Path;
@@ -19,6 +19,7 @@ pub fn get_current_version(folder: &std::path::Path) -> crate::Result<VersionId>
 
 pub struct RecoveredTable {
     pub id: TableId,
+    pub checksum_type: ChecksumType,
     pub checksum: Checksum,
     pub global_seqno: SeqNo,
 }
@@ -27,7 +28,7 @@ pub struct Recovery {
     pub tree_type: TreeType,
     pub curr_version_id: VersionId,
     pub table_ids: Vec<Vec<Vec<RecoveredTable>>>,
-    pub blob_file_ids: Vec<(BlobFileId, Checksum)>,
+    pub blob_file_ids: Vec<(BlobFileId, ChecksumType, Checksum)>,
     pub gc_stats: crate::blob_tree::FragmentationMap,
 }
 
@@ -69,11 +70,7 @@ pub fn recover(folder: &Path) -> crate::Result<Recovery> {
 
                 for _ in 0..table_count {
                     let id = reader.read_u64::<LittleEndian>()?;
-                    let checksum_type = reader.read_u8()?;
-
-                    if checksum_type != 0 {
-                        return Err(crate::Error::InvalidTag(("ChecksumType", checksum_type)));
-                    }
+                    let checksum_type = ChecksumType::try_from(reader.read_u8()?)?;
 
                     let checksum = reader.read_u128::<LittleEndian>()?;
                     let checksum = Checksum::from_raw(checksum);
@@ -82,6 +79,7 @@ pub fn recover(folder: &Path) -> crate::Result<Recovery> {
 
                     run.push(RecoveredTable {
                         id,
+                        checksum_type,
                         checksum,
                         global_seqno,
                     });
@@ -109,16 +107,12 @@ pub fn recover(folder: &Path) -> crate::Result<Recovery> {
         for _ in 0..blob_file_count {
             let id = reader.read_u64::<LittleEndian>()?;
 
-            let checksum_type = reader.read_u8()?;
-
-            if checksum_type != 0 {
-                return Err(crate::Error::InvalidTag(("ChecksumType", checksum_type)));
-            }
+            let checksum_type = ChecksumType::try_from(reader.read_u8()?)?;
 
             let checksum = reader.read_u128::<LittleEndian>()?;
             let checksum = Checksum::from_raw(checksum);
 
-            blob_file_ids.push((id, checksum));
+            blob_file_ids.push((id, checksum_type, checksum));
         }
 
         blob_file_ids
@@ -3,6 +3,7 @@
 // (found in the LICENSE-* files in the repository)
 
 use crate::{
+    checksum::ChecksumType,
     memtable::Memtable,
     tree::sealed::SealedMemtables,
     version::{persist_version, Version},
@@ -113,11 +114,12 @@ impl SuperVersions {
     pub(crate) fn upgrade_version<F: FnOnce(&SuperVersion) -> crate::Result<SuperVersion>>(
         &mut self,
         tree_path: &Path,
+        checksum_type: ChecksumType,
         f: F,
         seqno: &SequenceNumberCounter,
         visible_seqno: &SequenceNumberCounter,
     ) -> crate::Result<()> {
-        self.upgrade_version_with_seqno(tree_path, f, seqno.next(), visible_seqno)
+        self.upgrade_version_with_seqno(tree_path, checksum_type, f, seqno.next(), visible_seqno)
     }
 
     /// Like `upgrade_version`, but takes an already-allocated sequence number.
@@ -129,6 +131,7 @@ impl SuperVersions {
     >(
         &mut self,
         tree_path: &Path,
+        checksum_type: ChecksumType,
         f: F,
         seqno: SeqNo,
         visible_seqno: &SequenceNumberCounter,
@@ -137,7 +140,7 @@ impl SuperVersions {
         next_version.seqno = seqno;
         log::trace!("Next version seqno={}", next_version.seqno);
 
-        persist_version(tree_path, &next_version.version)?;
+        persist_version(tree_path, &next_version.version, checksum_type)?;
         self.append_version(next_version);
 
         visible_seqno.fetch_max(seqno + 1);
@@ -56,6 +56,9 @@ pub struct Metadata {
 
     /// Compression type used for all blobs in this file
     pub compression: CompressionType,
+
+    /// Checksum algorithm used for all blobs and the metadata block in this file
+    pub checksum_type: ChecksumType,
 }
 
 impl Metadata {
@@ -70,7 +73,7 @@ impl Metadata {
         #[rustfmt::skip]
         let meta_items = [
             meta("blob_file_version", &[0x3]),
-            meta("checksum_type", &[u8::from(ChecksumType::Xxh3)]),
+            meta("checksum_type", &[u8::from(self.checksum_type)]),
             meta("compression", &self.compression.encode_into_vec()),
             meta("crate_version", env!("CARGO_PKG_VERSION").as_bytes()),
             meta("created_at", &self.created_at.to_le_bytes()),
@@ -97,6 +100,7 @@ impl Metadata {
             &buf,
             crate::table::block::BlockType::Meta,
             CompressionType::None,
+            self.checksum_type,
         )?;
 
         Ok(())
@@ -114,7 +118,7 @@ impl Metadata {
         }
 
         // TODO: Block::from_slice
-        let block = Block::from_reader(reader, CompressionType::None)?;
+        let block = Block::from_reader(reader, CompressionType::None, None)?;
         let block = DataBlock::new(block);
 
         let id = read_u64!(block, b"id");
@@ -133,6 +137,16 @@ impl Metadata {
             CompressionType::decode_from(&mut bytes)?
         };
 
+        let checksum_type = {
+            #[expect(clippy::expect_used, reason = "checksum type is expected to exist")]
+            let bytes = block
+                .point_read(b"checksum_type", SeqNo::MAX)
+                .expect("checksum type should exist");
+
+            let mut bytes = &bytes.value[..];
+            ChecksumType::try_from(bytes.read_u8()?)?
+        };
+
         let key_range = KeyRange::new((
             #[expect(clippy::expect_used, reason = "key min is expected to exist")]
             block
@@ -150,6 +164,7 @@ impl Metadata {
             id,
             created_at,
             compression,
+            checksum_type,
             item_count,
             total_compressed_bytes: file_size,
             total_uncompressed_bytes,
@@ -170,6 +185,7 @@ mod tests {
             id: 0,
             created_at: 1_234_567_890,
             compression: CompressionType::None,
+            checksum_type: ChecksumType::Crc32c,
             item_count: 100,
             total_compressed_bytes: 1024,
             total_uncompressed_bytes: 2048,
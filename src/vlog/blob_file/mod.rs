@@ -9,7 +9,7 @@ pub mod reader;
 pub mod scanner;
 pub mod writer;
 
-use crate::{blob_tree::FragmentationMap, vlog::BlobFileId, Checksum};
+use crate::{blob_tree::FragmentationMap, vlog::BlobFileId, Checksum, ChecksumType};
 pub use meta::Metadata;
 use std::{
     path::{Path, PathBuf},
@@ -91,6 +91,12 @@ impl BlobFile {
         self.0.checksum
     }
 
+    /// Returns the checksum type the blob file was written with.
+    #[must_use]
+    pub fn checksum_type(&self) -> ChecksumType {
+        self.0.meta.checksum_type
+    }
+
     /// Returns the blob file path.
     #[must_use]
     pub fn path(&self) -> &Path {
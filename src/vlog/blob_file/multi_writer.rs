@@ -8,7 +8,7 @@ use crate::{
         blob_file::{Inner as BlobFileInner, Metadata},
         BlobFileId,
     },
-    BlobFile, CompressionType, SeqNo, SequenceNumberCounter,
+    BlobFile, ChecksumType, CompressionType, SeqNo, SequenceNumberCounter,
 };
 use std::{
     path::{Path, PathBuf},
@@ -28,6 +28,8 @@ pub struct MultiWriter {
 
     compression: CompressionType,
     passthrough_compression: CompressionType,
+
+    checksum_type: ChecksumType,
 }
 
 impl MultiWriter {
@@ -57,6 +59,8 @@ impl MultiWriter {
 
             compression: CompressionType::None,
             passthrough_compression: CompressionType::None,
+
+            checksum_type: ChecksumType::Xxh3,
         })
     }
 
@@ -86,6 +90,15 @@ impl MultiWriter {
         self
     }
 
+    /// Sets the checksum algorithm.
+    #[must_use]
+    #[doc(hidden)]
+    pub fn use_checksum_type(mut self, checksum_type: ChecksumType) -> Self {
+        self.checksum_type = checksum_type;
+        self.active_writer = self.active_writer.use_checksum_type(checksum_type);
+        self
+    }
+
     #[must_use]
     pub fn offset(&self) -> u64 {
         self.active_writer.offset()
@@ -103,8 +116,9 @@ impl MultiWriter {
         let new_blob_file_id = self.id_generator.next();
         let blob_file_path = self.folder.join(new_blob_file_id.to_string());
 
-        let new_writer =
-            Writer::new(blob_file_path, new_blob_file_id)?.use_compression(self.compression);
+        let new_writer = Writer::new(blob_file_path, new_blob_file_id)?
+            .use_compression(self.compression)
+            .use_checksum_type(self.checksum_type);
 
         let old_writer = std::mem::replace(&mut self.active_writer, new_writer);
         let blob_file = Self::consume_writer(old_writer, self.passthrough_compression)?;
@@ -147,6 +161,7 @@ impl MultiWriter {
                     } else {
                         passthrough_compression
                     },
+                    checksum_type: metadata.checksum_type,
                 },
             }));
 
@@ -62,10 +62,10 @@ impl<'a> Reader<'a> {
 
         {
             let checksum = {
-                let mut hasher = xxhash_rust::xxh3::Xxh3::default();
+                let mut hasher = self.blob_file.0.meta.checksum_type.hasher();
                 hasher.update(key);
                 hasher.update(&raw_data);
-                hasher.digest128()
+                hasher.checksum().into_u128()
             };
 
             if expected_checksum != checksum {
@@ -94,6 +94,21 @@ impl<'a> Reader<'a> {
 
                 builder.freeze().into()
             }
+
+            #[cfg(feature = "zstd")]
+            CompressionType::Zstd(_) => {
+                #[warn(unsafe_code)]
+                let mut builder = unsafe { UserValue::builder_unzeroed(real_val_len as usize) };
+
+                let len = zstd::bulk::decompress_to_buffer(&raw_data, &mut builder)
+                    .map_err(|_| crate::Error::Decompress(self.blob_file.0.meta.compression))?;
+
+                if len != real_val_len as usize {
+                    return Err(crate::Error::Decompress(self.blob_file.0.meta.compression));
+                }
+
+                builder.freeze().into()
+            }
         };
 
         Ok(value)
@@ -171,4 +186,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn blob_reader_roundtrip_zstd() -> crate::Result<()> {
+        let id_generator = SequenceNumberCounter::default();
+
+        let folder = tempfile::tempdir()?;
+        let mut writer = crate::vlog::BlobFileWriter::new(id_generator, folder.path())?
+            .use_target_size(u64::MAX)
+            .use_compression(CompressionType::Zstd(3));
+
+        let offset = writer.offset();
+        let on_disk_size = writer.write(b"a", 0, &b"abcdef".repeat(100))?;
+        let handle0 = ValueHandle {
+            blob_file_id: 0,
+            offset,
+            on_disk_size,
+        };
+
+        let offset = writer.offset();
+        let on_disk_size = writer.write(b"b", 0, b"ghi")?;
+        let handle1 = ValueHandle {
+            blob_file_id: 0,
+            offset,
+            on_disk_size,
+        };
+
+        let blob_file = writer.finish()?;
+        let blob_file = blob_file.first().unwrap();
+
+        let file = File::open(&blob_file.0.path)?;
+        let reader = Reader::new(blob_file, &file);
+
+        assert_eq!(reader.get(b"a", &handle0)?, b"abcdef".repeat(100));
+        assert_eq!(reader.get(b"b", &handle1)?, b"ghi");
+
+        Ok(())
+    }
 }
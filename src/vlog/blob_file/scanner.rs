@@ -5,7 +5,7 @@
 use super::writer::BLOB_HEADER_MAGIC;
 use crate::{
     vlog::{blob_file::meta::METADATA_HEADER_MAGIC, BlobFileId},
-    Checksum, SeqNo, UserKey, UserValue,
+    Checksum, ChecksumType, SeqNo, UserKey, UserValue,
 };
 use byteorder::{LittleEndian, ReadBytesExt};
 use std::{
@@ -19,6 +19,7 @@ pub struct Scanner {
     pub(crate) blob_file_id: BlobFileId, // TODO: remove unused?
     inner: BufReader<File>,
     is_terminated: bool,
+    checksum_type: ChecksumType,
 }
 
 impl Scanner {
@@ -39,8 +40,16 @@ impl Scanner {
             blob_file_id,
             inner: file_reader,
             is_terminated: false,
+            checksum_type: ChecksumType::Xxh3,
         }
     }
+
+    /// Sets the checksum algorithm of the blobs.
+    #[must_use]
+    pub fn use_checksum_type(mut self, checksum_type: ChecksumType) -> Self {
+        self.checksum_type = checksum_type;
+        self
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -94,10 +103,10 @@ impl Iterator for Scanner {
 
         {
             let checksum = {
-                let mut hasher = xxhash_rust::xxh3::Xxh3::default();
+                let mut hasher = self.checksum_type.hasher();
                 hasher.update(&key);
                 hasher.update(&value);
-                hasher.digest128()
+                hasher.checksum().into_u128()
             };
 
             if expected_checksum != checksum {
@@ -4,8 +4,10 @@
 
 use super::meta::Metadata;
 use crate::{
-    checksum::ChecksummedWriter, time::unix_timestamp, vlog::BlobFileId, Checksum, CompressionType,
-    KeyRange, SeqNo, UserKey,
+    checksum::{ChecksumType, ChecksummedWriter},
+    time::unix_timestamp,
+    vlog::BlobFileId,
+    Checksum, CompressionType, KeyRange, SeqNo, UserKey,
 };
 use byteorder::{LittleEndian, WriteBytesExt};
 use std::{
@@ -41,6 +43,8 @@ pub struct Writer {
     pub(crate) last_key: Option<UserKey>,
 
     pub(crate) compression: CompressionType,
+
+    pub(crate) checksum_type: ChecksumType,
 }
 
 impl Writer {
@@ -54,7 +58,7 @@ impl Writer {
         let path = path.as_ref();
 
         let writer = BufWriter::new(File::create(path)?);
-        let writer = ChecksummedWriter::new(writer);
+        let writer = ChecksummedWriter::new(writer, ChecksumType::Xxh3);
         let mut writer = sfa::Writer::from_writer(writer);
         writer.start("data")?;
 
@@ -73,6 +77,8 @@ impl Writer {
             last_key: None,
 
             compression: CompressionType::None,
+
+            checksum_type: ChecksumType::Xxh3,
         })
     }
 
@@ -81,6 +87,15 @@ impl Writer {
         self
     }
 
+    /// Sets the checksum algorithm of the blobs and the full file checksum.
+    ///
+    /// Needs to be set before any blob is written.
+    pub fn use_checksum_type(mut self, checksum_type: ChecksumType) -> Self {
+        self.checksum_type = checksum_type;
+        self.writer.get_mut().set_checksum_type(checksum_type);
+        self
+    }
+
     /// Returns the current offset in the file.
     ///
     /// This can be used to index an item into an external `Index`.
@@ -133,17 +148,23 @@ impl Writer {
 
             #[cfg(feature = "lz4")]
             CompressionType::Lz4 => std::borrow::Cow::Owned(lz4_flex::compress(value)),
+
+            #[cfg(feature = "zstd")]
+            CompressionType::Zstd(level) => {
+                std::borrow::Cow::Owned(zstd::bulk::compress(value, *level)?)
+            }
         };
 
         let checksum = {
-            let mut hasher = xxhash_rust::xxh3::Xxh3::default();
+            let mut hasher = self.checksum_type.hasher();
             hasher.update(key);
             hasher.update(&value);
-            hasher.digest128()
+            hasher.checksum()
         };
 
         // Write checksum
-        self.writer.write_u128::<LittleEndian>(checksum)?;
+        self.writer
+            .write_u128::<LittleEndian>(checksum.into_u128())?;
 
         // Write seqno
         self.writer.write_u64::<LittleEndian>(seqno)?;
@@ -220,6 +241,7 @@ impl Writer {
                     .expect("should have written at least 1 item"),
             )),
             compression: self.compression,
+            checksum_type: self.checksum_type,
         };
         metadata.encode_into(&mut self.writer)?;
 
@@ -14,7 +14,7 @@ pub use {
 
 use crate::{
     vlog::blob_file::{Inner as BlobFileInner, Metadata},
-    Checksum,
+    Checksum, ChecksumType,
 };
 use std::{
     path::{Path, PathBuf},
@@ -23,7 +23,7 @@ use std::{
 
 pub fn recover_blob_files(
     folder: &Path,
-    ids: &[(BlobFileId, Checksum)],
+    ids: &[(BlobFileId, ChecksumType, Checksum)],
 ) -> crate::Result<(Vec<BlobFile>, Vec<PathBuf>)> {
     if !folder.try_exists()? {
         return Ok((vec![], vec![]));
@@ -69,7 +69,9 @@ pub fn recover_blob_files(
         let blob_file_path = dirent.path();
         assert!(!blob_file_path.is_dir());
 
-        if let Some(&(_, checksum)) = ids.iter().find(|(id, _)| id == &blob_file_id) {
+        if let Some(&(_, checksum_type, checksum)) =
+            ids.iter().find(|(id, _, _)| id == &blob_file_id)
+        {
             log::trace!("Recovering blob file #{blob_file_id:?}");
 
             let meta = {
@@ -93,6 +95,17 @@ pub fn recover_blob_files(
                 Metadata::from_slice(&metadata_slice)?
             };
 
+            if meta.checksum_type != checksum_type {
+                log::error!(
+                    "Blob file #{blob_file_id} uses checksum type {}, but version records {checksum_type}",
+                    meta.checksum_type,
+                );
+                return Err(crate::Error::ChecksumTypeMismatch {
+                    got: meta.checksum_type,
+                    expected: checksum_type,
+                });
+            }
+
             blob_files.push(BlobFile(Arc::new(BlobFileInner {
                 id: blob_file_id,
                 path: blob_file_path,
@@ -129,7 +142,10 @@ mod tests {
     #[test]
     fn vlog_recovery_missing_blob_file() {
         assert!(matches!(
-            recover_blob_files(Path::new("."), &[(0, Checksum::from_raw(0))]),
+            recover_blob_files(
+                Path::new("."),
+                &[(0, ChecksumType::Xxh3, Checksum::from_raw(0))]
+            ),
             Err(crate::Error::Unrecoverable),
         ));
     }
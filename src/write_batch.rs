@@ -0,0 +1,471 @@
+// Copyright (c) 2025-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    merge_operator::{MergeOperator, MergeResult},
+    RangeTombstone, SeqNo, UserKey, UserValue, ValueType,
+};
+
+/// A batch of writes that is applied to a tree atomically
+///
+/// All writes of a batch share a single sequence number and
+/// are inserted into the same memtable, see [`AbstractTree::apply_batch`](crate::AbstractTree::apply_batch).
+///
+/// Because all writes share a sequence number, only one write per key is applied
+/// if the key is written multiple times: the last write, unless it is a merge operand.
+/// Trailing merge operands of a key are combined with its earlier writes in the batch,
+/// using the tree's merge operator.
+///
+/// Writes are applied in the order they were added to the batch:
+/// [`WriteBatch::remove_range`] removes writes that were added before it,
+/// while writes added after it are kept.
+///
+/// # Examples
+///
+/// ```
+/// # let folder = tempfile::tempdir()?;
+/// use lsm_tree::{AbstractTree, Config, WriteBatch};
+///
+/// let tree = Config::new(folder, Default::default(), Default::default()).open()?;
+///
+/// let mut batch = WriteBatch::new();
+/// batch.insert("a", "abc");
+/// batch.insert("b", "def");
+/// batch.remove("c");
+///
+/// tree.apply_batch(batch, 0)?;
+///
+/// assert_eq!(2, tree.len(1, None)?);
+/// #
+/// # Ok::<(), lsm_tree::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WriteBatch {
+    items: Vec<(UserKey, UserValue, ValueType)>,
+    range_tombstones: Vec<(UserKey, UserKey)>,
+    size: u64,
+}
+
+impl WriteBatch {
+    /// Creates an empty batch.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty batch with space for `n` writes.
+    #[must_use]
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(n),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the number of writes in the batch.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len() + self.range_tombstones.len()
+    }
+
+    /// Returns `true` if the batch contains no writes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the approximate size of the keys and values in the batch in bytes.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Removes all writes from the batch, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.range_tombstones.clear();
+        self.size = 0;
+    }
+
+    fn push(&mut self, key: UserKey, value: UserValue, value_type: ValueType) {
+        self.size += (key.len() + value.len()) as u64;
+        self.items.push((key, value, value_type));
+    }
+
+    /// Inserts a key-value pair.
+    pub fn insert<K: Into<UserKey>, V: Into<UserValue>>(&mut self, key: K, value: V) {
+        self.push(key.into(), value.into(), ValueType::Value);
+    }
+
+    /// Removes a key, see [`AbstractTree::remove`](crate::AbstractTree::remove).
+    pub fn remove<K: Into<UserKey>>(&mut self, key: K) {
+        self.push(key.into(), UserValue::empty(), ValueType::Tombstone);
+    }
+
+    /// Removes a key, see [`AbstractTree::remove_weak`](crate::AbstractTree::remove_weak).
+    pub fn remove_weak<K: Into<UserKey>>(&mut self, key: K) {
+        self.push(key.into(), UserValue::empty(), ValueType::WeakTombstone);
+    }
+
+    /// Writes a merge operand, see [`AbstractTree::merge`](crate::AbstractTree::merge).
+    pub fn merge<K: Into<UserKey>, V: Into<UserValue>>(&mut self, key: K, operand: V) {
+        self.push(key.into(), operand.into(), ValueType::Merge);
+    }
+
+    /// Removes all items in the key range `[start, end)`,
+    /// see [`AbstractTree::remove_range`](crate::AbstractTree::remove_range).
+    ///
+    /// This includes writes to the range that were previously added to the batch.
+    /// Writes that are added afterwards are not affected.
+    pub fn remove_range<K: Into<UserKey>>(&mut self, range: std::ops::Range<K>) {
+        let (start, end) = (range.start.into(), range.end.into());
+
+        // NOTE: The range tombstone gets the same seqno as the rest of the batch,
+        // so it does not cover the batch's own writes, which is why we drop them here
+        self.items.retain(|(key, value, _)| {
+            let is_covered = start <= *key && *key < end;
+
+            if is_covered {
+                self.size -= (key.len() + value.len()) as u64;
+            }
+
+            !is_covered
+        });
+
+        self.size += (start.len() + end.len()) as u64;
+        self.range_tombstones.push((start, end));
+    }
+
+    /// Turns the batch into its writes, using the given sequence number.
+    ///
+    /// Only one write of each key is kept, sorted by key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the merge operands of a key cannot be combined.
+    pub(crate) fn into_writes(
+        self,
+        seqno: SeqNo,
+        merge_operator: Option<&dyn MergeOperator>,
+    ) -> crate::Result<(
+        impl Iterator<Item = crate::InternalValue>,
+        impl Iterator<Item = RangeTombstone>,
+    )> {
+        let mut items = self.items;
+
+        // NOTE: Sorting is stable, so the writes of a key stay in the order they were added
+        items.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        let mut writes = Vec::with_capacity(items.len());
+
+        for key_writes in items.chunk_by(|(a, _, _), (b, _, _)| a == b) {
+            let Some((key, value, value_type)) = key_writes.last() else {
+                continue;
+            };
+
+            let (value, value_type) = match merge_operator {
+                Some(merge_op) if *value_type == ValueType::Merge => {
+                    Self::merge_writes(merge_op, key_writes)?
+                }
+                _ => (value.clone(), *value_type),
+            };
+
+            writes.push(crate::InternalValue::from_components(
+                key.clone(),
+                value,
+                seqno,
+                value_type,
+            ));
+        }
+
+        let range_tombstones = self
+            .range_tombstones
+            .into_iter()
+            .filter(|(start, end)| start < end)
+            .map(move |(start, end)| RangeTombstone::new(start, end, seqno));
+
+        Ok((writes.into_iter(), range_tombstones))
+    }
+
+    /// Combines the writes of a single key (oldest to newest) that end in merge operands.
+    ///
+    /// If the batch also contains a value or tombstone for the key, the operands
+    /// are fully merged on top of it, otherwise they are combined using
+    /// [`MergeOperator::partial_merge`].
+    fn merge_writes(
+        merge_op: &dyn MergeOperator,
+        key_writes: &[(UserKey, UserValue, ValueType)],
+    ) -> crate::Result<(UserValue, ValueType)> {
+        let base_idx = key_writes
+            .iter()
+            .rposition(|(_, _, value_type)| *value_type != ValueType::Merge);
+
+        let (base, operands) = match base_idx {
+            Some(idx) => key_writes.split_at(idx + 1),
+            None => (&[][..], key_writes),
+        };
+
+        if let Some((key, base_value, base_type)) = base.last() {
+            // NOTE: A tombstone ends the merge chain, so it acts as if there was no base value
+            let base_value = (*base_type == ValueType::Value).then_some(base_value);
+            let operands = operands
+                .iter()
+                .map(|(_, operand, _)| operand.clone())
+                .collect::<Vec<_>>();
+
+            return match merge_op.full_merge(key, base_value, &operands) {
+                MergeResult::Success(merged_value) => Ok((merged_value, ValueType::Value)),
+                MergeResult::Failure => {
+                    log::warn!(
+                        "Merge operator '{}' failed for key {key:?} in write batch",
+                        merge_op.name(),
+                    );
+                    Err(crate::Error::MergeFailed(merge_op.name()))
+                }
+            };
+        }
+
+        let mut operands = operands.iter();
+
+        let Some((_, first, _)) = operands.next() else {
+            return Err(crate::Error::MergeFailed(merge_op.name()));
+        };
+
+        let mut combined = first.clone();
+
+        for (key, operand, _) in operands {
+            let Some(value) = merge_op.partial_merge(key, &combined, operand) else {
+                // NOTE: All writes of the batch share a seqno, so we cannot keep both operands
+                log::warn!(
+                    "Merge operator '{}' cannot combine operands for key {key:?} in write batch",
+                    merge_op.name(),
+                );
+                return Err(crate::Error::MergeFailed(merge_op.name()));
+            };
+
+            combined = value;
+        }
+
+        Ok((combined, ValueType::Merge))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    /// Adds up integer operands, optionally supporting partial merges
+    struct CounterMerge {
+        partial: bool,
+    }
+
+    fn parse(value: &UserValue) -> i64 {
+        std::str::from_utf8(value)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default()
+    }
+
+    impl MergeOperator for CounterMerge {
+        fn name(&self) -> &'static str {
+            "CounterMerge"
+        }
+
+        fn full_merge(
+            &self,
+            _key: &UserKey,
+            existing_value: Option<&UserValue>,
+            operands: &[UserValue],
+        ) -> MergeResult {
+            let sum = existing_value.map(parse).unwrap_or_default()
+                + operands.iter().map(parse).sum::<i64>();
+
+            MergeResult::Success(sum.to_string().into_bytes().into())
+        }
+
+        fn partial_merge(
+            &self,
+            _key: &UserKey,
+            left: &UserValue,
+            right: &UserValue,
+        ) -> Option<UserValue> {
+            self.partial
+                .then(|| (parse(left) + parse(right)).to_string().into_bytes().into())
+        }
+    }
+
+    fn collect_items(
+        batch: WriteBatch,
+        merge_operator: Option<&dyn MergeOperator>,
+    ) -> crate::Result<Vec<(UserKey, UserValue, ValueType)>> {
+        let (items, _) = batch.into_writes(7, merge_operator)?;
+
+        Ok(items
+            .inspect(|item| assert_eq!(7, item.key.seqno))
+            .map(|item| (item.key.user_key, item.value, item.key.value_type))
+            .collect())
+    }
+
+    #[test]
+    fn write_batch_last_write_wins() -> crate::Result<()> {
+        let mut batch = WriteBatch::new();
+        batch.insert("b", "1");
+        batch.insert("a", "1");
+        batch.remove("b");
+        batch.merge("a", "2");
+        batch.insert("c", "1");
+        batch.merge("d", "1");
+        batch.insert("d", "5");
+        assert_eq!(7, batch.len());
+
+        assert_eq!(
+            vec![
+                (
+                    UserKey::from(*b"a"),
+                    UserValue::from(*b"3"),
+                    ValueType::Value
+                ),
+                (
+                    UserKey::from(*b"b"),
+                    UserValue::empty(),
+                    ValueType::Tombstone
+                ),
+                (
+                    UserKey::from(*b"c"),
+                    UserValue::from(*b"1"),
+                    ValueType::Value
+                ),
+                (
+                    UserKey::from(*b"d"),
+                    UserValue::from(*b"5"),
+                    ValueType::Value
+                ),
+            ],
+            collect_items(batch, Some(&CounterMerge { partial: false }))?,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_batch_merge_without_operator() -> crate::Result<()> {
+        let mut batch = WriteBatch::new();
+        batch.insert("a", "1");
+        batch.merge("a", "2");
+
+        assert_eq!(
+            vec![(
+                UserKey::from(*b"a"),
+                UserValue::from(*b"2"),
+                ValueType::Merge
+            )],
+            collect_items(batch, None)?,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_batch_merge_after_tombstone() -> crate::Result<()> {
+        let mut batch = WriteBatch::new();
+        batch.insert("a", "1");
+        batch.remove("a");
+        batch.merge("a", "2");
+        batch.merge("a", "3");
+
+        assert_eq!(
+            vec![(
+                UserKey::from(*b"a"),
+                UserValue::from(*b"5"),
+                ValueType::Value
+            )],
+            collect_items(batch, Some(&CounterMerge { partial: false }))?,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_batch_partial_merge_operands() -> crate::Result<()> {
+        let mut batch = WriteBatch::new();
+        batch.merge("a", "1");
+        batch.merge("a", "2");
+        batch.merge("a", "3");
+        batch.merge("b", "1");
+
+        assert_eq!(
+            vec![
+                (
+                    UserKey::from(*b"a"),
+                    UserValue::from(*b"6"),
+                    ValueType::Merge
+                ),
+                (
+                    UserKey::from(*b"b"),
+                    UserValue::from(*b"1"),
+                    ValueType::Merge
+                ),
+            ],
+            collect_items(batch, Some(&CounterMerge { partial: true }))?,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_batch_merge_operands_not_combinable() {
+        let mut batch = WriteBatch::new();
+        batch.merge("a", "1");
+        batch.merge("a", "2");
+
+        assert!(matches!(
+            collect_items(batch, Some(&CounterMerge { partial: false })),
+            Err(crate::Error::MergeFailed("CounterMerge")),
+        ));
+    }
+
+    #[test]
+    fn write_batch_remove_range_drops_previous_writes() -> crate::Result<()> {
+        let mut batch = WriteBatch::new();
+        batch.insert("a", "1");
+        batch.insert("b", "1");
+        batch.merge("c", "1");
+        batch.insert("d", "1");
+        batch.remove_range("b".."d");
+        batch.insert("c", "2");
+        assert_eq!(4, batch.len());
+        assert_eq!(8, batch.size());
+
+        let (items, range_tombstones) = batch.into_writes(7, None)?;
+        let items = items
+            .map(|item| (item.key.user_key, item.key.value_type))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (UserKey::from(*b"a"), ValueType::Value),
+                (UserKey::from(*b"c"), ValueType::Value),
+                (UserKey::from(*b"d"), ValueType::Value),
+            ],
+            items,
+        );
+        assert_eq!(1, range_tombstones.count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_batch_size() {
+        let mut batch = WriteBatch::new();
+        batch.insert("ab", "cde");
+        batch.remove("f");
+        batch.remove_range("g".."hi");
+        assert_eq!(9, batch.size());
+
+        batch.clear();
+        assert!(batch.is_empty());
+        assert_eq!(0, batch.size());
+    }
+}
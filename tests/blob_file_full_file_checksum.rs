@@ -1,4 +1,6 @@
-use lsm_tree::{get_tmp_folder, AbstractTree, Config, KvSeparationOptions, SequenceNumberCounter};
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, ChecksumType, Config, KvSeparationOptions, SequenceNumberCounter,
+};
 use test_log::test;
 use xxhash_rust::xxh3::xxh3_128;
 
@@ -119,3 +121,35 @@ fn blob_file_full_file_detect_corruption() -> lsm_tree::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn blob_file_full_file_checksum_crc32c() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    .checksum_type(ChecksumType::Crc32c)
+    .open()?;
+
+    for key in ('a'..='z').map(|c| c.to_string()) {
+        let value = nanoid::nanoid!();
+        tree.insert(key, value.as_bytes(), 0);
+    }
+    tree.flush_active_memtable(0)?;
+
+    let version = tree.current_version();
+    let blob_file = version.blob_files.iter().next().unwrap();
+
+    let expected_checksum = blob_file.checksum();
+    let real_checksum = ChecksumType::Crc32c.checksum(&std::fs::read(blob_file.path())?);
+    assert_eq!(
+        real_checksum, expected_checksum,
+        "full file checksum mismatch",
+    );
+
+    Ok(())
+}
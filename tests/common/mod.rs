@@ -0,0 +1,74 @@
+// NOTE: Every test binary compiles this module, but not every test uses every helper
+#![allow(dead_code)]
+
+use lsm_tree::{compaction::CompactionStrategy, AbstractTree, AnyTree, Guard, SeqNo};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{collections::BTreeMap, sync::Arc};
+
+pub type Model = BTreeMap<Vec<u8>, Vec<u8>>;
+
+const OPS: usize = 2_000;
+
+/// Checks that the tree contains exactly the items of the model
+pub fn assert_model(tree: &AnyTree, model: &Model) -> lsm_tree::Result<()> {
+    assert_eq!(model.len(), tree.len(SeqNo::MAX, None)?);
+
+    for (guard, (model_key, model_value)) in tree.iter(SeqNo::MAX, None).zip(model) {
+        let (key, value) = guard.into_inner()?;
+        assert_eq!(model_key, &*key);
+        assert_eq!(model_value, &*value);
+    }
+
+    for key in model.keys() {
+        assert!(tree.contains_key(key, SeqNo::MAX)?);
+    }
+
+    Ok(())
+}
+
+/// Runs random writes, flushes and compactions against the tree,
+/// and checks it against a model after every compaction
+///
+/// `after_compaction` is called after every compaction, so tests
+/// can check the shape of the tree the strategy produces.
+pub fn run_model(
+    tree: &AnyTree,
+    seed: u64,
+    compaction: Arc<dyn CompactionStrategy>,
+    mut after_compaction: impl FnMut(&AnyTree),
+) -> lsm_tree::Result<()> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut model = Model::new();
+
+    for seqno in 0..OPS as SeqNo {
+        let key = rng.random_range(0..100u8).to_be_bytes().to_vec();
+
+        match rng.random_range(0..10) {
+            0..=5 => {
+                let value = seqno.to_be_bytes().repeat(rng.random_range(1..20));
+                tree.insert(&key, &value, seqno);
+                model.insert(key, value);
+            }
+            6..=7 => {
+                tree.remove(&key, seqno);
+                model.remove(&key);
+            }
+            8 => {
+                tree.flush_active_memtable(0)?;
+            }
+            _ => {
+                tree.flush_active_memtable(0)?;
+                tree.compact(compaction.clone(), seqno)?;
+                after_compaction(tree);
+                assert_model(tree, &model)?;
+            }
+        }
+    }
+
+    tree.flush_active_memtable(0)?;
+    tree.compact(compaction, SeqNo::MAX)?;
+    after_compaction(tree);
+    assert_model(tree, &model)?;
+
+    Ok(())
+}
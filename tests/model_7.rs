@@ -0,0 +1,36 @@
+// Found by model testing
+
+use lsm_tree::{get_tmp_folder, AbstractTree, Result, SeqNo, SequenceNumberCounter};
+use test_log::test;
+
+#[test]
+fn model_7() -> Result<()> {
+    let folder = get_tmp_folder();
+
+    let path = folder.path();
+
+    let tree = lsm_tree::Config::new(
+        path,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    tree.insert(b"a", b"1", 0);
+    tree.insert(b"c", b"1", 1);
+    tree.flush_active_memtable(0)?;
+
+    tree.insert(b"b", b"2", 2);
+    tree.insert(b"d", b"old", 3);
+    tree.insert(b"z", b"2", 4);
+    tree.flush_active_memtable(0)?;
+
+    // NOTE: This table is disjoint with the first table, but overlaps the second one
+    tree.insert(b"d", b"new", 5);
+    tree.insert(b"e", b"3", 6);
+    tree.flush_active_memtable(0)?;
+
+    assert_eq!(Some(b"new".as_slice().into()), tree.get(b"d", SeqNo::MAX)?);
+
+    Ok(())
+}
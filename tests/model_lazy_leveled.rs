@@ -0,0 +1,85 @@
+mod common;
+
+use common::run_model;
+use lsm_tree::{
+    compaction::LazyLeveled, get_tmp_folder, AbstractTree, AnyTree, Config, KvSeparationOptions,
+    SequenceNumberCounter,
+};
+use std::sync::Arc;
+use test_log::test;
+
+fn compaction() -> Arc<LazyLeveled> {
+    // NOTE: Tiny tiers, so we get lots of merges across all levels
+    Arc::new(
+        LazyLeveled::default()
+            .with_l0_threshold(2)
+            .with_level_ratio(2)
+            .with_table_target_size(1_024),
+    )
+}
+
+/// Returns the highest number of runs in any level between L0 and the last level
+fn max_upper_level_run_count(tree: &AnyTree) -> usize {
+    let version = tree.current_version();
+    let last_level_idx = version.level_count() - 1;
+
+    version
+        .iter_levels()
+        .take(last_level_idx)
+        .skip(1)
+        .map(|level| level.run_count())
+        .max()
+        .unwrap_or_default()
+}
+
+#[test]
+fn model_lazy_leveled() -> lsm_tree::Result<()> {
+    for seed in 0..4 {
+        let folder = get_tmp_folder();
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        let mut max_run_count = 0;
+
+        run_model(&tree, seed, compaction(), |tree| {
+            max_run_count = max_run_count.max(max_upper_level_run_count(tree));
+        })?;
+
+        // NOTE: Upper levels are tiered, so they collect several runs
+        assert!(max_run_count > 1);
+
+        // NOTE: The last level is always a single sorted run
+        let version = tree.current_version();
+        let last_level = version.level(6).expect("last level should exist");
+        assert!(!last_level.is_empty());
+        assert!(last_level.is_disjoint());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn model_lazy_leveled_blob() -> lsm_tree::Result<()> {
+    for seed in 0..4 {
+        let folder = get_tmp_folder();
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .with_kv_separation(Some(
+            KvSeparationOptions::default().separation_threshold(32),
+        ))
+        .open()?;
+
+        run_model(&tree, seed, compaction(), |_| {})?;
+    }
+
+    Ok(())
+}
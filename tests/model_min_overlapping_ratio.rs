@@ -0,0 +1,137 @@
+mod common;
+
+use common::run_model;
+use lsm_tree::{
+    compaction::{Leveled, MoveDown},
+    get_tmp_folder, AbstractTree, AnyTree, Config, KvSeparationOptions, SequenceNumberCounter,
+};
+use std::sync::Arc;
+use test_log::test;
+
+fn compaction() -> Arc<Leveled> {
+    // NOTE: Tiny tables, so we get lots of merges across all levels
+    Arc::new(
+        Leveled::default()
+            .with_table_target_size(1_024)
+            .with_min_overlapping_ratio(true),
+    )
+}
+
+/// Writes L6 = [a00..a99, m00..m99] and L5 = [a00..a99, m50]
+///
+/// Returns the table IDs of the L5 tables.
+fn write_overlapping_ratio_tables(tree: &AnyTree) -> lsm_tree::Result<(u64, u64)> {
+    let seqno = SequenceNumberCounter::default();
+
+    for prefix in [b'a', b'm'] {
+        for k in 0..100u8 {
+            tree.insert([prefix, k], "v", seqno.next());
+        }
+        tree.flush_active_memtable(0)?;
+    }
+    tree.compact(Arc::new(MoveDown(0, 6)), 0)?;
+
+    for k in 0..100u8 {
+        tree.insert([b'a', k], "v", seqno.next());
+    }
+    tree.flush_active_memtable(0)?;
+    let large_table_id = newest_table_id(tree);
+
+    tree.insert([b'm', 50], "v", seqno.next());
+    tree.flush_active_memtable(0)?;
+    let small_table_id = newest_table_id(tree);
+
+    tree.compact(Arc::new(MoveDown(0, 5)), 0)?;
+    assert_eq!(Some(2), tree.level_table_count(5));
+
+    Ok((large_table_id, small_table_id))
+}
+
+fn newest_table_id(tree: &AnyTree) -> u64 {
+    tree.current_version()
+        .iter_tables()
+        .map(lsm_tree::Table::id)
+        .max()
+        .expect("tree should have tables")
+}
+
+fn contains_table(tree: &AnyTree, id: u64) -> bool {
+    tree.current_version()
+        .iter_tables()
+        .any(|table| table.id() == id)
+}
+
+#[test]
+fn model_min_overlapping_ratio() -> lsm_tree::Result<()> {
+    for seed in 0..4 {
+        let folder = get_tmp_folder();
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        run_model(&tree, seed, compaction(), |_| {})?;
+
+        assert!(tree
+            .current_version()
+            .iter_levels()
+            .skip(1)
+            .any(|level| !level.is_empty()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn model_min_overlapping_ratio_blob() -> lsm_tree::Result<()> {
+    for seed in 0..4 {
+        let folder = get_tmp_folder();
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .with_kv_separation(Some(
+            KvSeparationOptions::default().separation_threshold(32),
+        ))
+        .open()?;
+
+        run_model(&tree, seed, compaction(), |_| {})?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn model_min_overlapping_ratio_picked_table() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    let (large_table_id, small_table_id) = write_overlapping_ratio_tables(&tree)?;
+
+    tree.compact(
+        Arc::new(
+            Leveled::default()
+                .with_table_target_size(1)
+                .with_min_overlapping_ratio(true),
+        ),
+        0,
+    )?;
+
+    // NOTE: The large table rewrites fewer bytes per byte of its own size
+    assert!(!contains_table(&tree, large_table_id));
+    assert!(contains_table(&tree, small_table_id));
+    assert_eq!(200, tree.len(lsm_tree::SeqNo::MAX, None)?);
+
+    Ok(())
+}
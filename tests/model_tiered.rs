@@ -0,0 +1,83 @@
+mod common;
+
+use common::run_model;
+use lsm_tree::{
+    compaction::SizeTiered, get_tmp_folder, AbstractTree, Config, KvSeparationOptions,
+    SequenceNumberCounter,
+};
+use std::sync::Arc;
+use test_log::test;
+
+fn compaction() -> Arc<SizeTiered> {
+    // NOTE: Tiny tiers, so we get lots of merges across all levels
+    Arc::new(SizeTiered::new(1_024, 2))
+}
+
+#[test]
+fn model_tiered() -> lsm_tree::Result<()> {
+    for seed in 0..4 {
+        let folder = get_tmp_folder();
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        run_model(&tree, seed, compaction(), |_| {})?;
+
+        assert!(tree
+            .current_version()
+            .iter_levels()
+            .skip(1)
+            .any(|level| !level.is_empty()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn model_tiered_blob() -> lsm_tree::Result<()> {
+    for seed in 0..4 {
+        let folder = get_tmp_folder();
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .with_kv_separation(Some(
+            KvSeparationOptions::default().separation_threshold(32),
+        ))
+        .open()?;
+
+        run_model(&tree, seed, compaction(), |_| {})?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn model_tiered_config() {
+    use lsm_tree::compaction::CompactionStrategy;
+
+    let strategy = SizeTiered::new(1_024, 3);
+    let config = strategy.get_config();
+
+    assert_eq!(
+        lsm_tree::compaction::TIERED_COMPACTION_NAME,
+        strategy.get_name()
+    );
+    assert_eq!(
+        (&*config[0].0, &*config[0].1),
+        (
+            b"tiered_base_size".as_slice(),
+            1_024u32.to_le_bytes().as_slice()
+        )
+    );
+    assert_eq!(
+        (&*config[1].0, &*config[1].1),
+        (b"tiered_level_ratio".as_slice(), [3].as_slice())
+    );
+}
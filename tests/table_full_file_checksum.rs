@@ -1,4 +1,4 @@
-use lsm_tree::{get_tmp_folder, AbstractTree, Config, SequenceNumberCounter};
+use lsm_tree::{get_tmp_folder, AbstractTree, ChecksumType, Config, SequenceNumberCounter};
 use test_log::test;
 use xxhash_rust::xxh3::xxh3_128;
 
@@ -113,3 +113,34 @@ fn table_full_file_detect_corruption() -> lsm_tree::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn table_full_file_checksum_crc32c() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .checksum_type(ChecksumType::Crc32c)
+    .open()?;
+
+    for key in ('a'..='z').map(|c| c.to_string()) {
+        let value = nanoid::nanoid!();
+        tree.insert(key, value.as_bytes(), 0);
+    }
+    tree.flush_active_memtable(0)?;
+
+    let version = tree.current_version();
+    let table = version.iter_tables().next().unwrap();
+
+    let expected_checksum = table.checksum();
+    let real_checksum = ChecksumType::Crc32c.checksum(&std::fs::read(&*table.path)?);
+    assert_eq!(
+        real_checksum, expected_checksum,
+        "full file checksum mismatch",
+    );
+
+    Ok(())
+}
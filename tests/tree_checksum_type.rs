@@ -0,0 +1,199 @@
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, AnyTree, ChecksumType, Config, KvSeparationOptions, SeqNo,
+    SequenceNumberCounter,
+};
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+fn open_tree(
+    folder: &std::path::Path,
+    checksum_type: ChecksumType,
+    kv_separation: bool,
+) -> lsm_tree::Result<AnyTree> {
+    Config::new(
+        folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(
+        kv_separation.then(|| KvSeparationOptions::default().separation_threshold(1)),
+    )
+    .checksum_type(checksum_type)
+    .open()
+}
+
+fn write_items(tree: &AnyTree, seqno: SeqNo) -> lsm_tree::Result<()> {
+    for i in 0..ITEM_COUNT {
+        tree.insert(format!("{i:04}"), format!("{i}-{seqno}"), seqno);
+    }
+    tree.flush_active_memtable(0)?;
+    Ok(())
+}
+
+fn assert_items(tree: &AnyTree, seqno: SeqNo) -> lsm_tree::Result<()> {
+    assert_eq!(ITEM_COUNT, tree.len(SeqNo::MAX, None)?);
+
+    for i in 0..ITEM_COUNT {
+        assert_eq!(
+            Some(format!("{i}-{seqno}").into_bytes().into()),
+            tree.get(format!("{i:04}"), SeqNo::MAX)?,
+        );
+    }
+
+    Ok(())
+}
+
+fn table_checksum_types(tree: &AnyTree) -> Vec<ChecksumType> {
+    tree.current_version()
+        .iter_tables()
+        .map(|table| table.metadata.checksum_type)
+        .collect()
+}
+
+#[test]
+fn tree_checksum_type_crc32c() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    {
+        let tree = open_tree(folder.path(), ChecksumType::Crc32c, false)?;
+        write_items(&tree, 0)?;
+        assert_eq!(vec![ChecksumType::Crc32c], table_checksum_types(&tree));
+        assert_items(&tree, 0)?;
+    }
+
+    let tree = open_tree(folder.path(), ChecksumType::Crc32c, false)?;
+    assert_eq!(vec![ChecksumType::Crc32c], table_checksum_types(&tree));
+    assert_items(&tree, 0)?;
+
+    Ok(())
+}
+
+#[test]
+fn tree_checksum_type_mixed() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    {
+        let tree = open_tree(folder.path(), ChecksumType::Xxh3, false)?;
+        write_items(&tree, 0)?;
+    }
+
+    // NOTE: Tables written with different checksum types can live in the same tree
+    {
+        let tree = open_tree(folder.path(), ChecksumType::Crc32c, false)?;
+        assert_items(&tree, 0)?;
+
+        write_items(&tree, 1)?;
+
+        let mut checksum_types = table_checksum_types(&tree);
+        checksum_types.sort_by_key(|&checksum_type| u8::from(checksum_type));
+        assert_eq!(
+            vec![ChecksumType::Xxh3, ChecksumType::Crc32c],
+            checksum_types,
+        );
+
+        assert_items(&tree, 1)?;
+    }
+
+    let tree = open_tree(folder.path(), ChecksumType::Xxh3, false)?;
+    assert_items(&tree, 1)?;
+
+    // NOTE: Compaction rewrites the tables using the configured checksum type
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+    assert_eq!(vec![ChecksumType::Xxh3], table_checksum_types(&tree));
+    assert_items(&tree, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn tree_checksum_type_mixed_blob() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    {
+        let tree = open_tree(folder.path(), ChecksumType::Crc32c, true)?;
+        write_items(&tree, 0)?;
+        assert_eq!(1, tree.blob_file_count());
+        assert_items(&tree, 0)?;
+    }
+
+    let tree = open_tree(folder.path(), ChecksumType::Xxh3, true)?;
+    assert_items(&tree, 0)?;
+
+    write_items(&tree, 1)?;
+    assert_eq!(2, tree.blob_file_count());
+    assert_items(&tree, 1)?;
+
+    {
+        let version = tree.current_version();
+
+        let mut checksum_types = version
+            .blob_files
+            .iter()
+            .map(|blob_file| blob_file.checksum_type())
+            .collect::<Vec<_>>();
+        checksum_types.sort_by_key(|&checksum_type| u8::from(checksum_type));
+
+        assert_eq!(
+            vec![ChecksumType::Xxh3, ChecksumType::Crc32c],
+            checksum_types,
+        );
+    }
+
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+    assert_items(&tree, 1)?;
+
+    Ok(())
+}
+
+/// Writes the same items into two trees with different checksum types,
+/// then swaps a file of the first tree for the one of the second tree
+fn swap_file(subfolder: &str, kv_separation: bool) -> lsm_tree::Result<lsm_tree::Error> {
+    let folder = get_tmp_folder();
+    let other_folder = get_tmp_folder();
+
+    {
+        let tree = open_tree(folder.path(), ChecksumType::Xxh3, kv_separation)?;
+        write_items(&tree, 0)?;
+    }
+
+    {
+        let tree = open_tree(other_folder.path(), ChecksumType::Crc32c, kv_separation)?;
+        write_items(&tree, 0)?;
+    }
+
+    std::fs::copy(
+        other_folder.path().join(subfolder).join("0"),
+        folder.path().join(subfolder).join("0"),
+    )?;
+
+    Ok(open_tree(folder.path(), ChecksumType::Xxh3, kv_separation)
+        .err()
+        .expect("recovery should fail"))
+}
+
+#[test]
+fn tree_checksum_type_mismatch_table() -> lsm_tree::Result<()> {
+    assert!(matches!(
+        swap_file("tables", false)?,
+        lsm_tree::Error::ChecksumTypeMismatch {
+            got: ChecksumType::Crc32c,
+            expected: ChecksumType::Xxh3,
+        },
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn tree_checksum_type_mismatch_blob() -> lsm_tree::Result<()> {
+    assert!(matches!(
+        swap_file("blobs", true)?,
+        lsm_tree::Error::ChecksumTypeMismatch {
+            got: ChecksumType::Crc32c,
+            expected: ChecksumType::Xxh3,
+        },
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, AnyTree, Config, KvSeparationOptions, SeqNo,
+    SequenceNumberCounter,
+};
+use test_log::test;
+
+fn populate_tables(tree: &AnyTree) -> lsm_tree::Result<()> {
+    for key in 'a'..='e' {
+        tree.insert([key as u8], "", 0);
+        tree.flush_active_memtable(0)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn tree_compact_range_only_overlapping_tables() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    populate_tables(&tree)?;
+    assert_eq!(Some(5), tree.level_table_count(0));
+
+    tree.compact_range("b"..="c", u64::MAX, 0)?;
+
+    // NOTE: Only "b" and "c" were compacted down to the last level
+    assert_eq!(Some(3), tree.level_table_count(0));
+    assert_eq!(Some(1), tree.level_table_count(6));
+    assert_eq!(4, tree.table_count());
+
+    for key in 'a'..='e' {
+        assert!(tree.contains_key([key as u8], SeqNo::MAX)?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tree_compact_range_evicts_tombstones() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    for key in 'a'..='z' {
+        tree.insert([key as u8], "", 0);
+    }
+    tree.flush_active_memtable(0)?;
+
+    for key in 'f'..='k' {
+        tree.remove([key as u8], 1);
+    }
+    tree.flush_active_memtable(0)?;
+
+    assert_eq!(6, tree.tombstone_count());
+
+    tree.compact_range("f"..="k", u64::MAX, SeqNo::MAX)?;
+
+    assert_eq!(0, tree.tombstone_count());
+    assert_eq!(20, tree.len(SeqNo::MAX, None)?);
+    assert_eq!(20, tree.approximate_len());
+
+    Ok(())
+}
+
+#[test]
+fn tree_compact_range_moves_through_levels() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    tree.insert("a", "old", 0);
+    tree.insert("z", "old", 0);
+    tree.flush_active_memtable(0)?;
+    tree.compact(std::sync::Arc::new(lsm_tree::compaction::MoveDown(0, 3)), 0)?;
+
+    tree.insert("a", "new", 1);
+    tree.flush_active_memtable(0)?;
+
+    tree.compact_range("a"..="a", u64::MAX, 0)?;
+
+    // NOTE: Both tables overlap the range, so everything ends up in the last level
+    assert_eq!(1, tree.table_count());
+    assert_eq!(Some(1), tree.level_table_count(6));
+    assert_eq!(Some(b"new".into()), tree.get("a", SeqNo::MAX)?);
+    assert_eq!(Some(b"old".into()), tree.get("z", SeqNo::MAX)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_compact_range_rewrites_last_level() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    tree.insert("a", "", 0);
+    tree.flush_active_memtable(0)?;
+    tree.remove("a", 1);
+    tree.flush_active_memtable(0)?;
+    tree.compact(std::sync::Arc::new(lsm_tree::compaction::MoveDown(0, 6)), 0)?;
+
+    assert_eq!(Some(2), tree.level_table_count(6));
+
+    tree.compact_range::<&str, _>(.., u64::MAX, SeqNo::MAX)?;
+
+    assert_eq!(0, tree.table_count());
+
+    Ok(())
+}
+
+#[test]
+fn tree_compact_range_target_size() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    for x in 0u64..1_000 {
+        tree.insert(x.to_be_bytes(), "a".repeat(100), x);
+    }
+    tree.flush_active_memtable(0)?;
+
+    tree.compact_range::<&[u8], _>(.., 10_000, SeqNo::MAX)?;
+
+    assert_eq!(Some(0), tree.level_table_count(0));
+    assert!(tree.level_table_count(6).unwrap_or_default() > 1);
+    assert_eq!(1_000, tree.len(SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_compact_range_empty_range() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    populate_tables(&tree)?;
+
+    tree.compact_range("c"..="a", u64::MAX, 0)?;
+
+    assert_eq!(Some(5), tree.level_table_count(0));
+
+    Ok(())
+}
+
+#[test]
+fn tree_compact_range_blob() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    .open()?;
+
+    for key in 'a'..='e' {
+        tree.insert([key as u8], "blob value", 0);
+        tree.flush_active_memtable(0)?;
+    }
+
+    tree.compact_range("a"..="b", u64::MAX, 0)?;
+
+    assert_eq!(Some(3), tree.level_table_count(0));
+    assert_eq!(Some(1), tree.level_table_count(6));
+
+    for key in 'a'..='e' {
+        assert_eq!(
+            Some(b"blob value".into()),
+            tree.get([key as u8], SeqNo::MAX)?
+        );
+    }
+
+    Ok(())
+}
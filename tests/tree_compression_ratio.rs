@@ -0,0 +1,116 @@
+#![cfg(feature = "lz4")]
+
+use lsm_tree::{
+    config::{CompressionPolicy, CompressionRatioPolicy},
+    get_tmp_folder, AbstractTree, AnyTree, CompressionType, Config, SeqNo, SequenceNumberCounter,
+};
+use rand::RngCore;
+use test_log::test;
+
+const ITEM_COUNT: usize = 1_000;
+
+fn open_tree(folder: &std::path::Path, min_ratio: f32) -> lsm_tree::Result<AnyTree> {
+    Config::new(
+        folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .data_block_compression_policy(CompressionPolicy::all(CompressionType::Lz4))
+    .data_block_compression_ratio_policy(CompressionRatioPolicy::all(min_ratio))
+    .open()
+}
+
+fn write_items(tree: &AnyTree, value: impl Fn(usize) -> Vec<u8>) -> lsm_tree::Result<()> {
+    for i in 0..ITEM_COUNT {
+        tree.insert(format!("{i:08}"), value(i), 0);
+    }
+    tree.flush_active_memtable(0)?;
+    Ok(())
+}
+
+fn assert_items(tree: &AnyTree, value: impl Fn(usize) -> Vec<u8>) -> lsm_tree::Result<()> {
+    for i in 0..ITEM_COUNT {
+        assert_eq!(
+            Some(value(i).into()),
+            tree.get(format!("{i:08}"), SeqNo::MAX)?,
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn tree_compression_ratio_compressible() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let tree = open_tree(folder.path(), 1.0)?;
+
+    let value = |i: usize| format!("value-{i}-").repeat(10).into_bytes();
+    write_items(&tree, value)?;
+
+    let ratio = tree.level_compression_ratio(0).expect("level should exist");
+    assert!(ratio > 2.0, "compression ratio should be >2.0, got {ratio}");
+
+    let version = tree.current_version();
+    let table = version.iter_tables().next().expect("table should exist");
+    assert!(
+        (table.compression_ratio().expect("ratio should be known") - ratio).abs() < f32::EPSILON
+    );
+
+    assert_items(&tree, value)?;
+
+    Ok(())
+}
+
+#[test]
+fn tree_compression_ratio_incompressible() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let tree = open_tree(folder.path(), 1.0)?;
+
+    let values = (0..ITEM_COUNT)
+        .map(|_| {
+            let mut value = vec![0; 100];
+            rand::rng().fill_bytes(&mut value);
+            value
+        })
+        .collect::<Vec<_>>();
+
+    let value = |i: usize| values.get(i).cloned().expect("value should exist");
+    write_items(&tree, value)?;
+
+    // NOTE: Random values barely compress, but blocks never take more space than uncompressed
+    let ratio = tree.level_compression_ratio(0).expect("level should exist");
+    assert!(
+        (1.0..1.1).contains(&ratio),
+        "compression ratio should be ~1.0, got {ratio}",
+    );
+
+    assert_items(&tree, value)?;
+
+    Ok(())
+}
+
+#[test]
+fn tree_compression_ratio_min_ratio() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let value = |i: usize| format!("value-{i}-").repeat(10).into_bytes();
+
+    {
+        let tree = open_tree(folder.path(), 1_000.0)?;
+        write_items(&tree, value)?;
+
+        // NOTE: The blocks compress, but not enough to reach the minimum ratio
+        assert_eq!(Some(1.0), tree.level_compression_ratio(0));
+        assert_eq!(None, tree.level_compression_ratio(1));
+        assert_eq!(None, tree.level_compression_ratio(7));
+
+        assert_items(&tree, value)?;
+    }
+
+    // NOTE: Uncompressed blocks are marked in the block header,
+    // so they stay readable with a different configuration
+    let tree = open_tree(folder.path(), 1.0)?;
+    assert_eq!(Some(1.0), tree.level_compression_ratio(0));
+    assert_items(&tree, value)?;
+
+    Ok(())
+}
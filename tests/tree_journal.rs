@@ -0,0 +1,208 @@
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, AnyTree, Config, JournalSyncPolicy, KvSeparationOptions,
+    MergeOperator, MergeResult, SeqNo, SequenceNumberCounter, UserKey, UserValue, WriteBatch,
+};
+use std::{path::Path, sync::Arc};
+use test_log::test;
+
+struct ConcatMerge;
+
+impl MergeOperator for ConcatMerge {
+    fn name(&self) -> &'static str {
+        "concat"
+    }
+
+    fn full_merge(
+        &self,
+        _key: &UserKey,
+        base_value: Option<&UserValue>,
+        operands: &[UserValue],
+    ) -> MergeResult {
+        let mut value = base_value.map(|x| x.to_vec()).unwrap_or_default();
+        for operand in operands {
+            value.extend_from_slice(operand);
+        }
+        MergeResult::Success(value.into())
+    }
+}
+
+fn open(path: &Path, sync_policy: Option<JournalSyncPolicy>) -> lsm_tree::Result<AnyTree> {
+    Config::new(
+        path,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_journal(sync_policy)
+    .merge_operator(Some(Arc::new(ConcatMerge)))
+    .open()
+}
+
+fn journal_count(path: &Path) -> lsm_tree::Result<usize> {
+    Ok(std::fs::read_dir(path.join("journals"))?.count())
+}
+
+#[test]
+fn tree_journal_recover() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    for sync_policy in [
+        JournalSyncPolicy::PerWrite,
+        JournalSyncPolicy::PerBatch,
+        JournalSyncPolicy::Never,
+    ] {
+        let folder = folder.path().join(format!("{sync_policy:?}"));
+
+        {
+            let tree = open(&folder, Some(sync_policy))?;
+
+            tree.insert("a", "abc", 0);
+            tree.insert("b", "abc", 1);
+            tree.remove("b", 2);
+            tree.insert("c", "abc", 3);
+            tree.remove_weak("c", 4);
+            tree.merge("d", "1", 5);
+            tree.merge("d", "2", 6);
+            tree.insert("e", "abc", 7);
+            tree.insert("f", "abc", 8);
+            tree.remove_range("e".."f", 9);
+
+            let mut batch = WriteBatch::new();
+            batch.insert("g", "def");
+            batch.merge("d", "3");
+            batch.remove("a");
+            tree.apply_batch(batch, 10)?;
+
+            tree.sync_journal()?;
+        }
+
+        let tree = open(&folder, Some(sync_policy))?;
+
+        assert_eq!(Some(10), tree.get_highest_seqno());
+        assert_eq!(None, tree.get("a", SeqNo::MAX)?);
+        assert_eq!(Some("abc".as_bytes().into()), tree.get("a", 10)?);
+        assert_eq!(None, tree.get("b", SeqNo::MAX)?);
+        assert_eq!(None, tree.get("c", SeqNo::MAX)?);
+        assert_eq!(Some("123".as_bytes().into()), tree.get("d", SeqNo::MAX)?);
+        assert_eq!(None, tree.get("e", SeqNo::MAX)?);
+        assert_eq!(Some("abc".as_bytes().into()), tree.get("f", SeqNo::MAX)?);
+        assert_eq!(Some("def".as_bytes().into()), tree.get("g", SeqNo::MAX)?);
+        assert_eq!(3, tree.len(SeqNo::MAX, None)?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tree_journal_rotate_and_flush() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    {
+        let tree = open(folder.path(), Some(JournalSyncPolicy::PerWrite))?;
+        assert_eq!(1, journal_count(folder.path())?);
+
+        tree.insert("a", "abc", 0);
+        tree.flush_active_memtable(0)?;
+
+        // NOTE: The journal of the flushed memtable is deleted
+        assert_eq!(1, journal_count(folder.path())?);
+
+        tree.insert("b", "abc", 1);
+        tree.rotate_memtable();
+        tree.insert("c", "abc", 2);
+        assert_eq!(2, journal_count(folder.path())?);
+    }
+
+    {
+        let tree = open(folder.path(), Some(JournalSyncPolicy::PerWrite))?;
+
+        // NOTE: Sealed and active memtable are replayed into a single memtable
+        assert_eq!(1, journal_count(folder.path())?);
+        assert_eq!(1, tree.table_count());
+        assert_eq!(2, tree.active_memtable().len());
+        assert_eq!(3, tree.len(SeqNo::MAX, None)?);
+
+        tree.flush_active_memtable(0)?;
+        assert_eq!(0, tree.active_memtable().len());
+    }
+
+    let tree = open(folder.path(), Some(JournalSyncPolicy::PerWrite))?;
+    assert_eq!(1, journal_count(folder.path())?);
+    assert_eq!(0, tree.active_memtable().len());
+    assert_eq!(3, tree.len(SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_journal_clear() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    {
+        let tree = open(folder.path(), Some(JournalSyncPolicy::PerWrite))?;
+
+        tree.insert("a", "abc", 0);
+        tree.rotate_memtable();
+        tree.insert("b", "abc", 1);
+
+        tree.clear()?;
+        assert_eq!(1, journal_count(folder.path())?);
+
+        tree.insert("c", "abc", 2);
+    }
+
+    let tree = open(folder.path(), Some(JournalSyncPolicy::PerWrite))?;
+    assert_eq!(1, tree.len(SeqNo::MAX, None)?);
+    assert!(tree.contains_key("c", SeqNo::MAX)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_journal_disabled() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    {
+        let tree = open(folder.path(), None)?;
+        tree.insert("a", "abc", 0);
+        tree.sync_journal()?;
+    }
+
+    assert!(!folder.path().join("journals").try_exists()?);
+
+    let tree = open(folder.path(), None)?;
+    assert!(tree.is_empty(SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn blob_tree_journal_recover() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let config = || {
+        Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+        .with_journal(Some(JournalSyncPolicy::PerWrite))
+    };
+
+    {
+        let tree = config().open()?;
+        tree.insert("a", "abc", 0);
+        tree.flush_active_memtable(0)?;
+        tree.insert("b", "def", 1);
+    }
+
+    let tree = config().open()?;
+    assert_eq!(Some("abc".as_bytes().into()), tree.get("a", SeqNo::MAX)?);
+    assert_eq!(Some("def".as_bytes().into()), tree.get("b", SeqNo::MAX)?);
+
+    tree.flush_active_memtable(0)?;
+    assert_eq!(2, tree.blob_file_count());
+    assert_eq!(Some("def".as_bytes().into()), tree.get("b", SeqNo::MAX)?);
+
+    Ok(())
+}
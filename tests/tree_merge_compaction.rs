@@ -0,0 +1,199 @@
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, Config, KvSeparationOptions, MergeOperator, MergeResult, SeqNo,
+    SequenceNumberCounter, UserKey, UserValue,
+};
+use std::sync::Arc;
+use test_log::test;
+
+struct CounterMerge;
+
+impl MergeOperator for CounterMerge {
+    fn name(&self) -> &'static str {
+        "CounterMerge"
+    }
+
+    fn full_merge(
+        &self,
+        _key: &UserKey,
+        existing_value: Option<&UserValue>,
+        operands: &[UserValue],
+    ) -> MergeResult {
+        let mut counter = existing_value
+            .map(|v| u64::from_be_bytes((**v).try_into().unwrap()))
+            .unwrap_or_default();
+
+        for operand in operands {
+            counter += u64::from_be_bytes((**operand).try_into().unwrap());
+        }
+
+        MergeResult::Success(counter.to_be_bytes().into())
+    }
+
+    fn partial_merge(
+        &self,
+        _key: &UserKey,
+        left: &UserValue,
+        right: &UserValue,
+    ) -> Option<UserValue> {
+        let left = u64::from_be_bytes((**left).try_into().unwrap());
+        let right = u64::from_be_bytes((**right).try_into().unwrap());
+        Some((left + right).to_be_bytes().into())
+    }
+}
+
+fn counter(v: &[u8]) -> u64 {
+    u64::from_be_bytes(v.try_into().unwrap())
+}
+
+fn table_item_count(tree: &impl AbstractTree) -> u64 {
+    tree.current_version()
+        .iter_tables()
+        .map(|t| t.metadata.item_count)
+        .sum()
+}
+
+#[test]
+fn tree_merge_flush_fold() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    tree.insert("a", 5u64.to_be_bytes(), 0);
+
+    for seqno in 1..=10 {
+        tree.merge("a", 1u64.to_be_bytes(), seqno);
+    }
+
+    tree.flush_active_memtable(SeqNo::MAX)?;
+    assert_eq!(1, table_item_count(&tree));
+    assert_eq!(15, counter(&tree.get("a", SeqNo::MAX)?.unwrap()));
+
+    Ok(())
+}
+
+#[test]
+fn tree_merge_flush_snapshot() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    tree.insert("a", 5u64.to_be_bytes(), 0);
+
+    for seqno in 1..=10 {
+        tree.merge("a", 1u64.to_be_bytes(), seqno);
+    }
+
+    // NOTE: Operands 7..=10 stay visible to snapshots,
+    // everything at and below seqno 6 is folded into one value
+    tree.flush_active_memtable(6)?;
+    assert_eq!(5, table_item_count(&tree));
+
+    assert_eq!(15, counter(&tree.get("a", SeqNo::MAX)?.unwrap()));
+    assert_eq!(11, counter(&tree.get("a", 7)?.unwrap()));
+
+    Ok(())
+}
+
+#[test]
+fn tree_merge_flush_partial() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    tree.insert("a", 5u64.to_be_bytes(), 0);
+    tree.flush_active_memtable(0)?;
+
+    for seqno in 1..=10 {
+        tree.merge("a", 1u64.to_be_bytes(), seqno);
+    }
+
+    // NOTE: The base value is in another table, so the operands
+    // can only be combined with each other
+    tree.flush_active_memtable(SeqNo::MAX)?;
+    assert_eq!(2, tree.table_count());
+    assert_eq!(2, table_item_count(&tree));
+    assert_eq!(15, counter(&tree.get("a", SeqNo::MAX)?.unwrap()));
+
+    Ok(())
+}
+
+#[test]
+fn tree_merge_compaction_fold() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    for seqno in 0..10 {
+        tree.merge("a", 1u64.to_be_bytes(), seqno);
+        tree.flush_active_memtable(0)?;
+    }
+
+    assert_eq!(10, tree.table_count());
+    assert_eq!(10, table_item_count(&tree));
+    assert_eq!(10, counter(&tree.get("a", SeqNo::MAX)?.unwrap()));
+
+    // NOTE: Major compaction writes into the last level, so
+    // there cannot be a base value below the operands
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+    assert_eq!(1, tree.table_count());
+    assert_eq!(1, table_item_count(&tree));
+    assert_eq!(10, counter(&tree.get("a", SeqNo::MAX)?.unwrap()));
+
+    Ok(())
+}
+
+#[test]
+fn blob_tree_merge_flush_fold() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    tree.merge("a", 1u64.to_be_bytes(), 0);
+    tree.merge("a", 1u64.to_be_bytes(), 1);
+
+    // NOTE: Operands are never separated, so they stay readable
+    tree.flush_active_memtable(0)?;
+    assert_eq!(0, tree.blob_file_count());
+    assert_eq!(2, table_item_count(&tree));
+
+    tree.insert("b", 5u64.to_be_bytes(), 2);
+    tree.merge("b", 1u64.to_be_bytes(), 3);
+
+    // NOTE: The folded value is a regular value, so it is separated
+    tree.flush_active_memtable(SeqNo::MAX)?;
+    assert_eq!(1, tree.blob_file_count());
+    assert_eq!(6, counter(&tree.get("b", SeqNo::MAX)?.unwrap()));
+
+    Ok(())
+}
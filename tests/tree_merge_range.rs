@@ -0,0 +1,215 @@
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, Config, Guard, KvSeparationOptions, MergeOperator, MergeResult,
+    SeqNo, SequenceNumberCounter, UserKey, UserValue,
+};
+use std::sync::Arc;
+use test_log::test;
+
+struct CounterMerge;
+
+impl MergeOperator for CounterMerge {
+    fn name(&self) -> &'static str {
+        "CounterMerge"
+    }
+
+    fn full_merge(
+        &self,
+        _key: &UserKey,
+        existing_value: Option<&UserValue>,
+        operands: &[UserValue],
+    ) -> MergeResult {
+        let mut counter = existing_value
+            .map(|v| u64::from_be_bytes((**v).try_into().unwrap()))
+            .unwrap_or_default();
+
+        for operand in operands {
+            counter += u64::from_be_bytes((**operand).try_into().unwrap());
+        }
+
+        MergeResult::Success(counter.to_be_bytes().into())
+    }
+}
+
+fn counter(v: &[u8]) -> u64 {
+    u64::from_be_bytes(v.try_into().unwrap())
+}
+
+#[test]
+fn tree_merge_range() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    tree.insert("a", 10u64.to_be_bytes(), 0);
+    tree.merge("a", 1u64.to_be_bytes(), 1);
+    tree.merge("b", 5u64.to_be_bytes(), 2);
+    tree.insert("c", 3u64.to_be_bytes(), 3);
+
+    tree.rotate_memtable();
+
+    tree.merge("a", 1u64.to_be_bytes(), 4);
+    tree.merge("b", 5u64.to_be_bytes(), 5);
+    tree.merge("c", 4u64.to_be_bytes(), 6);
+
+    let expected = [(b"a", 12), (b"b", 10), (b"c", 7)];
+
+    for (key, value) in expected {
+        assert_eq!(value, counter(&tree.get(key, SeqNo::MAX)?.unwrap()));
+    }
+
+    let items = tree
+        .iter(SeqNo::MAX, None)
+        .map(|x| x.into_inner().map(|(k, v)| (k, counter(&v))))
+        .collect::<lsm_tree::Result<Vec<_>>>()?;
+
+    assert_eq!(
+        expected
+            .iter()
+            .map(|(k, v)| (UserKey::from(&k[..]), *v))
+            .collect::<Vec<_>>(),
+        items,
+    );
+
+    let items = tree
+        .iter(SeqNo::MAX, None)
+        .rev()
+        .map(|x| x.into_inner().map(|(k, v)| (k, counter(&v))))
+        .collect::<lsm_tree::Result<Vec<_>>>()?;
+
+    assert_eq!(
+        expected
+            .iter()
+            .rev()
+            .map(|(k, v)| (UserKey::from(&k[..]), *v))
+            .collect::<Vec<_>>(),
+        items,
+    );
+
+    let (_, v) = tree
+        .range("b"..="c", SeqNo::MAX, None)
+        .next()
+        .unwrap()
+        .into_inner()?;
+    assert_eq!(10, counter(&v));
+
+    let (_, v) = tree
+        .prefix("c", SeqNo::MAX, None)
+        .next_back()
+        .unwrap()
+        .into_inner()?;
+    assert_eq!(7, counter(&v));
+
+    Ok(())
+}
+
+#[test]
+fn tree_merge_range_snapshot() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    tree.merge("a", 1u64.to_be_bytes(), 0);
+    tree.merge("a", 1u64.to_be_bytes(), 1);
+    tree.remove("a", 2);
+    tree.merge("a", 1u64.to_be_bytes(), 3);
+
+    for (seqno, value) in [(1, Some(1)), (2, Some(2)), (3, None), (4, Some(1))] {
+        assert_eq!(
+            value,
+            tree.get("a", seqno)?.map(|v| counter(&v)),
+            "get at seqno={seqno}",
+        );
+
+        for item in [
+            tree.iter(seqno, None).next(),
+            tree.iter(seqno, None).next_back(),
+        ] {
+            assert_eq!(
+                value,
+                item.map(|x| x.into_inner())
+                    .transpose()?
+                    .map(|(_, v)| counter(&v)),
+                "iter at seqno={seqno}",
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tree_merge_range_blob() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(Some(KvSeparationOptions::default()))
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    tree.insert("a", 10u64.to_be_bytes(), 0);
+    tree.merge("a", 1u64.to_be_bytes(), 1);
+    tree.merge("b", 5u64.to_be_bytes(), 2);
+    tree.flush_active_memtable(0)?;
+
+    tree.merge("a", 1u64.to_be_bytes(), 3);
+    tree.merge("b", 5u64.to_be_bytes(), 4);
+
+    let expected = [(b"a", 12), (b"b", 10)];
+
+    for (key, value) in expected {
+        assert_eq!(value, counter(&tree.get(key, SeqNo::MAX)?.unwrap()));
+    }
+
+    assert_eq!(
+        vec![Some(12), Some(10)],
+        tree.multi_get(["a", "b"], SeqNo::MAX)?
+            .iter()
+            .map(|v| v.as_deref().map(counter))
+            .collect::<Vec<_>>(),
+    );
+
+    let items = tree
+        .iter(SeqNo::MAX, None)
+        .map(|x| x.into_inner().map(|(k, v)| (k, counter(&v))))
+        .collect::<lsm_tree::Result<Vec<_>>>()?;
+
+    assert_eq!(
+        expected
+            .iter()
+            .map(|(k, v)| (UserKey::from(&k[..]), *v))
+            .collect::<Vec<_>>(),
+        items,
+    );
+
+    let (_, v) = tree
+        .range("b"..="c", SeqNo::MAX, None)
+        .next_back()
+        .unwrap()
+        .into_inner()?;
+    assert_eq!(10, counter(&v));
+
+    let (_, v) = tree
+        .prefix("a", SeqNo::MAX, None)
+        .next()
+        .unwrap()
+        .into_inner()?;
+    assert_eq!(12, counter(&v));
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, AnyTree, Config, KvSeparationOptions, MergeOperator, MergeResult,
+    SeqNo, SequenceNumberCounter, UserKey, UserValue,
+};
+use std::sync::Arc;
+use test_log::test;
+
+struct ConcatMerge;
+
+impl MergeOperator for ConcatMerge {
+    fn name(&self) -> &'static str {
+        "concat"
+    }
+
+    fn full_merge(
+        &self,
+        _key: &UserKey,
+        base_value: Option<&UserValue>,
+        operands: &[UserValue],
+    ) -> MergeResult {
+        let mut value = base_value.map(|x| x.to_vec()).unwrap_or_default();
+        for operand in operands {
+            value.extend_from_slice(operand);
+        }
+        MergeResult::Success(value.into())
+    }
+}
+
+/// Spreads items over tables, sealed memtables and the active memtable
+fn fill(tree: &AnyTree, seqno: &SequenceNumberCounter) -> lsm_tree::Result<()> {
+    for x in 0..1_000u64 {
+        tree.insert(x.to_be_bytes(), x.to_string().repeat(10), seqno.next());
+    }
+    tree.flush_active_memtable(0)?;
+
+    for x in (0..1_000u64).step_by(7) {
+        tree.insert(x.to_be_bytes(), "newer", seqno.next());
+    }
+    for x in (0..1_000u64).step_by(11) {
+        tree.remove(x.to_be_bytes(), seqno.next());
+    }
+    tree.flush_active_memtable(0)?;
+
+    tree.remove_range(300u64.to_be_bytes()..350u64.to_be_bytes(), seqno.next());
+    for x in (0..1_000u64).step_by(13) {
+        tree.merge(x.to_be_bytes(), "+m", seqno.next());
+    }
+    tree.rotate_memtable();
+
+    for x in (500..1_200u64).step_by(3) {
+        tree.insert(x.to_be_bytes(), "active", seqno.next());
+    }
+
+    Ok(())
+}
+
+fn check_against_get(tree: &AnyTree, seqno: SeqNo) -> lsm_tree::Result<()> {
+    // NOTE: Unsorted, with duplicates and missing keys
+    let keys = (0..1_300u64)
+        .rev()
+        .chain([5, 5, 999, 0])
+        .map(u64::to_be_bytes)
+        .collect::<Vec<_>>();
+
+    let values = tree.multi_get(&keys, seqno)?;
+    assert_eq!(keys.len(), values.len());
+
+    for (key, value) in keys.iter().zip(values) {
+        assert_eq!(tree.get(key, seqno)?, value, "mismatch for key {key:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tree_multi_get() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = Config::new(&folder, seqno.clone(), SequenceNumberCounter::default()).open()?;
+    tree.set_merge_operator(Some(Arc::new(ConcatMerge)));
+
+    fill(&tree, &seqno)?;
+
+    check_against_get(&tree, SeqNo::MAX)?;
+    check_against_get(&tree, 1_500)?;
+    check_against_get(&tree, 500)?;
+
+    tree.major_compact(8_000, 0)?;
+    check_against_get(&tree, SeqNo::MAX)?;
+
+    Ok(())
+}
+
+#[test]
+fn tree_multi_get_empty() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    assert!(tree.multi_get(Vec::<&[u8]>::new(), SeqNo::MAX)?.is_empty());
+    assert_eq!(vec![None, None], tree.multi_get(["a", "b"], SeqNo::MAX)?);
+
+    Ok(())
+}
+
+#[test]
+fn blob_tree_multi_get() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = Config::new(&folder, seqno.clone(), SequenceNumberCounter::default())
+        .with_kv_separation(Some(
+            KvSeparationOptions::default().separation_threshold(20),
+        ))
+        .open()?;
+
+    fill(&tree, &seqno)?;
+
+    check_against_get(&tree, SeqNo::MAX)?;
+    check_against_get(&tree, 500)?;
+
+    tree.major_compact(8_000, 0)?;
+    check_against_get(&tree, SeqNo::MAX)?;
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn tree_multi_get_shares_block_loads() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = Config::new(&folder, seqno.clone(), SequenceNumberCounter::default()).open()?;
+
+    for x in 0..1_000u64 {
+        tree.insert(x.to_be_bytes(), "abc", seqno.next());
+    }
+    tree.flush_active_memtable(0)?;
+
+    let keys = (0..1_000u64).map(u64::to_be_bytes).collect::<Vec<_>>();
+
+    let before = tree.metrics().data_block_load_count();
+    assert!(tree
+        .multi_get(&keys, SeqNo::MAX)?
+        .iter()
+        .all(Option::is_some));
+    let loads = tree.metrics().data_block_load_count() - before;
+
+    let before = tree.metrics().data_block_load_count();
+    for key in &keys {
+        assert!(tree.get(key, SeqNo::MAX)?.is_some());
+    }
+    let single_loads = tree.metrics().data_block_load_count() - before;
+
+    assert!(loads < single_loads / 10, "{loads} >= {single_loads} / 10");
+
+    Ok(())
+}
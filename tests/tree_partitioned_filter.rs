@@ -0,0 +1,71 @@
+use lsm_tree::{
+    config::PinningPolicy, get_tmp_folder, AbstractTree, Config, SeqNo, SequenceNumberCounter,
+};
+use test_log::test;
+
+const ITEM_COUNT: u64 = 10_000;
+
+fn partitioned_filter_config(
+    path: &std::path::Path,
+    seqno: SequenceNumberCounter,
+    pin_tli: bool,
+) -> Config {
+    Config::new(path, seqno, SequenceNumberCounter::default())
+        .filter_block_partitioning_policy(PinningPolicy::all(true))
+        .filter_block_pinning_policy(PinningPolicy::all(false))
+        .top_level_filter_block_pinning_policy(PinningPolicy::all(pin_tli))
+}
+
+fn point_reads(tree: &impl AbstractTree) -> lsm_tree::Result<()> {
+    for k in 0..ITEM_COUNT {
+        assert!(tree.get((k * 2).to_be_bytes(), SeqNo::MAX)?.is_some());
+        assert!(tree.get((k * 2 + 1).to_be_bytes(), SeqNo::MAX)?.is_none());
+    }
+    Ok(())
+}
+
+#[test]
+fn tree_partitioned_filter_unpinned_tli() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    {
+        let tree = partitioned_filter_config(folder.path(), seqno.clone(), false).open()?;
+
+        for k in 0..ITEM_COUNT {
+            tree.insert((k * 2).to_be_bytes(), "abc", seqno.next());
+        }
+        tree.flush_active_memtable(0)?;
+
+        point_reads(&tree)?;
+
+        tree.major_compact(8_000, 0)?;
+
+        point_reads(&tree)?;
+    }
+
+    let tree = partitioned_filter_config(folder.path(), seqno.clone(), false).open()?;
+    point_reads(&tree)?;
+
+    #[cfg(feature = "metrics")]
+    assert!(tree.metrics().filter_efficiency() > 0.4);
+
+    Ok(())
+}
+
+#[test]
+fn tree_partitioned_filter_pinned_tli() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = partitioned_filter_config(folder.path(), seqno.clone(), true).open()?;
+
+    for k in 0..ITEM_COUNT {
+        tree.insert((k * 2).to_be_bytes(), "abc", seqno.next());
+    }
+    tree.flush_active_memtable(0)?;
+
+    point_reads(&tree)?;
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use lsm_tree::{
+    config::{BloomConstructionPolicy, FilterPolicy, FilterPolicyEntry},
+    get_tmp_folder, AbstractTree, Config, FixedPrefixExtractor, Guard, KvSeparationOptions, SeqNo,
+    SequenceNumberCounter,
+};
+use std::sync::Arc;
+use test_log::test;
+
+const TENANT_COUNT: u64 = 10;
+const ITEMS_PER_TENANT: u64 = 100;
+
+fn tenant_key(tenant: u64, item: u64) -> Vec<u8> {
+    format!("t{tenant:03}:{item:05}").into_bytes()
+}
+
+fn prefix_bloom_config(path: &std::path::Path, seqno: SequenceNumberCounter) -> Config {
+    Config::new(path, seqno, SequenceNumberCounter::default())
+        .filter_policy(FilterPolicy::all(FilterPolicyEntry::PrefixBloom(
+            BloomConstructionPolicy::BitsPerKey(10.0),
+        )))
+        .prefix_extractor(Some(Arc::new(FixedPrefixExtractor(4))))
+}
+
+/// Writes every tenant into its own table
+fn fill_tenants(tree: &impl AbstractTree, seqno: &SequenceNumberCounter) -> lsm_tree::Result<()> {
+    for tenant in 0..TENANT_COUNT {
+        for item in 0..ITEMS_PER_TENANT {
+            tree.insert(tenant_key(tenant, item), "abc", seqno.next());
+        }
+        tree.flush_active_memtable(0)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn tree_prefix_bloom_scan() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = prefix_bloom_config(folder.path(), seqno.clone()).open()?;
+    fill_tenants(&tree, &seqno)?;
+    assert_eq!(TENANT_COUNT as usize, tree.table_count());
+
+    for tenant in 0..TENANT_COUNT {
+        let prefix = format!("t{tenant:03}");
+
+        let keys = tree
+            .prefix(&prefix, SeqNo::MAX, None)
+            .map(|guard| guard.key())
+            .collect::<lsm_tree::Result<Vec<_>>>()?;
+
+        let expected = (0..ITEMS_PER_TENANT)
+            .map(|item| tenant_key(tenant, item))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            expected,
+            keys.iter().map(|k| k.to_vec()).collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            ITEMS_PER_TENANT as usize,
+            tree.prefix(&prefix, SeqNo::MAX, None).rev().count(),
+        );
+    }
+
+    // Longer prefix still extracts to the tenant
+    assert_eq!(10, tree.prefix("t005:0001", SeqNo::MAX, None).count());
+
+    // Shorter prefix cannot be filtered, so it needs to scan everything
+    assert_eq!(
+        (TENANT_COUNT * ITEMS_PER_TENANT) as usize,
+        tree.prefix("t", SeqNo::MAX, None).count(),
+    );
+
+    assert_eq!(0, tree.prefix("t999", SeqNo::MAX, None).count());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn tree_prefix_bloom_skips_tables() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = prefix_bloom_config(folder.path(), seqno.clone()).open()?;
+
+    // NOTE: Make all tables overlap, so only the filter can skip them
+    for tenant in 0..TENANT_COUNT {
+        for item in 0..ITEMS_PER_TENANT {
+            tree.insert(tenant_key(tenant, item), "abc", seqno.next());
+        }
+        tree.insert("a", "abc", seqno.next());
+        tree.insert("z", "abc", seqno.next());
+        tree.flush_active_memtable(0)?;
+    }
+
+    assert_eq!(
+        ITEMS_PER_TENANT as usize,
+        tree.prefix("t003", SeqNo::MAX, None).count(),
+    );
+
+    // 9 out of 10 tables should have been skipped (minus false positives)
+    assert!(tree.metrics().filter_efficiency() > 0.7);
+
+    Ok(())
+}
+
+#[test]
+fn tree_prefix_bloom_sealed_memtable() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = prefix_bloom_config(folder.path(), seqno.clone()).open()?;
+    fill_tenants(&tree, &seqno)?;
+
+    tree.insert(tenant_key(3, 999), "abc", seqno.next());
+    tree.rotate_memtable();
+    tree.insert(tenant_key(4, 999), "abc", seqno.next());
+    tree.rotate_memtable();
+    tree.insert(tenant_key(3, 1_000), "abc", seqno.next());
+
+    assert_eq!(
+        ITEMS_PER_TENANT as usize + 2,
+        tree.prefix("t003", SeqNo::MAX, None).count(),
+    );
+    assert_eq!(
+        ITEMS_PER_TENANT as usize + 1,
+        tree.prefix("t004", SeqNo::MAX, None).count(),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tree_prefix_bloom_compaction() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = prefix_bloom_config(folder.path(), seqno.clone()).open()?;
+    fill_tenants(&tree, &seqno)?;
+
+    tree.major_compact(4_096, 0)?;
+    assert!(tree.table_count() > 1);
+
+    for tenant in 0..TENANT_COUNT {
+        assert_eq!(
+            ITEMS_PER_TENANT as usize,
+            tree.prefix(format!("t{tenant:03}"), SeqNo::MAX, None)
+                .count(),
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tree_prefix_bloom_extractor_changed() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    {
+        let tree = prefix_bloom_config(folder.path(), seqno.clone()).open()?;
+        fill_tenants(&tree, &seqno)?;
+    }
+
+    // NOTE: Tables were written using another extractor, so they must not be filtered
+    let tree = prefix_bloom_config(folder.path(), seqno.clone())
+        .prefix_extractor(Some(Arc::new(FixedPrefixExtractor(2))))
+        .open()?;
+
+    assert_eq!(
+        (TENANT_COUNT * ITEMS_PER_TENANT) as usize,
+        tree.prefix("t0", SeqNo::MAX, None).count(),
+    );
+    assert_eq!(
+        ITEMS_PER_TENANT as usize,
+        tree.prefix("t007", SeqNo::MAX, None).count(),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn blob_tree_prefix_bloom_scan() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = prefix_bloom_config(folder.path(), seqno.clone())
+        .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+        .open()?;
+    fill_tenants(&tree, &seqno)?;
+
+    for tenant in 0..TENANT_COUNT {
+        let items = tree
+            .prefix(format!("t{tenant:03}"), SeqNo::MAX, None)
+            .map(|guard| guard.into_inner())
+            .collect::<lsm_tree::Result<Vec<_>>>()?;
+
+        assert_eq!(ITEMS_PER_TENANT as usize, items.len());
+        assert!(items.iter().all(|(_, v)| &**v == b"abc"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,348 @@
+use lsm_tree::{
+    config::CompressionPolicy, get_tmp_folder, AbstractTree, Config, Guard, KvSeparationOptions,
+    MergeOperator, MergeResult, SeqNo, SequenceNumberCounter, UserKey, UserValue,
+};
+use std::sync::Arc;
+use test_log::test;
+
+struct ConcatMerge;
+
+impl MergeOperator for ConcatMerge {
+    fn name(&self) -> &'static str {
+        "ConcatMerge"
+    }
+
+    fn full_merge(
+        &self,
+        _key: &UserKey,
+        existing_value: Option<&UserValue>,
+        operands: &[UserValue],
+    ) -> MergeResult {
+        let mut value = existing_value.map(|v| v.to_vec()).unwrap_or_default();
+
+        for operand in operands {
+            value.extend_from_slice(operand);
+        }
+
+        MergeResult::Success(value.into())
+    }
+}
+
+fn keys(tree: &impl AbstractTree, seqno: SeqNo) -> lsm_tree::Result<Vec<String>> {
+    tree.iter(seqno, None)
+        .map(|guard| {
+            let key = guard.key()?;
+            Ok(String::from_utf8(key.to_vec()).unwrap())
+        })
+        .collect()
+}
+
+fn keys_rev(tree: &impl AbstractTree, seqno: SeqNo) -> lsm_tree::Result<Vec<String>> {
+    tree.iter(seqno, None)
+        .rev()
+        .map(|guard| {
+            let key = guard.key()?;
+            Ok(String::from_utf8(key.to_vec()).unwrap())
+        })
+        .collect()
+}
+
+fn insert_abcde(tree: &impl AbstractTree) {
+    for (seqno, key) in ["a", "b", "c", "d", "e"].into_iter().enumerate() {
+        tree.insert(key, "v", seqno as SeqNo);
+    }
+}
+
+#[test]
+fn tree_range_tombstone_memtable() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    insert_abcde(&tree);
+    tree.remove_range("b".."d", 5);
+
+    assert!(tree.contains_key("a", SeqNo::MAX)?);
+    assert!(!tree.contains_key("b", SeqNo::MAX)?);
+    assert!(!tree.contains_key("c", SeqNo::MAX)?);
+    assert!(tree.contains_key("d", SeqNo::MAX)?);
+
+    assert_eq!(vec!["a", "d", "e"], keys(&tree, SeqNo::MAX)?);
+    assert_eq!(vec!["e", "d", "a"], keys_rev(&tree, SeqNo::MAX)?);
+    assert_eq!(3, tree.len(SeqNo::MAX, None)?);
+
+    // NOTE: Snapshot from before the range deletion
+    assert!(tree.contains_key("b", 5)?);
+    assert_eq!(vec!["a", "b", "c", "d", "e"], keys(&tree, 5)?);
+
+    // NOTE: Newer versions are not deleted
+    tree.insert("c", "v2", 6);
+    assert_eq!(vec!["a", "c", "d", "e"], keys(&tree, SeqNo::MAX)?);
+    assert_eq!(b"v2", &*tree.get("c", SeqNo::MAX)?.unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn tree_range_tombstone_memtable_insert_after_read() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    insert_abcde(&tree);
+
+    tree.remove_range("a".."b", 5);
+    assert_eq!(vec!["b", "c", "d", "e"], keys(&tree, SeqNo::MAX)?);
+
+    // NOTE: The memtable's fragmented range tombstones need to be rebuilt
+    tree.remove_range("d".."f", 6);
+    assert_eq!(vec!["b", "c"], keys(&tree, SeqNo::MAX)?);
+    assert_eq!(vec!["b", "c", "d", "e"], keys(&tree, 6)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_range_tombstone_empty_range() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    insert_abcde(&tree);
+
+    let (size, _) = tree.remove_range("d".."b", 5);
+    assert_eq!(0, size);
+
+    assert_eq!(5, tree.len(SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_range_tombstone_flush() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    {
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        insert_abcde(&tree);
+        tree.flush_active_memtable(0)?;
+
+        tree.remove_range("b".."d", 5);
+        tree.flush_active_memtable(0)?;
+        assert_eq!(2, tree.table_count());
+
+        assert_eq!(vec!["a", "d", "e"], keys(&tree, SeqNo::MAX)?);
+        assert_eq!(vec!["e", "d", "a"], keys_rev(&tree, SeqNo::MAX)?);
+        assert!(!tree.contains_key("b", SeqNo::MAX)?);
+        assert!(tree.contains_key("b", 5)?);
+    }
+
+    // NOTE: Range tombstones are persisted in tables
+    {
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        assert_eq!(vec!["a", "d", "e"], keys(&tree, SeqNo::MAX)?);
+        assert!(!tree.contains_key("c", SeqNo::MAX)?);
+        assert!(tree.contains_key("c", 5)?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tree_range_tombstone_flush_same_memtable() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    insert_abcde(&tree);
+    tree.remove_range("b".."d", 5);
+    tree.flush_active_memtable(SeqNo::MAX)?;
+
+    assert_eq!(vec!["a", "d", "e"], keys(&tree, SeqNo::MAX)?);
+
+    // NOTE: Covered items are dropped during flush if no snapshot can see them
+    let item_count: u64 = tree
+        .current_version()
+        .iter_tables()
+        .map(|t| t.metadata.item_count)
+        .sum();
+    assert_eq!(3, item_count);
+
+    Ok(())
+}
+
+#[test]
+fn tree_range_tombstone_major_compact() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    insert_abcde(&tree);
+    tree.flush_active_memtable(0)?;
+
+    tree.remove_range("a".."z", 5);
+    tree.flush_active_memtable(0)?;
+    assert_eq!(2, tree.table_count());
+
+    // NOTE: Snapshots may still need the covered items
+    tree.major_compact(u64::MAX, 0)?;
+    assert_eq!(1, tree.table_count());
+    assert_eq!(0, tree.len(SeqNo::MAX, None)?);
+    assert_eq!(5, tree.len(5, None)?);
+
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+    assert_eq!(0, tree.table_count());
+    assert_eq!(0, tree.len(SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_range_tombstone_merge() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .merge_operator(Some(Arc::new(ConcatMerge)))
+    .open()?;
+
+    tree.insert("a", "base", 0);
+    tree.merge("a", "1", 1);
+    tree.remove_range("a".."b", 2);
+    tree.merge("a", "2", 3);
+
+    // NOTE: The range tombstone ends the merge chain
+    assert_eq!(b"2", &*tree.get("a", SeqNo::MAX)?.unwrap());
+    assert_eq!(b"base1", &*tree.get("a", 2)?.unwrap());
+
+    let items = tree
+        .iter(SeqNo::MAX, None)
+        .map(|guard| guard.into_inner())
+        .collect::<lsm_tree::Result<Vec<_>>>()?;
+    assert_eq!(1, items.len());
+    assert_eq!(b"2", &*items[0].1);
+
+    tree.flush_active_memtable(0)?;
+    assert_eq!(b"2", &*tree.get("a", SeqNo::MAX)?.unwrap());
+    assert_eq!(b"base1", &*tree.get("a", 2)?.unwrap());
+
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+    assert_eq!(b"2", &*tree.get("a", SeqNo::MAX)?.unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn blob_tree_range_tombstone() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    .open()?;
+
+    insert_abcde(&tree);
+    tree.flush_active_memtable(0)?;
+
+    tree.remove_range("b".."d", 5);
+    assert_eq!(vec!["a", "d", "e"], keys(&tree, SeqNo::MAX)?);
+    assert!(tree.get("b", SeqNo::MAX)?.is_none());
+
+    tree.flush_active_memtable(0)?;
+    assert_eq!(vec!["a", "d", "e"], keys(&tree, SeqNo::MAX)?);
+    assert!(tree.get("b", SeqNo::MAX)?.is_none());
+    assert!(tree.get("b", 5)?.is_some());
+
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+    assert_eq!(vec!["a", "d", "e"], keys(&tree, SeqNo::MAX)?);
+    assert!(tree.get("b", 5)?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn tree_range_tombstone_compact_multiple_tables() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .data_block_compression_policy(CompressionPolicy::disabled())
+    .open()?;
+
+    for x in 0..100u64 {
+        tree.insert(x.to_be_bytes(), "a".repeat(1_000), x);
+    }
+    tree.flush_active_memtable(0)?;
+
+    tree.remove_range(20u64.to_be_bytes()..80u64.to_be_bytes(), 100);
+    tree.flush_active_memtable(0)?;
+
+    tree.major_compact(10_000, 0)?;
+    assert!(tree.table_count() > 1);
+
+    // NOTE: Range tombstones are split at table boundaries, so the tables stay disjoint
+    let version = tree.current_version();
+    for level in version.iter_levels() {
+        for run in level.iter() {
+            let ranges = run
+                .iter()
+                .map(|t| &t.metadata.key_range)
+                .collect::<Vec<_>>();
+            assert!(lsm_tree::KeyRange::is_disjoint(&ranges));
+        }
+    }
+
+    assert_eq!(40, tree.len(SeqNo::MAX, None)?);
+    assert_eq!(100, tree.len(100, None)?);
+
+    tree.major_compact(10_000, SeqNo::MAX)?;
+    assert_eq!(40, tree.len(SeqNo::MAX, None)?);
+
+    Ok(())
+}
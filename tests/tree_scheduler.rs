@@ -0,0 +1,157 @@
+use lsm_tree::{
+    compaction::Leveled, get_tmp_folder, AbstractTree, Config, KvSeparationOptions, Scheduler,
+    SchedulerOptions, SeqNo, SequenceNumberCounter,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use test_log::test;
+
+const ITEM_COUNT: u64 = 10_000;
+
+fn wait_until(mut f: impl FnMut() -> bool) -> bool {
+    let start = Instant::now();
+
+    while start.elapsed() < Duration::from_secs(30) {
+        if f() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    false
+}
+
+#[test]
+fn tree_scheduler_flush() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = Config::new(&folder, seqno.clone(), SequenceNumberCounter::default()).open()?;
+
+    let scheduler = Scheduler::start(
+        &tree,
+        SchedulerOptions::default()
+            .max_memtable_size(32_000)
+            .poll_interval(Duration::from_millis(1))
+            // NOTE: Keep all tables in L0
+            .compaction_strategy(Arc::new(Leveled::default().with_l0_threshold(u8::MAX))),
+    )?;
+
+    for x in 0..ITEM_COUNT {
+        tree.insert(x.to_be_bytes(), "abc", seqno.next());
+    }
+
+    assert!(wait_until(
+        || tree.active_memtable().size() < 32_000 && tree.sealed_memtable_count() == 0
+    ));
+    assert!(tree.table_count() > 1);
+
+    scheduler.shutdown();
+
+    assert_eq!(ITEM_COUNT as usize, tree.len(SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_scheduler_compaction() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = Config::new(&folder, seqno.clone(), SequenceNumberCounter::default()).open()?;
+
+    let scheduler = Scheduler::start(
+        &tree,
+        SchedulerOptions::default()
+            .compaction_threads(2)
+            .poll_interval(Duration::from_millis(1))
+            .compaction_strategy(Arc::new(Leveled::default().with_l0_threshold(2))),
+    )?;
+
+    for batch in 0..10 {
+        for x in 0..1_000u64 {
+            tree.insert(x.to_be_bytes(), batch.to_string(), seqno.next());
+        }
+        tree.rotate_memtable();
+        scheduler.notify();
+    }
+
+    assert!(wait_until(
+        || tree.sealed_memtable_count() == 0 && tree.l0_run_count() < 2
+    ));
+
+    drop(scheduler);
+
+    assert_eq!(1_000, tree.len(SeqNo::MAX, None)?);
+    assert_eq!(
+        Some("9".as_bytes().into()),
+        tree.get(0u64.to_be_bytes(), SeqNo::MAX)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tree_scheduler_gc_watermark() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+    let watermark = SequenceNumberCounter::default();
+
+    let tree = Config::new(&folder, seqno.clone(), SequenceNumberCounter::default()).open()?;
+
+    let scheduler = Scheduler::start(
+        &tree,
+        SchedulerOptions::default()
+            .poll_interval(Duration::from_millis(1))
+            .gc_watermark(watermark.clone()),
+    )?;
+
+    tree.insert("a", "old", seqno.next());
+    tree.insert("a", "new", seqno.next());
+
+    watermark.set(seqno.get());
+    tree.rotate_memtable();
+
+    assert!(wait_until(|| tree.sealed_memtable_count() == 0));
+    scheduler.shutdown();
+
+    // NOTE: The old version was evicted during the flush
+    assert_eq!(1, tree.approximate_len());
+    assert_eq!(Some("new".as_bytes().into()), tree.get("a", SeqNo::MAX)?);
+
+    Ok(())
+}
+
+#[test]
+fn blob_tree_scheduler() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = Config::new(&folder, seqno.clone(), SequenceNumberCounter::default())
+        .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+        .open()?;
+
+    let scheduler = Scheduler::start(
+        &tree,
+        SchedulerOptions::default()
+            .max_memtable_size(32_000)
+            .poll_interval(Duration::from_millis(1)),
+    )?;
+
+    for x in 0..ITEM_COUNT {
+        tree.insert(x.to_be_bytes(), "abc", seqno.next());
+    }
+
+    assert!(wait_until(
+        || tree.active_memtable().size() < 32_000 && tree.sealed_memtable_count() == 0
+    ));
+    assert!(tree.blob_file_count() > 0);
+
+    scheduler.shutdown();
+
+    assert_eq!(ITEM_COUNT as usize, tree.len(SeqNo::MAX, None)?);
+
+    Ok(())
+}
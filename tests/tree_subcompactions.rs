@@ -0,0 +1,169 @@
+mod common;
+
+use common::{assert_model, Model};
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, AnyTree, Config, KvSeparationOptions, SeqNo,
+    SequenceNumberCounter,
+};
+use test_log::test;
+
+const ITEM_COUNT: usize = 1_000;
+
+fn populate(tree: &AnyTree) -> lsm_tree::Result<Model> {
+    let mut model = Model::new();
+    let mut seqno = 0;
+
+    for table_idx in 0..8u64 {
+        for i in 0..ITEM_COUNT as u64 {
+            // NOTE: Every table covers a shifted part of the key space,
+            // so there are overlapping tables and versions of the same key
+            let key = (table_idx * 100 + i).to_be_bytes().to_vec();
+
+            if i % 7 == 0 {
+                tree.remove(&key, seqno);
+                model.remove(&key);
+            } else {
+                let value = format!("{table_idx}-{i}").repeat(5).into_bytes();
+                tree.insert(&key, &value, seqno);
+                model.insert(key, value);
+            }
+
+            seqno += 1;
+        }
+
+        tree.flush_active_memtable(0)?;
+    }
+
+    Ok(model)
+}
+
+#[test]
+fn tree_subcompactions_major_compaction() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .max_subcompactions(4)
+    .open()?;
+
+    let model = populate(&tree)?;
+    assert_eq!(Some(8), tree.level_table_count(0));
+
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+
+    // NOTE: Every subcompaction writes its own table
+    assert_eq!(Some(4), tree.level_table_count(6));
+    assert_eq!(4, tree.table_count());
+
+    // NOTE: Tombstones are evicted, so the tables only contain live data
+    assert_eq!(model.len(), tree.approximate_len());
+    assert_eq!(0, tree.tombstone_count());
+
+    assert_model(&tree, &model)?;
+
+    // NOTE: Output tables need to be disjoint
+    let version = tree.current_version();
+    let last_level = version.level(6).expect("last level should exist");
+    assert!(last_level.is_disjoint());
+
+    Ok(())
+}
+
+#[test]
+fn tree_subcompactions_recover() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let model = {
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .max_subcompactions(3)
+        .open()?;
+
+        let model = populate(&tree)?;
+        tree.major_compact(u64::MAX, SeqNo::MAX)?;
+        assert_eq!(3, tree.table_count());
+
+        model
+    };
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    assert_eq!(3, tree.table_count());
+    assert_model(&tree, &model)?;
+
+    Ok(())
+}
+
+#[test]
+fn tree_subcompactions_blob() -> lsm_tree::Result<()> {
+    let mut stale_blob_bytes = vec![];
+
+    for max_subcompactions in [1, 4] {
+        let folder = get_tmp_folder();
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .with_kv_separation(Some(
+            KvSeparationOptions::default().separation_threshold(16),
+        ))
+        .max_subcompactions(max_subcompactions)
+        .open()?;
+
+        let model = populate(&tree)?;
+
+        tree.major_compact(u64::MAX, SeqNo::MAX)?;
+
+        assert_eq!(Some(max_subcompactions), tree.level_table_count(6));
+        assert_model(&tree, &model)?;
+
+        stale_blob_bytes.push(tree.stale_blob_bytes());
+    }
+
+    // NOTE: Fragmentation of all subcompactions is tracked
+    assert!(stale_blob_bytes.iter().all(|&x| x > 0));
+    assert_eq!(stale_blob_bytes.first(), stale_blob_bytes.last());
+
+    Ok(())
+}
+
+#[test]
+fn tree_subcompactions_range_tombstone_not_split() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .max_subcompactions(4)
+    .open()?;
+
+    let mut model = populate(&tree)?;
+
+    let start = 150u64.to_be_bytes();
+    let end = 250u64.to_be_bytes();
+    tree.remove_range(start.to_vec()..end.to_vec(), 8_000);
+    tree.flush_active_memtable(0)?;
+    model.retain(|key, _| key.as_slice() < start.as_slice() || key.as_slice() >= end.as_slice());
+
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+
+    assert_eq!(1, tree.table_count());
+    assert_model(&tree, &model)?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use fs_extra::dir::CopyOptions;
+use lsm_tree::{get_tmp_folder, AbstractTree, Config, SeqNo, SequenceNumberCounter};
+use test_log::test;
+
+// NOTE: The fixture was written by a version that did not
+// store the compressed data size in the table metadata
+#[test]
+fn tree_load_v3_without_compressed_size() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    fs_extra::dir::copy(
+        "test_fixture/v3_tree",
+        folder.path(),
+        &CopyOptions::new().content_only(true),
+    )
+    .expect("should copy fixture");
+
+    let tree = Config::new(
+        folder.path(),
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    assert_eq!(100, tree.len(SeqNo::MAX, None)?);
+    assert_eq!(Some(b"value-42".into()), tree.get("0042", SeqNo::MAX)?,);
+
+    let version = tree.current_version();
+    let table = version.iter_tables().next().expect("table should exist");
+    assert_eq!(None, table.compression_ratio());
+    assert_eq!(None, tree.level_compression_ratio(0));
+
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, AnyTree, Config, CorruptedFile, KvSeparationOptions,
+    SequenceNumberCounter,
+};
+use std::path::Path;
+use test_log::test;
+
+fn open_tree(folder: &Path, kv_separation: bool) -> lsm_tree::Result<AnyTree> {
+    Config::new(
+        folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(
+        kv_separation.then(|| KvSeparationOptions::default().separation_threshold(1)),
+    )
+    .open()
+}
+
+fn write_table(tree: &AnyTree, seqno: u64) -> lsm_tree::Result<()> {
+    for key in ('a'..='z').map(|c| c.to_string()) {
+        tree.insert(key, nanoid::nanoid!().repeat(10), seqno);
+    }
+    tree.flush_active_memtable(0)?;
+    Ok(())
+}
+
+fn corrupt(path: &Path, offset: u64) -> lsm_tree::Result<()> {
+    use std::io::{Read, Seek, Write};
+
+    let mut f = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)?;
+
+    let mut byte = [0];
+    f.seek(std::io::SeekFrom::Start(offset))?;
+    f.read_exact(&mut byte)?;
+
+    f.seek(std::io::SeekFrom::Start(offset))?;
+    f.write_all(&[!byte[0]])?;
+    f.sync_all()?;
+
+    Ok(())
+}
+
+#[test]
+fn tree_verify_ok() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let tree = open_tree(folder.path(), false)?;
+
+    write_table(&tree, 0)?;
+    write_table(&tree, 1)?;
+    tree.remove_range("a".."c", 2);
+    tree.flush_active_memtable(0)?;
+
+    let report = tree.verify();
+    assert!(report.is_ok(), "{report:?}");
+    assert_eq!(3, report.table_count);
+    assert_eq!(0, report.blob_file_count);
+
+    Ok(())
+}
+
+#[test]
+fn tree_verify_blob_ok() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let tree = open_tree(folder.path(), true)?;
+
+    write_table(&tree, 0)?;
+    write_table(&tree, 1)?;
+
+    let report = tree.verify();
+    assert!(report.is_ok(), "{report:?}");
+    assert_eq!(2, report.table_count);
+    assert_eq!(2, report.blob_file_count);
+
+    Ok(())
+}
+
+#[test]
+fn tree_verify_table_corruption() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let tree = open_tree(folder.path(), false)?;
+
+    write_table(&tree, 0)?;
+    write_table(&tree, 1)?;
+
+    let version = tree.current_version();
+    let mut tables = version.iter_tables();
+    let corrupted_table = tables.next().unwrap();
+    let intact_table = tables.next().unwrap();
+
+    // NOTE: Corrupt the data of the first data block
+    corrupt(&corrupted_table.path, 100)?;
+
+    let report = tree.verify();
+    assert!(!report.is_ok());
+    assert_eq!(2, report.table_count);
+
+    // NOTE: Both the full file checksum and the block checksum do not match
+    assert_eq!(2, report.corruptions.len());
+
+    for corruption in &report.corruptions {
+        assert_eq!(CorruptedFile::Table(corrupted_table.id()), corruption.file);
+        assert_eq!(*corrupted_table.path, corruption.path);
+        assert!(matches!(
+            corruption.error,
+            lsm_tree::Error::ChecksumMismatch { .. },
+        ));
+    }
+
+    assert!(report.corruptions.iter().any(|c| c.offset.is_none()));
+    assert!(report.corruptions.iter().any(|c| c.offset == Some(0)));
+
+    assert!(report
+        .corruptions
+        .iter()
+        .all(|c| c.file != CorruptedFile::Table(intact_table.id())));
+
+    Ok(())
+}
+
+#[test]
+fn tree_verify_table_header_corruption() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let tree = open_tree(folder.path(), false)?;
+
+    write_table(&tree, 0)?;
+
+    let version = tree.current_version();
+    let table = version.iter_tables().next().unwrap();
+
+    // NOTE: Corrupt the magic bytes of the first block header
+    corrupt(&table.path, 0)?;
+
+    let report = tree.verify();
+    assert!(!report.is_ok());
+    assert_eq!(2, report.corruptions.len());
+
+    assert!(
+        report
+            .corruptions
+            .iter()
+            .any(|c| c.offset == Some(0)
+                && matches!(c.error, lsm_tree::Error::InvalidHeader("Block")))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tree_verify_blob_file_corruption() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let tree = open_tree(folder.path(), true)?;
+
+    write_table(&tree, 0)?;
+    write_table(&tree, 1)?;
+
+    let version = tree.current_version();
+    let mut blob_files = version.blob_files.iter();
+    let corrupted_blob_file = blob_files.next().unwrap();
+    let intact_blob_file = blob_files.next().unwrap();
+
+    // NOTE: Corrupt the value of the first blob
+    corrupt(corrupted_blob_file.path(), 50)?;
+
+    let report = tree.verify();
+    assert!(!report.is_ok());
+    assert_eq!(2, report.blob_file_count);
+
+    // NOTE: Both the full file checksum and the blob checksum do not match
+    assert_eq!(2, report.corruptions.len());
+
+    for corruption in &report.corruptions {
+        assert_eq!(
+            CorruptedFile::BlobFile(corrupted_blob_file.id()),
+            corruption.file,
+        );
+        assert!(matches!(
+            corruption.error,
+            lsm_tree::Error::ChecksumMismatch { .. },
+        ));
+    }
+
+    assert!(report.corruptions.iter().any(|c| c.offset.is_none()));
+    assert!(report.corruptions.iter().any(|c| c.offset == Some(0)));
+
+    assert!(report
+        .corruptions
+        .iter()
+        .all(|c| c.file != CorruptedFile::BlobFile(intact_blob_file.id())));
+
+    Ok(())
+}
+
+#[test]
+fn tree_verify_multiple_corruptions() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+    let tree = open_tree(folder.path(), true)?;
+
+    write_table(&tree, 0)?;
+
+    let version = tree.current_version();
+    let table = version.iter_tables().next().unwrap();
+    let blob_file = version.blob_files.iter().next().unwrap();
+
+    corrupt(&table.path, 100)?;
+    corrupt(blob_file.path(), 50)?;
+
+    let report = tree.verify();
+    assert!(!report.is_ok());
+
+    assert!(report
+        .corruptions
+        .iter()
+        .any(|c| c.file == CorruptedFile::Table(table.id())));
+
+    assert!(report
+        .corruptions
+        .iter()
+        .any(|c| c.file == CorruptedFile::BlobFile(blob_file.id())));
+
+    Ok(())
+}
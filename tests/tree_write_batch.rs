@@ -0,0 +1,262 @@
+use lsm_tree::{
+    get_tmp_folder, AbstractTree, Config, KvSeparationOptions, MergeOperator, MergeResult, SeqNo,
+    SequenceNumberCounter, UserKey, UserValue, WriteBatch,
+};
+use std::{collections::HashMap, sync::Arc};
+use test_log::test;
+
+struct CounterMerge;
+
+impl MergeOperator for CounterMerge {
+    fn name(&self) -> &'static str {
+        "CounterMerge"
+    }
+
+    fn full_merge(
+        &self,
+        _key: &UserKey,
+        existing_value: Option<&UserValue>,
+        operands: &[UserValue],
+    ) -> MergeResult {
+        let mut sum = existing_value.map(|v| counter(v)).unwrap_or_default();
+
+        for operand in operands {
+            sum += counter(operand);
+        }
+
+        MergeResult::Success(sum.to_be_bytes().into())
+    }
+
+    fn partial_merge(
+        &self,
+        _key: &UserKey,
+        left: &UserValue,
+        right: &UserValue,
+    ) -> Option<UserValue> {
+        Some((counter(left) + counter(right)).to_be_bytes().into())
+    }
+}
+
+fn counter(v: &[u8]) -> u64 {
+    u64::from_be_bytes(v.try_into().unwrap())
+}
+
+#[test]
+fn tree_write_batch_single_seqno() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    tree.insert("x", "old", 0);
+    tree.insert("y", "old", 0);
+
+    let mut batch = WriteBatch::new();
+    batch.insert("a", "1");
+    batch.insert("b", "2");
+    batch.remove("x");
+    batch.remove_range("y".."z");
+    batch.merge("c", "3");
+    assert_eq!(5, batch.len());
+
+    tree.apply_batch(batch, 1)?;
+
+    // NOTE: Snapshot before the batch sees none of its writes
+    assert_eq!(None, tree.get("a", 1)?);
+    assert_eq!(None, tree.get("b", 1)?);
+    assert!(tree.contains_key("x", 1)?);
+    assert!(tree.contains_key("y", 1)?);
+
+    // ...snapshot after the batch sees all of them
+    assert_eq!(Some("1".as_bytes().into()), tree.get("a", 2)?);
+    assert_eq!(Some("2".as_bytes().into()), tree.get("b", 2)?);
+    assert!(!tree.contains_key("x", 2)?);
+    assert!(!tree.contains_key("y", 2)?);
+    assert!(tree.contains_key("c", 2)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_write_batch_size_accounting() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    let (item_size, _) = tree.insert("a", "abc", 0);
+
+    let mut batch = WriteBatch::new();
+    for _ in 0..100 {
+        batch.insert("b", "abc");
+    }
+    let (batch_size, memtable_size) = tree.apply_batch(batch, 1)?;
+
+    // NOTE: Only the last write of a key is applied
+    assert_eq!(item_size, batch_size);
+    assert_eq!(2 * item_size, memtable_size);
+    assert_eq!(memtable_size, tree.active_memtable().size());
+    assert_eq!(2, tree.active_memtable().len());
+
+    assert_eq!((0, memtable_size), tree.apply_batch(WriteBatch::new(), 2)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_write_batch_not_split_by_rotation() -> lsm_tree::Result<()> {
+    const BATCH_SIZE: usize = 50;
+
+    let folder = get_tmp_folder();
+    let seqno = SequenceNumberCounter::default();
+
+    let tree = Config::new(&folder, seqno.clone(), SequenceNumberCounter::default()).open()?;
+
+    let rotated = std::thread::scope(|s| {
+        let writer = s.spawn(|| {
+            for _ in 0..200 {
+                let batch_seqno = seqno.next();
+
+                let mut batch = WriteBatch::with_capacity(BATCH_SIZE);
+                for k in 0..BATCH_SIZE {
+                    batch.insert(format!("{batch_seqno}:{k}"), "abc");
+                }
+                tree.apply_batch(batch, batch_seqno).unwrap();
+            }
+        });
+
+        let mut rotated = vec![];
+        while !writer.is_finished() {
+            rotated.extend(tree.rotate_memtable());
+        }
+        rotated.extend(tree.rotate_memtable());
+        rotated
+    });
+
+    let mut total = 0;
+
+    for memtable in rotated {
+        let mut counts = HashMap::<SeqNo, usize>::new();
+        for item in memtable.iter() {
+            *counts.entry(item.key.seqno).or_default() += 1;
+        }
+        assert!(counts.values().all(|&count| count == BATCH_SIZE));
+        total += counts.len();
+    }
+
+    assert_eq!(200, total);
+
+    Ok(())
+}
+
+#[test]
+fn blob_tree_write_batch() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    .open()?;
+
+    let mut batch = WriteBatch::new();
+    batch.insert("a", "abc");
+    batch.insert("b", "def");
+    batch.remove("c");
+    tree.apply_batch(batch, 0)?;
+
+    tree.flush_active_memtable(0)?;
+
+    assert_eq!(Some("abc".as_bytes().into()), tree.get("a", 1)?);
+    assert_eq!(Some("def".as_bytes().into()), tree.get("b", 1)?);
+    assert_eq!(2, tree.len(1, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_write_batch_insert_then_remove_range() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    tree.insert("b", "old", 0);
+
+    let mut batch = WriteBatch::new();
+    batch.insert("a", "1");
+    batch.insert("b", "1");
+    batch.insert("c", "1");
+    batch.remove_range("b".."d");
+    batch.insert("c", "2");
+
+    tree.apply_batch(batch, 1)?;
+
+    // NOTE: The range deletion removes the writes that were added to the batch before it...
+    assert_eq!(Some("1".as_bytes().into()), tree.get("a", 2)?);
+    assert!(!tree.contains_key("b", 2)?);
+
+    // ...but not the ones that were added after it
+    assert_eq!(Some("2".as_bytes().into()), tree.get("c", 2)?);
+
+    tree.flush_active_memtable(0)?;
+
+    assert_eq!(Some("1".as_bytes().into()), tree.get("a", 2)?);
+    assert!(!tree.contains_key("b", 2)?);
+    assert_eq!(Some("2".as_bytes().into()), tree.get("c", 2)?);
+    assert_eq!(2, tree.len(2, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_write_batch_merge() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .merge_operator(Some(Arc::new(CounterMerge)))
+    .open()?;
+
+    tree.insert("a", 10u64.to_be_bytes(), 0);
+    tree.insert("b", 10u64.to_be_bytes(), 0);
+
+    let mut batch = WriteBatch::new();
+    batch.merge("a", 1u64.to_be_bytes());
+    batch.merge("a", 2u64.to_be_bytes());
+    batch.insert("b", 5u64.to_be_bytes());
+    batch.merge("b", 1u64.to_be_bytes());
+    batch.merge("b", 1u64.to_be_bytes());
+
+    tree.apply_batch(batch, 1)?;
+
+    // NOTE: Operands without a base value in the batch are combined, and merged on top of the tree's value...
+    assert_eq!(13, counter(&tree.get("a", 2)?.unwrap()));
+
+    // ...while operands on top of a value in the batch are merged with it
+    assert_eq!(7, counter(&tree.get("b", 2)?.unwrap()));
+
+    tree.flush_active_memtable(0)?;
+
+    assert_eq!(13, counter(&tree.get("a", 2)?.unwrap()));
+    assert_eq!(7, counter(&tree.get("b", 2)?.unwrap()));
+
+    Ok(())
+}
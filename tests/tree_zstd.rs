@@ -0,0 +1,228 @@
+#![cfg(feature = "zstd")]
+
+use lsm_tree::{
+    config::{BlockSizePolicy, CompressionPolicy},
+    get_tmp_folder, AbstractTree, CompressionType, Config, Guard, KvSeparationOptions, SeqNo,
+    SequenceNumberCounter,
+};
+use test_log::test;
+
+const ITEM_COUNT: usize = 1_000;
+
+#[test]
+fn tree_zstd_compression() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    {
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .data_block_compression_policy(CompressionPolicy::new([
+            CompressionType::None,
+            CompressionType::Zstd(1),
+            CompressionType::Zstd(19),
+        ]))
+        .index_block_compression_policy(CompressionPolicy::all(CompressionType::Zstd(3)))
+        .open()?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            let value = x.to_string().repeat(10);
+            tree.insert(x.to_be_bytes(), value, x);
+        }
+        tree.flush_active_memtable(0)?;
+        tree.major_compact(u64::MAX, SeqNo::MAX)?;
+
+        assert_eq!(ITEM_COUNT, tree.len(SeqNo::MAX, None)?);
+    }
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .open()?;
+
+    assert_eq!(ITEM_COUNT, tree.len(SeqNo::MAX, None)?);
+
+    for x in 0..ITEM_COUNT as u64 {
+        let value = tree
+            .get(x.to_be_bytes(), SeqNo::MAX)?
+            .expect("should exist");
+        assert_eq!(x.to_string().repeat(10).as_bytes(), &*value);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tree_zstd_blob_compression() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .with_kv_separation(Some(
+        KvSeparationOptions::default()
+            .compression(CompressionType::Zstd(3))
+            .separation_threshold(1),
+    ))
+    .open()?;
+
+    let big_value = b"abc".repeat(1_000);
+
+    tree.insert("a", &big_value, 0);
+    tree.insert("b", b"smol", 0);
+    tree.flush_active_memtable(0)?;
+    assert_eq!(1, tree.blob_file_count());
+
+    // NOTE: The blob file is smaller than the raw values
+    for blob_file in tree.current_version().blob_files.iter() {
+        assert!(std::fs::metadata(blob_file.path())?.len() < big_value.len() as u64);
+    }
+
+    assert_eq!(Some(big_value.into()), tree.get("a", SeqNo::MAX)?);
+    assert_eq!(Some(b"smol".into()), tree.get("b", SeqNo::MAX)?);
+
+    tree.major_compact(u64::MAX, SeqNo::MAX)?;
+
+    assert_eq!(Some(b"smol".into()), tree.get("b", SeqNo::MAX)?);
+
+    Ok(())
+}
+
+fn write_compressible_items(tree: &lsm_tree::AnyTree) -> lsm_tree::Result<()> {
+    for x in 0..ITEM_COUNT as u64 * 20 {
+        let key = format!("user:{x:0>10}");
+        let value = format!("{{\"id\":{x},\"name\":\"user{x}\",\"mail\":\"user{x}@example.com\"}}");
+        tree.insert(key, value, x);
+    }
+    tree.flush_active_memtable(0)?;
+    Ok(())
+}
+
+#[test]
+fn tree_zstd_compression_dictionary() -> lsm_tree::Result<()> {
+    let mut disk_space = vec![];
+
+    for dictionary_size in [0, 8_192] {
+        let folder = get_tmp_folder();
+
+        {
+            let tree = Config::new(
+                &folder,
+                SequenceNumberCounter::default(),
+                SequenceNumberCounter::default(),
+            )
+            .data_block_compression_policy(CompressionPolicy::all(CompressionType::Zstd(3)))
+            .data_block_size_policy(BlockSizePolicy::all(1_024))
+            .compression_dictionary_size_policy(BlockSizePolicy::all(dictionary_size))
+            .open()?;
+
+            write_compressible_items(&tree)?;
+            tree.major_compact(u64::MAX, SeqNo::MAX)?;
+
+            if dictionary_size > 0 {
+                // NOTE: Includes the prepared decompression dictionary
+                assert!(tree.pinned_compression_dictionary_size() > dictionary_size as usize);
+            } else {
+                assert_eq!(0, tree.pinned_compression_dictionary_size());
+            }
+
+            let report = tree.verify();
+            assert!(report.is_ok(), "{report:?}");
+
+            disk_space.push(tree.disk_space());
+        }
+
+        let tree = Config::new(
+            &folder,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        assert_eq!(ITEM_COUNT * 20, tree.len(SeqNo::MAX, None)?);
+
+        let value = tree
+            .get("user:0000000042", SeqNo::MAX)?
+            .expect("should exist");
+        assert_eq!(
+            b"{\"id\":42,\"name\":\"user42\",\"mail\":\"user42@example.com\"}",
+            &*value,
+        );
+
+        for (idx, guard) in tree.iter(SeqNo::MAX, None).rev().take(10).enumerate() {
+            let key = guard.key()?;
+            assert_eq!(
+                format!("user:{:0>10}", ITEM_COUNT * 20 - 1 - idx).as_bytes(),
+                &*key
+            );
+        }
+    }
+
+    // NOTE: Small data blocks compress better with a dictionary
+    assert!(disk_space[1] < disk_space[0]);
+
+    Ok(())
+}
+
+#[test]
+fn tree_zstd_compression_dictionary_target_size() -> lsm_tree::Result<()> {
+    const TARGET_SIZE: u64 = 64_000;
+
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .data_block_compression_policy(CompressionPolicy::all(CompressionType::Zstd(3)))
+    .data_block_size_policy(BlockSizePolicy::all(1_024))
+    .compression_dictionary_size_policy(BlockSizePolicy::all(8_192))
+    .open()?;
+
+    write_compressible_items(&tree)?;
+    tree.major_compact(TARGET_SIZE, SeqNo::MAX)?;
+
+    // NOTE: Data blocks that are held back for dictionary training
+    // still count towards the table target size
+    assert!(tree.table_count() > 1);
+
+    for table in tree.current_version().iter_tables() {
+        assert!(std::fs::metadata(&*table.path)?.len() < TARGET_SIZE * 2);
+    }
+
+    assert_eq!(ITEM_COUNT * 20, tree.len(SeqNo::MAX, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn tree_zstd_compression_dictionary_too_few_samples() -> lsm_tree::Result<()> {
+    let folder = get_tmp_folder();
+
+    let tree = Config::new(
+        &folder,
+        SequenceNumberCounter::default(),
+        SequenceNumberCounter::default(),
+    )
+    .data_block_compression_policy(CompressionPolicy::all(CompressionType::Zstd(3)))
+    .compression_dictionary_size_policy(BlockSizePolicy::all(8_192))
+    .open()?;
+
+    tree.insert("a", "a", 0);
+    tree.insert("b", "b", 1);
+    tree.flush_active_memtable(0)?;
+
+    // NOTE: Falls back to compressing without dictionary
+    assert_eq!(1, tree.table_count());
+    assert_eq!(0, tree.pinned_compression_dictionary_size());
+    assert_eq!(Some(b"b".into()), tree.get("b", SeqNo::MAX)?);
+
+    Ok(())
+}